@@ -1,25 +1,137 @@
-//! A crate to custom derive `Position` for any type that has a field named `vector`
+//! A crate to custom derive the `geometry` traits
+//!
+//! - `Position` reads the position from `self.vector.position`, another field can be given with
+//!   `#[position(field = "position")]` (a dotted path like `"body.position"` works too)
+//! - `Collide` takes the radius from `#[collide(radius = 6.0)]`, or from a method with
+//!   `#[collide(radius = Self::radius_method)]`
 
 extern crate proc_macro;
 
+use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn;
+use syn::{Data, DeriveInput, Expr, Fields, Ident, Lit, LitStr};
 
 use crate::proc_macro::TokenStream;
 
-#[proc_macro_derive(Position)]
+#[proc_macro_derive(Position, attributes(position))]
 pub fn position_derive(input: TokenStream) -> TokenStream {
-    let ast: syn::DeriveInput = syn::parse(input).unwrap();
+    let ast = syn::parse_macro_input!(input as DeriveInput);
+    expand_position(&ast)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(Collide, attributes(collide))]
+pub fn collide_derive(input: TokenStream) -> TokenStream {
+    let ast = syn::parse_macro_input!(input as DeriveInput);
+    expand_collide(&ast)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_position(ast: &DeriveInput) -> syn::Result<TokenStream2> {
+    let mut field = None;
+    for attr in ast.attrs.iter().filter(|attr| attr.path().is_ident("position")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("field") {
+                field = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `field = \"...\"`"))
+            }
+        })?;
+    }
+
+    let path = match &field {
+        Some(field) => parse_field_path(field)?,
+        None => vec![
+            Ident::new("vector", ast.ident.span()),
+            Ident::new("position", ast.ident.span()),
+        ],
+    };
+
+    // The first segment of the path has to be a named field of the struct
+    let fields = named_fields(ast, "Position")?;
+    if !fields.iter().any(|name| **name == path[0]) {
+        let message = format!("no field named `{}`, use `#[position(field = \"...\")]` to select the position field", path[0]);
+        return Err(match &field {
+            Some(field) => syn::Error::new_spanned(field, message),
+            None => syn::Error::new_spanned(&ast.ident, message),
+        });
+    }
 
     let name = &ast.ident;
-    let gen = quote! {
-        impl Position for #name {
-            fn x(&self) -> f32 { self.vector.position.x }
-            fn y(&self) -> f32 { self.vector.position.y }
-            fn x_mut(&mut self) -> &mut f32 { &mut self.vector.position.x }
-            fn y_mut(&mut self) -> &mut f32 { &mut self.vector.position.y }
-            fn position(&self) -> Point { self.vector.position }
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::geometry::Position for #name #ty_generics #where_clause {
+            fn x(&self) -> ::geometry::Scalar { self.#(#path).*.x }
+            fn y(&self) -> ::geometry::Scalar { self.#(#path).*.y }
+            fn x_mut(&mut self) -> &mut ::geometry::Scalar { &mut self.#(#path).*.x }
+            fn y_mut(&mut self) -> &mut ::geometry::Scalar { &mut self.#(#path).*.y }
+            fn position(&self) -> ::geometry::WorldPoint { self.#(#path).* }
+        }
+    })
+}
+
+fn expand_collide(ast: &DeriveInput) -> syn::Result<TokenStream2> {
+    let mut radius = None;
+    for attr in ast.attrs.iter().filter(|attr| attr.path().is_ident("collide")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("radius") {
+                radius = Some(meta.value()?.parse::<Expr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `radius = ...`"))
+            }
+        })?;
+    }
+
+    let radius = match radius {
+        // A number is used as a constant radius
+        Some(Expr::Lit(expr)) => match &expr.lit {
+            Lit::Float(_) | Lit::Int(_) => quote! { ::geometry::scalar!(#expr) },
+            _ => return Err(syn::Error::new_spanned(expr, "expected a number or a method path")),
+        },
+        // A path is called as a method taking `&self`
+        Some(Expr::Path(expr)) => quote! { #expr(self) },
+        Some(expr) => return Err(syn::Error::new_spanned(expr, "expected a number or a method path")),
+        None => {
+            return Err(syn::Error::new_spanned(
+                &ast.ident,
+                "missing `#[collide(radius = ...)]` attribute",
+            ))
         }
     };
-    gen.into()
+
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::geometry::Collide for #name #ty_generics #where_clause {
+            fn radius(&self) -> ::geometry::Scalar { #radius }
+        }
+    })
+}
+
+/// Splits a dotted field path like `"vector.position"` into its segments
+fn parse_field_path(field: &LitStr) -> syn::Result<Vec<Ident>> {
+    field
+        .value()
+        .split('.')
+        .map(|segment| {
+            syn::parse_str::<Ident>(segment)
+                .map(|ident| Ident::new(&ident.to_string(), field.span()))
+                .map_err(|_| syn::Error::new_spanned(field, format!("`{}` is not a valid field path", field.value())))
+        })
+        .collect()
+}
+
+/// Returns the names of the fields of a struct with named fields
+fn named_fields<'a>(ast: &'a DeriveInput, derive: &str) -> syn::Result<Vec<&'a Ident>> {
+    match &ast.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => Ok(fields.named.iter().filter_map(|f| f.ident.as_ref()).collect()),
+            _ => Err(syn::Error::new_spanned(&ast.ident, format!("`{}` can only be derived for structs with named fields", derive))),
+        },
+        _ => Err(syn::Error::new_spanned(&ast.ident, format!("`{}` can only be derived for structs", derive))),
+    }
 }
@@ -0,0 +1,21 @@
+use geometry::Vector;
+use geometry_derive::{Collide, Position};
+
+#[derive(Position, Collide)]
+struct MissingRadius {
+    vector: Vector,
+}
+
+#[derive(Position, Collide)]
+#[collide(radius = "6.0")]
+struct StringRadius {
+    vector: Vector,
+}
+
+#[derive(Position, Collide)]
+#[collide(radius = 1.0 + 2.0)]
+struct ExpressionRadius {
+    vector: Vector,
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+use geometry::WorldPoint;
+use geometry_derive::Position;
+
+#[derive(Position)]
+#[position(name = "position")]
+struct UnknownKey {
+    position: WorldPoint,
+}
+
+#[derive(Position)]
+#[position(field = "position..x")]
+struct InvalidPath {
+    position: WorldPoint,
+}
+
+#[derive(Position)]
+enum NotAStruct {
+    Point(WorldPoint),
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use geometry::WorldPoint;
+use geometry_derive::Position;
+
+#[derive(Position)]
+struct NoVector {
+    position: WorldPoint,
+}
+
+#[derive(Position)]
+#[position(field = "location")]
+struct WrongField {
+    position: WorldPoint,
+}
+
+fn main() {}
@@ -0,0 +1,40 @@
+use geometry::{Collide, Vector, WorldPoint};
+use geometry_derive::{Collide, Position};
+
+#[derive(Position, Collide)]
+#[collide(radius = 6.0)]
+struct Constant {
+    vector: Vector,
+}
+
+#[derive(Position, Collide)]
+#[collide(radius = 2)]
+struct Integer {
+    vector: Vector,
+}
+
+#[derive(Position, Collide)]
+#[position(field = "position")]
+#[collide(radius = Self::current_radius)]
+struct Method {
+    position: WorldPoint,
+    ttl: f32,
+}
+
+impl Method {
+    fn current_radius(&self) -> f32 {
+        self.ttl * 2.0
+    }
+}
+
+fn main() {
+    let constant = Constant { vector: Vector::new(WorldPoint::new(0.0, 0.0), 0.0) };
+    let integer = Integer { vector: Vector::new(WorldPoint::new(7.0, 0.0), 0.0) };
+    let method = Method { position: WorldPoint::new(0.0, 10.0), ttl: 1.5 };
+
+    assert_eq!(constant.radius(), 6.0);
+    assert_eq!(integer.radius(), 2.0);
+    assert_eq!(method.radius(), 3.0);
+    assert!(constant.collides_with(&integer));
+    assert!(!constant.collides_with(&method));
+}
@@ -0,0 +1,37 @@
+use geometry::{Position, Vector, WorldPoint};
+use geometry_derive::Position;
+
+#[derive(Position)]
+struct Default {
+    vector: Vector,
+}
+
+#[derive(Position)]
+#[position(field = "position")]
+struct Field {
+    position: WorldPoint,
+}
+
+struct Body {
+    position: WorldPoint,
+}
+
+#[derive(Position)]
+#[position(field = "body.position")]
+struct Nested<T> {
+    body: Body,
+    _data: T,
+}
+
+fn main() {
+    let mut default = Default { vector: Vector::new(WorldPoint::new(1.0, 2.0), 0.0) };
+    *default.x_mut() += 1.0;
+    assert_eq!(default.position(), WorldPoint::new(2.0, 2.0));
+
+    let mut field = Field { position: WorldPoint::new(3.0, 4.0) };
+    *field.y_mut() = 5.0;
+    assert_eq!(field.position(), WorldPoint::new(3.0, 5.0));
+
+    let nested = Nested { body: Body { position: WorldPoint::new(6.0, 7.0) }, _data: () };
+    assert_eq!((nested.x(), nested.y()), (6.0, 7.0));
+}
@@ -0,0 +1,72 @@
+//! Update time of a horde of enemies led to the player by a flow field
+//!
+//! A frame at 60 FPS lasts about 16ms, an update of the field and of the horde should stay well
+//! under it
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use geometry::{scalar, FlowField, Neighbourhood, Scalar};
+
+const SIZE: usize = 128;
+const ENEMIES: usize = 5000;
+
+/// A grid with walls every few tiles and slower tiles in between, like water and forests
+fn cost((column, row): (usize, usize)) -> Option<Scalar> {
+    if column >= SIZE || row >= SIZE || (column % 8 == 4 && row % 16 != 0) {
+        None
+    } else if (column + row) % 5 == 0 {
+        Some(scalar!(2))
+    } else {
+        Some(scalar!(1))
+    }
+}
+
+/// Spreads the enemies over the open tiles of the grid
+fn enemies() -> Vec<(usize, usize)> {
+    (0..)
+        .map(|index| ((index * 7919) % SIZE, (index * 104729) % SIZE))
+        .filter(|&tile| cost(tile).is_some())
+        .take(ENEMIES)
+        .collect()
+}
+
+fn flow_field(c: &mut Criterion) {
+    let enemies = enemies();
+
+    c.bench_function("flow field search 128x128", |b| {
+        let mut goal = 0;
+        let mut field = FlowField::new(SIZE, SIZE, Neighbourhood::Eight);
+        b.iter(|| {
+            // The goal jumps too far for the field to be repaired
+            goal = (goal + 1) % 2;
+            field.update(black_box((64 + goal * 2, 64)), usize::MAX, cost)
+        })
+    });
+
+    c.bench_function("flow field repair 128x128", |b| {
+        let mut goal = 0;
+        let mut field = FlowField::new(SIZE, SIZE, Neighbourhood::Eight);
+        b.iter(|| {
+            // The player changes tile on every update
+            goal = (goal + 1) % 2;
+            field.update(black_box((64 + goal, 64)), usize::MAX, cost)
+        })
+    });
+
+    c.bench_function("flow field steering 5000 enemies", |b| {
+        let mut field = FlowField::new(SIZE, SIZE, Neighbourhood::Eight);
+        field.update((64, 64), usize::MAX, cost);
+        b.iter(|| enemies.iter().filter_map(|&tile| field.next_tile(black_box(tile))).count())
+    });
+
+    c.bench_function("flow field update with 5000 enemies", |b| {
+        let mut goal = 0;
+        let mut field = FlowField::new(SIZE, SIZE, Neighbourhood::Eight);
+        b.iter(|| {
+            goal = (goal + 1) % 2;
+            field.update(black_box((64 + goal, 64)), usize::MAX, cost);
+            enemies.iter().filter_map(|&tile| field.next_tile(tile)).count()
+        })
+    });
+}
+
+criterion_group!(benches, flow_field);
+criterion_main!(benches);
@@ -0,0 +1,192 @@
+//! A* shortest paths over a grid of tiles with movement costs
+//!
+//! Tiles are `(column, row)` indices. The cost of a tile is given by a closure, `None` meaning the
+//! tile can't be entered, which is also how the bounds of the grid are told apart.
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use super::{scalar, IsoProjection, Real, Scalar, WorldPoint};
+
+/// The tiles a path can move to from a tile
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// Up, down, left and right
+    Four,
+    /// The four sides and the four corners, without cutting through blocked corners
+    Eight,
+}
+
+impl Neighbourhood {
+    /// Returns the tiles next to the given one with the length of the step to them
+    pub(crate) fn neighbours<F>(
+        self,
+        (column, row): (usize, usize),
+        diagonal: Scalar,
+        cost: &F,
+    ) -> impl Iterator<Item = ((usize, usize), Scalar)> + '_
+    where
+        F: Fn((usize, usize)) -> Option<Scalar>,
+    {
+        let offset = move |dx: i32, dy: i32| {
+            let column = column.checked_add_signed(dx as isize)?;
+            let row = row.checked_add_signed(dy as isize)?;
+            Some((column, row))
+        };
+        let open = move |dx: i32, dy: i32| offset(dx, dy).is_some_and(|tile| cost(tile).is_some());
+        let corners = if self == Neighbourhood::Eight { 4 } else { 0 };
+
+        let sides = [(1, 0), (0, 1), (-1, 0), (0, -1)]
+            .into_iter()
+            .filter_map(move |(dx, dy)| offset(dx, dy).map(|tile| (tile, Scalar::ONE)));
+        // Diagonal steps need both sides of the corner to be open
+        let corners = [(1, 1), (-1, 1), (-1, -1), (1, -1)]
+            .into_iter()
+            .take(corners)
+            .filter(move |&(dx, dy)| open(dx, 0) && open(0, dy))
+            .filter_map(move |(dx, dy)| offset(dx, dy).map(|tile| (tile, diagonal)));
+        sides.chain(corners)
+    }
+}
+
+/// A path between two tiles, both included
+#[derive(Clone, Debug, PartialEq)]
+pub struct TilePath {
+    pub tiles: Vec<(usize, usize)>,
+    /// The total cost of the steps of the path
+    pub cost: Scalar,
+}
+
+impl TilePath {
+    /// Returns the world positions of the centers of the tiles of the path
+    pub fn waypoints(&self, projection: &IsoProjection) -> Vec<WorldPoint> {
+        self.tiles.iter().map(|&(column, row)| projection.tile_center(column as i32, row as i32)).collect()
+    }
+}
+
+/// An `AStar` finds the cheapest paths between tiles
+///
+/// Entering a tile costs its cost, times the square root of two for diagonal steps
+#[derive(Clone, Copy, Debug)]
+pub struct AStar {
+    pub neighbourhood: Neighbourhood,
+    /// The lowest cost of a tile, which keeps the estimates of the remaining cost below the real
+    /// one so that the paths found are the cheapest
+    pub min_cost: Scalar,
+    /// The number of tiles explored before giving up on a search
+    pub max_explored: usize,
+}
+
+/// The cheapest known cost to each tile, and the tile it is reached from
+type Reached = HashMap<(usize, usize), (Scalar, Option<(usize, usize)>)>;
+
+/// A tile waiting to be explored, the most promising first
+#[derive(Clone, Debug)]
+pub(crate) struct Open {
+    pub(crate) tile: (usize, usize),
+    /// The cost so far plus the estimate of the remaining cost
+    pub(crate) estimate: Scalar,
+    /// The order the tile was opened in, to break ties the same way every time
+    pub(crate) order: usize,
+}
+
+impl PartialEq for Open {
+    fn eq(&self, other: &Open) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Open {}
+
+impl PartialOrd for Open {
+    fn partial_cmp(&self, other: &Open) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Open {
+    // Reversed, `BinaryHeap` being a max-heap
+    fn cmp(&self, other: &Open) -> Ordering {
+        other.estimate.total_cmp(&self.estimate).then_with(|| other.order.cmp(&self.order))
+    }
+}
+
+impl AStar {
+    /// Returns a new `AStar` with the given neighbourhood, for tiles costing at least 1
+    pub fn new(neighbourhood: Neighbourhood) -> AStar {
+        AStar {
+            neighbourhood,
+            min_cost: Scalar::ONE,
+            max_explored: usize::MAX,
+        }
+    }
+
+    /// Returns the cheapest path from `start` to `goal`, or `None` if there is none or if the
+    /// search explored too many tiles
+    ///
+    /// The start tile doesn't need to be enterable, so that bodies standing on a blocked tile can
+    /// still find their way out
+    pub fn find_path<F>(&self, start: (usize, usize), goal: (usize, usize), cost: F) -> Option<TilePath>
+    where
+        F: Fn((usize, usize)) -> Option<Scalar>,
+    {
+        let diagonal = scalar!(2).sqrt();
+        let mut open = BinaryHeap::new();
+        let mut reached = Reached::new();
+        let mut order = 0;
+        let mut explored = 0;
+
+        reached.insert(start, (Scalar::ZERO, None));
+        open.push(Open { tile: start, estimate: self.estimate(start, goal, diagonal), order });
+
+        while let Some(Open { tile, estimate, .. }) = open.pop() {
+            let so_far = reached[&tile].0;
+            // Skip the outdated entries of tiles reached again more cheaply
+            if estimate > so_far + self.estimate(tile, goal, diagonal) {
+                continue;
+            }
+            if tile == goal {
+                return Some(Self::rebuild(&reached, goal));
+            }
+            explored += 1;
+            if explored > self.max_explored {
+                return None;
+            }
+
+            for (next, length) in self.neighbourhood.neighbours(tile, diagonal, &cost) {
+                let Some(tile_cost) = cost(next) else { continue };
+                let next_cost = so_far + tile_cost * length;
+                if reached.get(&next).is_none_or(|&(known, _)| next_cost < known) {
+                    reached.insert(next, (next_cost, Some(tile)));
+                    order += 1;
+                    open.push(Open { tile: next, estimate: next_cost + self.estimate(next, goal, diagonal), order });
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns the lowest possible cost from a tile to the goal
+    fn estimate(&self, from: (usize, usize), goal: (usize, usize), diagonal: Scalar) -> Scalar {
+        let dx = Scalar::from_f32(from.0.abs_diff(goal.0) as f32);
+        let dy = Scalar::from_f32(from.1.abs_diff(goal.1) as f32);
+        let steps = match self.neighbourhood {
+            Neighbourhood::Four => dx + dy,
+            // Octile distance: diagonal steps first, then straight ones
+            Neighbourhood::Eight => {
+                let (short, long) = if dx < dy { (dx, dy) } else { (dy, dx) };
+                short * diagonal + (long - short)
+            }
+        };
+        steps * self.min_cost
+    }
+
+    /// Follows the tiles back from the goal to the start
+    fn rebuild(reached: &Reached, goal: (usize, usize)) -> TilePath {
+        let mut tiles = vec![goal];
+        while let Some(previous) = reached[tiles.last().unwrap()].1 {
+            tiles.push(previous);
+        }
+        tiles.reverse();
+        TilePath { tiles, cost: reached[&goal].0 }
+    }
+}
@@ -0,0 +1,49 @@
+//! Conversions between the screen space types and the `mint` and `ggez` types
+//!
+//! Only screen space types are converted, since that's the space `ggez` draws in
+use super::{Point, Real, Scalar, ScreenSpace, Size};
+#[cfg(feature = "ggez")]
+use super::Rect;
+
+impl From<Point<ScreenSpace>> for mint::Point2<f32> {
+    fn from(point: Point<ScreenSpace>) -> Self {
+        mint::Point2 { x: point.x.to_f32(), y: point.y.to_f32() }
+    }
+}
+
+impl From<mint::Point2<f32>> for Point<ScreenSpace> {
+    fn from(point: mint::Point2<f32>) -> Self {
+        Point::new(Scalar::from_f32(point.x), Scalar::from_f32(point.y))
+    }
+}
+
+impl From<Size<ScreenSpace>> for mint::Vector2<f32> {
+    fn from(size: Size<ScreenSpace>) -> Self {
+        mint::Vector2 { x: size.width.to_f32(), y: size.height.to_f32() }
+    }
+}
+
+impl From<mint::Vector2<f32>> for Size<ScreenSpace> {
+    fn from(size: mint::Vector2<f32>) -> Self {
+        Size::new(Scalar::from_f32(size.x), Scalar::from_f32(size.y))
+    }
+}
+
+#[cfg(feature = "ggez")]
+impl From<Rect<ScreenSpace>> for ggez::graphics::Rect {
+    fn from(rect: Rect<ScreenSpace>) -> Self {
+        ggez::graphics::Rect::new(rect.x.to_f32(), rect.y.to_f32(), rect.width.to_f32(), rect.height.to_f32())
+    }
+}
+
+#[cfg(feature = "ggez")]
+impl From<ggez::graphics::Rect> for Rect<ScreenSpace> {
+    fn from(rect: ggez::graphics::Rect) -> Self {
+        Rect::new(
+            Scalar::from_f32(rect.x),
+            Scalar::from_f32(rect.y),
+            Scalar::from_f32(rect.w),
+            Scalar::from_f32(rect.h),
+        )
+    }
+}
@@ -0,0 +1,198 @@
+//! Flow fields, the distances of every tile of a grid to a single goal
+//!
+//! Any number of bodies can follow a flow field to the goal by stepping to the neighbouring tile
+//! closest to it. When the goal steps to a neighbouring tile, the field is repaired rather than
+//! searched again: the cheapest path from any tile to the new goal costs at most the one through
+//! the previous goal, so the previous distances plus the cost of that step bound the new ones, and
+//! only the tiles getting closer to the new goal are explored. The costs work as in `AStar`, so
+//! that the distance of a tile is the cost of the cheapest path from it.
+use std::collections::BinaryHeap;
+
+use super::astar::Open;
+use super::{scalar, Neighbourhood, Real, Scalar};
+
+/// A `FlowField` leads every tile of a grid to a goal tile
+///
+/// The search for a new goal is spread over several updates, the field of the previous goal
+/// being kept until the new one is complete
+#[derive(Clone, Debug)]
+pub struct FlowField {
+    pub neighbourhood: Neighbourhood,
+    width: usize,
+    height: usize,
+    /// The goal of the complete field
+    goal: Option<(usize, usize)>,
+    /// The distance to the goal of each tile, row by row, `None` for the tiles that can't be
+    /// entered or that can't reach the goal
+    distances: Vec<Option<Scalar>>,
+    /// The cost of each tile when the field was searched, the field can only be repaired while
+    /// they stay the same
+    costs: Vec<Option<Scalar>>,
+    /// The search in progress towards a new goal
+    search: Option<Search>,
+}
+
+/// A search of the distances to a goal, from the goal outwards
+#[derive(Clone, Debug)]
+struct Search {
+    goal: (usize, usize),
+    /// The cost of each tile, read once when the search starts
+    costs: Vec<Option<Scalar>>,
+    distances: Vec<Option<Scalar>>,
+    open: BinaryHeap<Open>,
+    order: usize,
+}
+
+impl FlowField {
+    /// Returns an empty `FlowField` over a grid of the given size
+    pub fn new(width: usize, height: usize, neighbourhood: Neighbourhood) -> FlowField {
+        FlowField {
+            neighbourhood,
+            width,
+            height,
+            goal: None,
+            distances: vec![None; width * height],
+            costs: vec![],
+            search: None,
+        }
+    }
+
+    /// Returns the goal of the field, `None` before the first search is complete
+    pub fn goal(&self) -> Option<(usize, usize)> {
+        self.goal
+    }
+
+    /// Leads the field to the given goal, exploring at most `budget` tiles
+    ///
+    /// A new search starts when the goal changes, reading the cost of every tile, and goes on in
+    /// the next calls until it is complete. It repairs the field when the goal steps to a
+    /// neighbour of the previous one and the costs haven't changed, and starts from scratch
+    /// otherwise. Returns true if the field leads to the given goal.
+    pub fn update<F>(&mut self, goal: (usize, usize), budget: usize, cost: F) -> bool
+    where
+        F: Fn((usize, usize)) -> Option<Scalar>,
+    {
+        if self.goal == Some(goal) && self.search.is_none() {
+            return true;
+        }
+        if self.search.as_ref().is_none_or(|search| search.goal != goal) {
+            self.search = Some(self.start(goal, cost));
+        }
+
+        let diagonal = scalar!(2).sqrt();
+        let width = self.width;
+        let search = self.search.as_mut().expect("The search was just started");
+        for _ in 0..budget {
+            let Some(Open { tile, estimate, .. }) = search.open.pop() else {
+                let search = self.search.take().expect("The search is in progress");
+                self.goal = Some(search.goal);
+                self.distances = search.distances;
+                self.costs = search.costs;
+                return true;
+            };
+            // Skip the outdated entries of tiles reached again more cheaply
+            if search.distances[tile.1 * width + tile.0].is_some_and(|known| estimate > known) {
+                continue;
+            }
+            // Stepping onto this tile from a neighbour costs the tile's cost, the goal being
+            // reachable even if it can't be entered
+            let cost = |tile| tile_cost(&search.costs, width, tile);
+            let step = cost(tile).unwrap_or(Scalar::ONE);
+            for (next, length) in self.neighbourhood.neighbours(tile, diagonal, &cost) {
+                if cost(next).is_none() {
+                    continue;
+                }
+                let distance = estimate + step * length;
+                let known = &mut search.distances[next.1 * width + next.0];
+                if known.is_none_or(|known| distance < known) {
+                    *known = Some(distance);
+                    search.order += 1;
+                    search.open.push(Open { tile: next, estimate: distance, order: search.order });
+                }
+            }
+        }
+        false
+    }
+
+    /// Returns the cost of the cheapest path from the given tile to the goal
+    pub fn distance(&self, (column, row): (usize, usize)) -> Option<Scalar> {
+        if column >= self.width || row >= self.height {
+            return None;
+        }
+        self.distances[row * self.width + column]
+    }
+
+    /// Returns the neighbouring tile to step to from the given one, the closest to the goal
+    ///
+    /// Returns `None` at the goal, and on the tiles that can't reach it
+    pub fn next_tile(&self, (column, row): (usize, usize)) -> Option<(usize, usize)> {
+        let offset = |dx: i32, dy: i32| {
+            let column = column.checked_add_signed(dx as isize)?;
+            let row = row.checked_add_signed(dy as isize)?;
+            self.distance((column, row)).map(|distance| ((column, row), distance))
+        };
+
+        let corners = if self.neighbourhood == Neighbourhood::Eight { 4 } else { 0 };
+        let sides = [(1, 0), (0, 1), (-1, 0), (0, -1)].into_iter().filter_map(|(dx, dy)| offset(dx, dy));
+        // Diagonal steps need both sides of the corner to be open
+        let corners = [(1, 1), (-1, 1), (-1, -1), (1, -1)]
+            .into_iter()
+            .take(corners)
+            .filter(|&(dx, dy)| offset(dx, 0).is_some() && offset(0, dy).is_some())
+            .filter_map(|(dx, dy)| offset(dx, dy));
+
+        let (next, distance) = sides.chain(corners).min_by(|(_, a), (_, b)| a.total_cmp(b))?;
+        // The tiles that can't be entered have no distance but still lead out of them
+        match self.distance((column, row)) {
+            Some(current) if current <= distance => None,
+            _ => Some(next),
+        }
+    }
+
+    /// Starts a search from the given goal, from the distances to the previous goal when it is
+    /// one step away
+    fn start<F>(&self, goal: (usize, usize), cost: F) -> Search
+    where
+        F: Fn((usize, usize)) -> Option<Scalar>,
+    {
+        let costs: Vec<_> = (0..self.height)
+            .flat_map(|row| (0..self.width).map(move |column| (column, row)))
+            .map(cost)
+            .collect();
+        let step = self.goal.filter(|_| costs == self.costs).and_then(|previous| self.step(previous, goal, &costs));
+        let mut distances = match step {
+            // Going through the previous goal, every tile is at most this far from the new one:
+            // the search only explores the tiles it brings closer
+            Some(step) => self.distances.iter().map(|distance| distance.map(|distance| distance + step)).collect(),
+            None => vec![None; self.width * self.height],
+        };
+        let mut open = BinaryHeap::new();
+        if goal.0 < self.width && goal.1 < self.height {
+            distances[goal.1 * self.width + goal.0] = Some(Scalar::ZERO);
+            open.push(Open { tile: goal, estimate: Scalar::ZERO, order: 0 });
+        }
+        Search { goal, costs, distances, open, order: 0 }
+    }
+
+    /// Returns the cost of stepping from the previous goal onto the new one, `None` if it takes
+    /// more than a step or if the previous goal can't be entered, its distances then going
+    /// through a tile no path can use
+    fn step(&self, previous: (usize, usize), goal: (usize, usize), costs: &[Option<Scalar>]) -> Option<Scalar> {
+        let cost = |tile| tile_cost(costs, self.width, tile);
+        cost(previous)?;
+        let (_, length) = self
+            .neighbourhood
+            .neighbours(previous, scalar!(2).sqrt(), &cost)
+            .find(|&(tile, _)| tile == goal && goal.0 < self.width && goal.1 < self.height)?;
+        Some(cost(goal).unwrap_or(Scalar::ONE) * length)
+    }
+}
+
+/// Returns the cost of the given tile, `None` outside of the grid
+fn tile_cost(costs: &[Option<Scalar>], width: usize, (column, row): (usize, usize)) -> Option<Scalar> {
+    if column < width {
+        *costs.get(row * width + column)?
+    } else {
+        None
+    }
+}
@@ -0,0 +1,88 @@
+//! Traversal of the cells of a grid crossed by a segment
+//!
+//! Cells are one unit wide, so tile space positions can be used directly, the cell `(x, y)`
+//! covering the positions from `(x, y)` to `(x + 1, y + 1)`
+use super::{Real, Scalar, TilePoint};
+
+/// An iterator over the cells crossed by a segment, in the order they are crossed
+///
+/// Uses the Amanatides & Woo voxel traversal: every cell touched by the segment is returned once,
+/// starting with the cell of the start point and ending with the cell of the end point
+pub struct GridTraversal {
+    cell: (i32, i32),
+    step: (i32, i32),
+    /// The fraction of the segment at which the next vertical and horizontal cell borders are hit
+    t_max: (Scalar, Scalar),
+    /// The fraction of the segment between two vertical and two horizontal cell borders
+    t_delta: (Scalar, Scalar),
+    /// The number of cells left to return
+    remaining: u32,
+    end: (i32, i32),
+}
+
+impl GridTraversal {
+    /// Returns the traversal of the segment going from `from` to `to`
+    pub fn new(from: TilePoint, to: TilePoint) -> GridTraversal {
+        let cell = (from.x.floor_i32(), from.y.floor_i32());
+        let end = (to.x.floor_i32(), to.y.floor_i32());
+
+        let axis = |from: Scalar, to: Scalar| {
+            let delta = to - from;
+            if delta > Scalar::ZERO {
+                (1, (from.floor() + Scalar::ONE - from) / delta, Scalar::ONE / delta)
+            } else if delta < Scalar::ZERO {
+                (-1, (from - from.floor()) / -delta, Scalar::ONE / -delta)
+            } else {
+                (0, Scalar::MAX, Scalar::MAX)
+            }
+        };
+        let (step_x, t_max_x, t_delta_x) = axis(from.x, to.x);
+        let (step_y, t_max_y, t_delta_y) = axis(from.y, to.y);
+
+        GridTraversal {
+            cell,
+            step: (step_x, step_y),
+            t_max: (t_max_x, t_max_y),
+            t_delta: (t_delta_x, t_delta_y),
+            remaining: (end.0 - cell.0).unsigned_abs() + (end.1 - cell.1).unsigned_abs() + 1,
+            end,
+        }
+    }
+}
+
+impl Iterator for GridTraversal {
+    type Item = (i32, i32);
+
+    fn next(&mut self) -> Option<(i32, i32)> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let current = self.cell;
+
+        if self.remaining > 0 {
+            // Cross the closest border, making sure rounding errors never step past the end cell
+            let step_x = if self.cell.0 == self.end.0 {
+                false
+            } else if self.cell.1 == self.end.1 {
+                true
+            } else {
+                self.t_max.0 < self.t_max.1
+            };
+
+            if step_x {
+                self.cell.0 += self.step.0;
+                self.t_max.0 += self.t_delta.0;
+            } else {
+                self.cell.1 += self.step.1;
+                self.t_max.1 += self.t_delta.1;
+            }
+        }
+
+        Some(current)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining as usize, Some(self.remaining as usize))
+    }
+}
@@ -0,0 +1,97 @@
+//! Isometric projection between the tile, world and screen coordinate spaces
+//!
+//! - tile space: columns and rows of the map grid, the integer part being the tile index
+//! - world space: unscaled isometric pixels, relative to the map origin
+//! - screen space: scaled pixels, relative to a screen origin (usually the map position)
+use super::{scalar, Point, Real, Scalar, ScreenPoint, TilePoint, WorldPoint, WorldSize};
+
+/// An `IsoProjection` converts positions between the tile, world and screen spaces
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IsoProjection {
+    /// The size of a tile diamond, in world pixels
+    pub tile_size: WorldSize,
+    /// The scale applied when going from world to screen space
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialize::scalar"))]
+    pub scale: Scalar,
+    /// The world position of the top corner of the tile (0, 0)
+    pub origin: WorldPoint,
+}
+
+impl IsoProjection {
+    /// Returns a new `IsoProjection` with the top corner of the tile (0, 0) at the world origin
+    pub fn new(tile_size: WorldSize, scale: Scalar) -> IsoProjection {
+        IsoProjection {
+            tile_size,
+            scale,
+            origin: Point::default(),
+        }
+    }
+
+    /// Returns a new `IsoProjection` with the center of a `map_size` x `map_size` grid at the
+    /// world origin
+    pub fn centered(tile_size: WorldSize, scale: Scalar, map_size: usize) -> IsoProjection {
+        IsoProjection {
+            tile_size,
+            scale,
+            origin: Point::new(Scalar::ZERO, -Scalar::from_f32(map_size as f32) * tile_size.height / scalar!(2)),
+        }
+    }
+
+    /// Converts a position in tile space to world space
+    pub fn tile_to_world(&self, tile: TilePoint) -> WorldPoint {
+        let half_width = self.tile_size.width / scalar!(2);
+        let half_height = self.tile_size.height / scalar!(2);
+        Point::new(
+            self.origin.x + (tile.x - tile.y) * half_width,
+            self.origin.y + (tile.x + tile.y) * half_height,
+        )
+    }
+
+    /// Converts a position in world space to tile space
+    pub fn world_to_tile(&self, world: WorldPoint) -> TilePoint {
+        let x = (world.x - self.origin.x) / (self.tile_size.width / scalar!(2));
+        let y = (world.y - self.origin.y) / (self.tile_size.height / scalar!(2));
+        Point::new((y + x) / scalar!(2), (y - x) / scalar!(2))
+    }
+
+    /// Converts a position in world space to screen space
+    pub fn world_to_screen(&self, world: WorldPoint, screen_origin: ScreenPoint) -> ScreenPoint {
+        Point::new(
+            world.x * self.scale + screen_origin.x,
+            world.y * self.scale + screen_origin.y,
+        )
+    }
+
+    /// Converts a position in screen space to world space
+    pub fn screen_to_world(&self, screen: ScreenPoint, screen_origin: ScreenPoint) -> WorldPoint {
+        Point::new(
+            (screen.x - screen_origin.x) / self.scale,
+            (screen.y - screen_origin.y) / self.scale,
+        )
+    }
+
+    /// Converts a position in tile space to screen space
+    pub fn tile_to_screen(&self, tile: TilePoint, screen_origin: ScreenPoint) -> ScreenPoint {
+        self.world_to_screen(self.tile_to_world(tile), screen_origin)
+    }
+
+    /// Converts a position in screen space to tile space
+    pub fn screen_to_tile(&self, screen: ScreenPoint, screen_origin: ScreenPoint) -> TilePoint {
+        self.world_to_tile(self.screen_to_world(screen, screen_origin))
+    }
+
+    /// Returns the world position of the center of the tile at the given index
+    pub fn tile_center(&self, column: i32, row: i32) -> WorldPoint {
+        let half = scalar!(0.5);
+        self.tile_to_world(Point::new(Scalar::from_f32(column as f32) + half, Scalar::from_f32(row as f32) + half))
+    }
+
+    /// Returns the index of the tile containing the given world position
+    ///
+    /// The index may be negative or out of the map, bounds checking is up to the caller
+    pub fn tile_index(&self, world: WorldPoint) -> (i32, i32) {
+        let tile = self.world_to_tile(world);
+        (tile.x.floor_i32(), tile.y.floor_i32())
+    }
+}
@@ -1,10 +1,38 @@
+pub use self::astar::{AStar, Neighbourhood, TilePath};
+pub use self::flow::FlowField;
+pub use self::grid::GridTraversal;
+pub use self::iso::IsoProjection;
 pub use self::point::Point;
+pub use self::polygon::Polygon;
+pub use self::rect::Rect;
+pub use self::scalar::{Real, Scalar};
+pub use self::shape::{Contact, Shape};
 pub use self::size::Size;
+pub use self::spatial::SpatialGrid;
+pub use self::space::{
+    ScreenPoint, ScreenRect, ScreenSize, ScreenSpace, TilePoint, TilePolygon, TileSpace, WorldPoint, WorldPolygon,
+    WorldRect, WorldSize, WorldSpace,
+};
 pub use self::traits::{Position, Collide};
 pub use self::vector::Vector;
 
+mod astar;
+#[cfg(feature = "mint")]
+mod convert;
+mod flow;
+mod grid;
+mod iso;
 mod point;
+mod polygon;
+mod rect;
+mod scalar;
+#[cfg(feature = "serde")]
+mod serialize;
+mod shape;
 mod size;
+mod spatial;
+pub mod space;
+pub mod steering;
 mod traits;
 #[macro_use]
 mod vector;
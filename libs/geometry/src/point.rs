@@ -1,165 +1,206 @@
-use std::ops::{Add, Div, Mul, Sub};
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Add, Div, Mul, Neg, Sub};
 
 use rand::Rng;
 
-use super::Size;
+use super::{Real, Scalar, Size};
 
 /// A `Point` represents a position in space
-#[derive(Clone, Default, Copy, Debug)]
-pub struct Point {
-    pub x: f32,
-    pub y: f32,
+///
+/// The `S` marker tells which coordinate space the point lives in (see the `space` module)
+pub struct Point<S> {
+    pub x: Scalar,
+    pub y: Scalar,
+    space: PhantomData<S>,
 }
 
-impl Point {
+impl<S> Point<S> {
     /// Returns a new `Point` with the given coordinates
-    pub fn new(x: f32, y: f32) -> Point {
-        Point { x, y }
+    pub const fn new(x: Scalar, y: Scalar) -> Point<S> {
+        Point { x, y, space: PhantomData }
     }
 
     /// Returns a random `Point` within the given bounds (exclusive)
-    pub fn random<R: Rng>(rng: &mut R, bounds: Size) -> Point {
-        Point {
-            x: rng.gen_range(0.0..bounds.width),
-            y: rng.gen_range(0.0..bounds.height),
-        }
+    pub fn random<R: Rng>(rng: &mut R, bounds: Size<S>) -> Point<S> {
+        Point::new(bounds.random_x(rng), bounds.random_y(rng))
+    }
+
+    /// Reinterprets the coordinates of this point in another space, without converting them
+    ///
+    /// Prefer the conversions of `IsoProjection`, this is only meant for spaces sharing the same
+    /// units and origin
+    pub fn cast<T>(self) -> Point<T> {
+        Point::new(self.x, self.y)
     }
 
     /// Returns the squared distance from this point to the given one
-    pub fn squared_distance_to(self, target: Point) -> f32 {
+    pub fn squared_distance_to(self, target: Point<S>) -> Scalar {
         (self.x - target.x) * (self.x - target.x) + (self.y - target.y) * (self.y - target.y)
     }
 
+    /// Returns the dot product of this point and the given one, both seen as vectors
+    pub fn dot(self, other: Point<S>) -> Scalar {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// Returns the distance from the origin to this point
+    pub fn length(self) -> Scalar {
+        self.dot(self).sqrt()
+    }
+
+    /// Returns a point in the same direction with a length of 1, or the origin if the length is 0
+    pub fn normalize(self) -> Point<S> {
+        let length = self.length();
+        if length > Scalar::ZERO {
+            Point::new(self.x / length, self.y / length)
+        } else {
+            Point::default()
+        }
+    }
+
+    /// Returns the point rotated a quarter turn counter-clockwise through the origin
+    pub fn perpendicular(self) -> Point<S> {
+        Point::new(-self.y, self.x)
+    }
+
     /// Rotates the point through the origin in the given angle (radians)
-    pub fn rotate(mut self, radians: f32) -> Point {
-        let radius = (self.x * self.x + self.y * self.y).sqrt();
-        let point_angle = (self.y / self.x).atan();
-        let final_angle = point_angle + radians;
-        self.x = final_angle.cos() * radius;
-        self.y = final_angle.sin() * radius;
-        self
+    pub fn rotate(self, radians: Scalar) -> Point<S> {
+        let (sin, cos) = (radians.sin(), radians.cos());
+        Point::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
     }
 
     /// Translates the point by another point
-    pub fn translate(mut self, other: &Point) -> Point {
+    pub fn translate(mut self, other: &Point<S>) -> Point<S> {
         self.x += other.x;
         self.y += other.y;
         self
     }
 
     /// Checks if this point is contained in a circle
-    pub fn intersect_circle(self, center: &Point, radius: f32) -> bool {
-        (self.x - center.x).powi(2) + (self.y - center.y).powi(2) < radius.powi(2)
+    pub fn intersect_circle(self, center: &Point<S>, radius: Scalar) -> bool {
+        self.squared_distance_to(*center) < radius * radius
     }
 
     /// Converts to array of 2 elements
     pub fn point2(self) -> [f32; 2] {
-        [self.x, self.y]
+        [self.x.to_f32(), self.y.to_f32()]
+    }
+}
+
+// Clone, Copy, Default and Debug are implemented by hand so they don't require the space
+// marker to implement them
+
+impl<S> Clone for Point<S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for Point<S> {}
+
+impl<S> Default for Point<S> {
+    fn default() -> Self {
+        Point::new(Scalar::ZERO, Scalar::ZERO)
+    }
+}
+
+impl<S> fmt::Debug for Point<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Point")
+            .field("x", &self.x)
+            .field("y", &self.y)
+            .finish()
     }
 }
 
 /// Implements '==' for Point, as well as its inverse '!='
-impl PartialEq for Point {
+impl<S> PartialEq for Point<S> {
     fn eq(&self, _rhs: &Self) -> bool {
         (self.x == _rhs.x) && (self.y == _rhs.y)
     }
 }
 
 /// Implements the '+' operator for Point + Point
-impl Add for Point {
-    type Output = Point;
+impl<S> Add for Point<S> {
+    type Output = Point<S>;
 
-    fn add(self, _rhs: Point) -> Point {
-        Point {
-            x: self.x + _rhs.x,
-            y: self.y + _rhs.y,
-        }
+    fn add(self, _rhs: Point<S>) -> Point<S> {
+        Point::new(self.x + _rhs.x, self.y + _rhs.y)
     }
 }
 
-/// Implements the '+' operator for Point + f32
-impl Add<f32> for Point {
-    type Output = Point;
+/// Implements the '+' operator for Point + Scalar
+impl<S> Add<Scalar> for Point<S> {
+    type Output = Point<S>;
 
-    fn add(self, _rhs: f32) -> Point {
-        Point {
-            x: self.x + _rhs,
-            y: self.y + _rhs,
-        }
+    fn add(self, _rhs: Scalar) -> Point<S> {
+        Point::new(self.x + _rhs, self.y + _rhs)
+    }
+}
+
+/// Implements the unary '-' operator for -Point
+impl<S> Neg for Point<S> {
+    type Output = Point<S>;
+
+    fn neg(self) -> Point<S> {
+        Point::new(-self.x, -self.y)
     }
 }
 
 /// Implements the '-' operator for Point - Point
-impl Sub for Point {
-    type Output = Point;
+impl<S> Sub for Point<S> {
+    type Output = Point<S>;
 
-    fn sub(self, _rhs: Point) -> Point {
-        Point {
-            x: self.x - _rhs.x,
-            y: self.y - _rhs.y,
-        }
+    fn sub(self, _rhs: Point<S>) -> Point<S> {
+        Point::new(self.x - _rhs.x, self.y - _rhs.y)
     }
 }
 
-/// Implements the '-' operator for Point - f32
-impl Sub<f32> for Point {
-    type Output = Point;
+/// Implements the '-' operator for Point - Scalar
+impl<S> Sub<Scalar> for Point<S> {
+    type Output = Point<S>;
 
-    fn sub(self, _rhs: f32) -> Point {
-        Point {
-            x: self.x - _rhs,
-            y: self.y - _rhs,
-        }
+    fn sub(self, _rhs: Scalar) -> Point<S> {
+        Point::new(self.x - _rhs, self.y - _rhs)
     }
 }
 
 /// Implements the '*' operator for Point * Point
-impl Mul for Point {
-    type Output = Point;
+impl<S> Mul for Point<S> {
+    type Output = Point<S>;
 
-    fn mul(self, _rhs: Point) -> Point {
-        Point {
-            x: self.x * _rhs.x,
-            y: self.y * _rhs.y,
-        }
+    fn mul(self, _rhs: Point<S>) -> Point<S> {
+        Point::new(self.x * _rhs.x, self.y * _rhs.y)
     }
 }
 
-/// Implements the '*' operator for Point * f32
-impl Mul<f32> for Point {
-    type Output = Point;
+/// Implements the '*' operator for Point * Scalar
+impl<S> Mul<Scalar> for Point<S> {
+    type Output = Point<S>;
 
-    fn mul(self, _rhs: f32) -> Point {
-        Point {
-            x: self.x * _rhs,
-            y: self.x * _rhs,
-        }
+    fn mul(self, _rhs: Scalar) -> Point<S> {
+        Point::new(self.x * _rhs, self.y * _rhs)
     }
 }
 
 /// Implements the '/' operator for Point / Point
-impl Div for Point {
-    type Output = Point;
-
-    fn div(self, _rhs: Point) -> Point {
-        assert_ne!(_rhs.x, 0f32);
-        assert_ne!(_rhs.y, 0f32);
-        Point {
-            x: self.x / _rhs.x,
-            y: self.y / _rhs.y,
-        }
+impl<S> Div for Point<S> {
+    type Output = Point<S>;
+
+    fn div(self, _rhs: Point<S>) -> Point<S> {
+        assert_ne!(_rhs.x, Scalar::ZERO);
+        assert_ne!(_rhs.y, Scalar::ZERO);
+        Point::new(self.x / _rhs.x, self.y / _rhs.y)
     }
 }
 
-/// Implements the '/' operator for Point / f32:
-impl Div<f32> for Point {
-    type Output = Point;
+/// Implements the '/' operator for Point / Scalar:
+impl<S> Div<Scalar> for Point<S> {
+    type Output = Point<S>;
 
-    fn div(self, _rhs: f32) -> Point {
-        assert_ne!(_rhs, 0f32);
-        Point {
-            x: self.x / _rhs,
-            y: self.y / _rhs,
-        }
+    fn div(self, _rhs: Scalar) -> Point<S> {
+        assert_ne!(_rhs, Scalar::ZERO);
+        Point::new(self.x / _rhs, self.y / _rhs)
     }
 }
@@ -0,0 +1,189 @@
+use std::fmt;
+
+use rand::Rng;
+
+use super::{scalar, Point, Real, Rect, Scalar};
+
+/// A `Polygon` represents a closed area in space, bounded by its vertices
+///
+/// The `S` marker tells which coordinate space the polygon lives in (see the `space` module)
+pub struct Polygon<S> {
+    vertices: Vec<Point<S>>,
+}
+
+impl<S> Polygon<S> {
+    /// Returns a new `Polygon` with the given vertices, in order around the polygon
+    pub fn new(vertices: Vec<Point<S>>) -> Polygon<S> {
+        assert!(vertices.len() >= 3, "a polygon needs at least 3 vertices");
+        Polygon { vertices }
+    }
+
+    /// Returns the vertices of the polygon
+    pub fn vertices(&self) -> &[Point<S>] {
+        &self.vertices
+    }
+
+    /// Returns the edges of the polygon, as pairs of vertices
+    pub fn edges(&self) -> impl Iterator<Item = (Point<S>, Point<S>)> + '_ {
+        self.vertices
+            .iter()
+            .copied()
+            .zip(self.vertices.iter().copied().cycle().skip(1))
+    }
+
+    /// Returns the area of the polygon
+    pub fn area(&self) -> Scalar {
+        self.signed_area().abs()
+    }
+
+    /// Returns the smallest rectangle containing the polygon
+    pub fn bounding_rect(&self) -> Rect<S> {
+        Rect::bounding(self.vertices.iter().copied()).unwrap()
+    }
+
+    /// Returns true if the point is inside the polygon
+    ///
+    /// Works for concave polygons too, by counting the edges crossed by a ray going right
+    pub fn contains(&self, point: Point<S>) -> bool {
+        self.edges()
+            .filter(|(a, b)| (a.y > point.y) != (b.y > point.y))
+            .filter(|(a, b)| point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x))
+            .count()
+            % 2
+            == 1
+    }
+
+    /// Returns the point on the border of the polygon closest to the given one
+    pub fn closest_point(&self, point: Point<S>) -> Point<S> {
+        self.edges()
+            .map(|(a, b)| {
+                let edge = b - a;
+                let length = edge.dot(edge);
+                let t = if length > Scalar::ZERO {
+                    ((point - a).dot(edge) / length).clamp(Scalar::ZERO, Scalar::ONE)
+                } else {
+                    Scalar::ZERO
+                };
+                a + edge * t
+            })
+            .min_by(|a, b| a.squared_distance_to(point).total_cmp(&b.squared_distance_to(point)))
+            .unwrap()
+    }
+
+    /// Returns the point itself if it is inside the polygon, or the closest point on its border
+    pub fn confine(&self, point: Point<S>) -> Point<S> {
+        if self.contains(point) {
+            point
+        } else {
+            self.closest_point(point)
+        }
+    }
+
+    /// Returns a uniformly distributed random point inside the polygon
+    ///
+    /// The polygon must be convex
+    pub fn random_point<R: Rng>(&self, rng: &mut R) -> Point<S> {
+        // Split the polygon in a fan of triangles, and pick one weighted by its area
+        let origin = self.vertices[0];
+        let triangles: Vec<(Point<S>, Point<S>, Scalar)> = self.vertices[1..]
+            .windows(2)
+            .map(|pair| {
+                let (b, c) = (pair[0], pair[1]);
+                let area = ((b - origin).x * (c - origin).y - (c - origin).x * (b - origin).y).abs();
+                (b, c, area)
+            })
+            .collect();
+
+        let total: Scalar = triangles.iter().map(|&(_, _, area)| area).sum();
+        let mut pick = Scalar::from_f32(rng.gen::<f32>()) * total;
+        let &(b, c, _) = triangles
+            .iter()
+            .find(|&&(_, _, area)| {
+                pick -= area;
+                pick <= Scalar::ZERO
+            })
+            .unwrap_or(triangles.last().unwrap());
+
+        // Fold the unit square onto the triangle
+        let (mut u, mut v) = (Scalar::from_f32(rng.gen::<f32>()), Scalar::from_f32(rng.gen::<f32>()));
+        if u + v > Scalar::ONE {
+            u = Scalar::ONE - u;
+            v = Scalar::ONE - v;
+        }
+        origin + (b - origin) * u + (c - origin) * v
+    }
+
+    /// Returns the part of this polygon inside the `clip` polygon, or `None` if they don't overlap
+    ///
+    /// Uses the Sutherland-Hodgman algorithm, so `clip` must be convex
+    pub fn clip(&self, clip: &Polygon<S>) -> Option<Polygon<S>> {
+        // Edges are kept on the same side as the clip polygon's interior, whatever its winding
+        let orientation = clip.signed_area().signum();
+        let inside = |a: Point<S>, b: Point<S>, point: Point<S>| {
+            ((b - a).x * (point - a).y - (b - a).y * (point - a).x) * orientation >= Scalar::ZERO
+        };
+
+        let mut output = self.vertices.clone();
+        for (a, b) in clip.edges() {
+            let input = std::mem::take(&mut output);
+            for (index, &current) in input.iter().enumerate() {
+                let previous = input[(index + input.len() - 1) % input.len()];
+                let intersection = || {
+                    let (edge, segment) = (b - a, current - previous);
+                    let denominator = edge.x * segment.y - edge.y * segment.x;
+                    let t = (edge.x * (a.y - previous.y) - edge.y * (a.x - previous.x)) / denominator;
+                    previous + segment * t
+                };
+                match (inside(a, b, previous), inside(a, b, current)) {
+                    (true, true) => output.push(current),
+                    (true, false) => output.push(intersection()),
+                    (false, true) => {
+                        output.push(intersection());
+                        output.push(current);
+                    }
+                    (false, false) => (),
+                }
+            }
+            if output.is_empty() {
+                return None;
+            }
+        }
+
+        if output.len() >= 3 {
+            Some(Polygon::new(output))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the area of the polygon, positive if the vertices go clockwise on screen
+    fn signed_area(&self) -> Scalar {
+        self.edges().map(|(a, b)| a.x * b.y - b.x * a.y).sum::<Scalar>() / scalar!(2)
+    }
+}
+
+impl<S> From<Rect<S>> for Polygon<S> {
+    fn from(rect: Rect<S>) -> Self {
+        let (min, max) = (rect.min(), rect.max());
+        Polygon::new(vec![min, Point::new(max.x, min.y), max, Point::new(min.x, max.y)])
+    }
+}
+
+impl<S> Clone for Polygon<S> {
+    fn clone(&self) -> Self {
+        Polygon { vertices: self.vertices.clone() }
+    }
+}
+
+/// Implements '==' for Polygon, as well as its inverse '!='
+impl<S> PartialEq for Polygon<S> {
+    fn eq(&self, _rhs: &Self) -> bool {
+        self.vertices == _rhs.vertices
+    }
+}
+
+impl<S> fmt::Debug for Polygon<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Polygon").field(&self.vertices).finish()
+    }
+}
@@ -0,0 +1,161 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use super::{scalar, Point, Scalar, Size};
+
+/// A `Rect` represents an axis-aligned rectangle in space
+///
+/// The `S` marker tells which coordinate space the rectangle lives in (see the `space` module)
+pub struct Rect<S> {
+    /// The left side of the rectangle
+    pub x: Scalar,
+    /// The top side of the rectangle
+    pub y: Scalar,
+    pub width: Scalar,
+    pub height: Scalar,
+    space: PhantomData<S>,
+}
+
+impl<S> Rect<S> {
+    /// Returns a new `Rect` with the given top left corner and dimensions
+    pub const fn new(x: Scalar, y: Scalar, width: Scalar, height: Scalar) -> Rect<S> {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+            space: PhantomData,
+        }
+    }
+
+    /// Returns the `Rect` going from `min` (top left) to `max` (bottom right)
+    pub fn from_corners(min: Point<S>, max: Point<S>) -> Rect<S> {
+        Rect::new(min.x, min.y, max.x - min.x, max.y - min.y)
+    }
+
+    /// Returns the `Rect` of the given size centered on `center`
+    pub fn from_center(center: Point<S>, size: Size<S>) -> Rect<S> {
+        Rect::new(
+            center.x - size.width / scalar!(2),
+            center.y - size.height / scalar!(2),
+            size.width,
+            size.height,
+        )
+    }
+
+    /// Returns the smallest `Rect` containing all the points, or `None` if there are none
+    pub fn bounding<I: IntoIterator<Item = Point<S>>>(points: I) -> Option<Rect<S>> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let (min, max) = points.fold((first, first), |(min, max), point| {
+            (
+                Point::new(min.x.min(point.x), min.y.min(point.y)),
+                Point::new(max.x.max(point.x), max.y.max(point.y)),
+            )
+        });
+        Some(Rect::from_corners(min, max))
+    }
+
+    /// Returns the top left corner
+    pub fn min(&self) -> Point<S> {
+        Point::new(self.x, self.y)
+    }
+
+    /// Returns the bottom right corner
+    pub fn max(&self) -> Point<S> {
+        Point::new(self.x + self.width, self.y + self.height)
+    }
+
+    /// Returns the center of the rectangle
+    pub fn center(&self) -> Point<S> {
+        Point::new(self.x + self.width / scalar!(2), self.y + self.height / scalar!(2))
+    }
+
+    /// Returns the dimensions of the rectangle
+    pub fn size(&self) -> Size<S> {
+        Size::new(self.width, self.height)
+    }
+
+    /// Returns true if the point is inside the rectangle or on its border
+    pub fn contains(&self, point: Point<S>) -> bool {
+        let max = self.max();
+        point.x >= self.x && point.x <= max.x && point.y >= self.y && point.y <= max.y
+    }
+
+    /// Returns true if the two rectangles overlap (touching borders don't count)
+    pub fn intersects(&self, other: &Rect<S>) -> bool {
+        self.x < other.max().x
+            && other.x < self.max().x
+            && self.y < other.max().y
+            && other.y < self.max().y
+    }
+
+    /// Returns the area covered by both rectangles, or `None` if they don't overlap
+    pub fn intersection(&self, other: &Rect<S>) -> Option<Rect<S>> {
+        if !self.intersects(other) {
+            return None;
+        }
+        let (max, other_max) = (self.max(), other.max());
+        Some(Rect::from_corners(
+            Point::new(self.x.max(other.x), self.y.max(other.y)),
+            Point::new(max.x.min(other_max.x), max.y.min(other_max.y)),
+        ))
+    }
+
+    /// Returns the smallest rectangle containing both rectangles
+    pub fn union(&self, other: &Rect<S>) -> Rect<S> {
+        let (max, other_max) = (self.max(), other.max());
+        Rect::from_corners(
+            Point::new(self.x.min(other.x), self.y.min(other.y)),
+            Point::new(max.x.max(other_max.x), max.y.max(other_max.y)),
+        )
+    }
+
+    /// Returns the rectangle grown by `margin` on every side (shrunk if negative)
+    pub fn expand(&self, margin: Scalar) -> Rect<S> {
+        Rect::new(
+            self.x - margin,
+            self.y - margin,
+            self.width + scalar!(2) * margin,
+            self.height + scalar!(2) * margin,
+        )
+    }
+
+    /// Returns the point of the rectangle closest to the given one
+    pub fn clamp(&self, point: Point<S>) -> Point<S> {
+        let max = self.max();
+        Point::new(point.x.clamp(self.x, max.x), point.y.clamp(self.y, max.y))
+    }
+}
+
+impl<S> Clone for Rect<S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for Rect<S> {}
+
+impl<S> Default for Rect<S> {
+    fn default() -> Self {
+        Rect::from_corners(Point::default(), Point::default())
+    }
+}
+
+/// Implements '==' for Rect, as well as its inverse '!='
+impl<S> PartialEq for Rect<S> {
+    fn eq(&self, _rhs: &Self) -> bool {
+        self.x == _rhs.x && self.y == _rhs.y && self.width == _rhs.width && self.height == _rhs.height
+    }
+}
+
+impl<S> fmt::Debug for Rect<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Rect")
+            .field("x", &self.x)
+            .field("y", &self.y)
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .finish()
+    }
+}
@@ -0,0 +1,300 @@
+//! The number type used for every coordinate
+//!
+//! `Scalar` is `f32` by default. With the `fixed` feature it becomes a 64 bits fixed-point number
+//! (32 integer bits, 32 fractional bits), so that the simulation gives bit-exact results on every
+//! build and platform. Code meant to work in both modes uses `Scalar`, the `Real` trait and the
+//! `scalar!` macro instead of `f32` and float literals.
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// The number type of the coordinates
+#[cfg(not(feature = "fixed"))]
+pub type Scalar = f32;
+
+/// The number type of the coordinates
+#[cfg(feature = "fixed")]
+pub type Scalar = fixed::types::I32F32;
+
+/// Returns the given number literal as a `Scalar`, usable in constants
+///
+/// ```
+/// use geometry::{scalar, Scalar};
+///
+/// const SPEED: Scalar = scalar!(200.0);
+/// assert_eq!(SPEED * scalar!(2), scalar!(400));
+/// ```
+#[cfg(not(feature = "fixed"))]
+#[macro_export]
+macro_rules! scalar {
+    ($value:literal) => {
+        $value as $crate::Scalar
+    };
+}
+
+/// Returns the given number literal as a `Scalar`, usable in constants
+///
+/// ```
+/// use geometry::{scalar, Scalar};
+///
+/// const SPEED: Scalar = scalar!(200.0);
+/// assert_eq!(SPEED * scalar!(2), scalar!(400));
+/// ```
+#[cfg(feature = "fixed")]
+#[macro_export]
+macro_rules! scalar {
+    ($value:literal) => {
+        $crate::Scalar::lit(stringify!($value))
+    };
+}
+
+/// The operations the geometry needs from its number type
+///
+/// `min`, `max` and `clamp` aren't part of it, `f32` has them built in and fixed-point numbers
+/// get them from `Ord`
+pub trait Real:
+    Copy
+    + Default
+    + PartialEq
+    + PartialOrd
+    + fmt::Debug
+    + fmt::Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+    + Sum
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const PI: Self;
+    /// The largest value, standing for infinity
+    const MAX: Self;
+    /// The smallest value, standing for negative infinity
+    const MIN: Self;
+
+    /// Converts from a `f32`, rounding to the closest value
+    fn from_f32(value: f32) -> Self;
+
+    /// Converts to a `f32`, rounding to the closest value
+    fn to_f32(self) -> f32;
+
+    /// Returns the bits of the number, to hash the exact value
+    fn bits(self) -> u64;
+
+    fn sqrt(self) -> Self;
+    fn abs(self) -> Self;
+    fn floor(self) -> Self;
+    fn signum(self) -> Self;
+    fn rem_euclid(self, other: Self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+
+    /// Returns the ordering between two numbers, NaNs included
+    fn total_cmp(&self, other: &Self) -> std::cmp::Ordering;
+
+    /// Converts to the closest integer, rounding toward negative infinity
+    fn floor_i32(self) -> i32 {
+        self.floor().to_f32() as i32
+    }
+}
+
+impl Real for f32 {
+    const ZERO: f32 = 0.0;
+    const ONE: f32 = 1.0;
+    const PI: f32 = std::f32::consts::PI;
+    const MAX: f32 = f32::INFINITY;
+    const MIN: f32 = f32::NEG_INFINITY;
+
+    fn from_f32(value: f32) -> f32 {
+        value
+    }
+
+    fn to_f32(self) -> f32 {
+        self
+    }
+
+    fn bits(self) -> u64 {
+        f32::to_bits(self) as u64
+    }
+
+    fn sqrt(self) -> f32 {
+        f32::sqrt(self)
+    }
+
+    fn abs(self) -> f32 {
+        f32::abs(self)
+    }
+
+    fn floor(self) -> f32 {
+        f32::floor(self)
+    }
+
+    fn signum(self) -> f32 {
+        f32::signum(self)
+    }
+
+    fn rem_euclid(self, other: f32) -> f32 {
+        f32::rem_euclid(self, other)
+    }
+
+    fn sin(self) -> f32 {
+        f32::sin(self)
+    }
+
+    fn cos(self) -> f32 {
+        f32::cos(self)
+    }
+
+    fn atan2(self, other: f32) -> f32 {
+        f32::atan2(self, other)
+    }
+
+    fn total_cmp(&self, other: &f32) -> std::cmp::Ordering {
+        f32::total_cmp(self, other)
+    }
+}
+
+#[cfg(feature = "fixed")]
+impl Real for fixed::types::I32F32 {
+    const ZERO: Self = Self::ZERO;
+    const ONE: Self = Self::ONE;
+    const PI: Self = Self::PI;
+    const MAX: Self = Self::MAX;
+    const MIN: Self = Self::MIN;
+
+    fn from_f32(value: f32) -> Self {
+        Self::from_num(value)
+    }
+
+    fn to_f32(self) -> f32 {
+        self.to_num()
+    }
+
+    fn bits(self) -> u64 {
+        Self::to_bits(self) as u64
+    }
+
+    fn sqrt(self) -> Self {
+        Self::sqrt(self)
+    }
+
+    fn abs(self) -> Self {
+        Self::abs(self)
+    }
+
+    fn floor(self) -> Self {
+        Self::floor(self)
+    }
+
+    fn signum(self) -> Self {
+        Self::signum(self)
+    }
+
+    fn rem_euclid(self, other: Self) -> Self {
+        Self::rem_euclid(self, other)
+    }
+
+    fn sin(self) -> Self {
+        fixed_trig::sin(self)
+    }
+
+    fn cos(self) -> Self {
+        fixed_trig::sin(self + Self::FRAC_PI_2)
+    }
+
+    fn atan2(self, other: Self) -> Self {
+        fixed_trig::atan2(self, other)
+    }
+
+    fn total_cmp(&self, other: &Self) -> std::cmp::Ordering {
+        Ord::cmp(self, other)
+    }
+
+    fn floor_i32(self) -> i32 {
+        Self::floor(self).to_num()
+    }
+}
+
+/// Trigonometry on fixed-point numbers, using only integer operations so results are the same
+/// everywhere
+#[cfg(feature = "fixed")]
+mod fixed_trig {
+    use fixed::types::I32F32;
+
+    /// Evaluates the odd series `x + c1 x^3 + c2 x^5 + ...` with Horner's method
+    fn odd_series(x: I32F32, coefficients: &[I32F32]) -> I32F32 {
+        let squared = x * x;
+        let sum = coefficients
+            .iter()
+            .rev()
+            .fold(I32F32::ZERO, |sum, &coefficient| (sum + coefficient) * squared);
+        x + x * sum
+    }
+
+    /// Returns the sine, from the Taylor series on the angle brought back to [-pi/2, pi/2]
+    pub fn sin(angle: I32F32) -> I32F32 {
+        const COEFFICIENTS: [I32F32; 5] = [
+            I32F32::lit("-0.16666666666666666"),  // -1/3!
+            I32F32::lit("0.008333333333333333"),  // 1/5!
+            I32F32::lit("-0.0001984126984126984"), // -1/7!
+            I32F32::lit("0.0000027557319223985893"), // 1/9!
+            I32F32::lit("-0.00000002505210838544172"), // -1/11!
+        ];
+        let angle = (angle + I32F32::PI).rem_euclid(I32F32::TAU) - I32F32::PI;
+        let angle = if angle > I32F32::FRAC_PI_2 {
+            I32F32::PI - angle
+        } else if angle < -I32F32::FRAC_PI_2 {
+            -I32F32::PI - angle
+        } else {
+            angle
+        };
+        odd_series(angle, &COEFFICIENTS)
+    }
+
+    /// Returns the arc tangent of a ratio in [-1, 1]
+    fn atan(ratio: I32F32) -> I32F32 {
+        const COEFFICIENTS: [I32F32; 7] = [
+            I32F32::lit("-0.3333333333333333"),
+            I32F32::lit("0.2"),
+            I32F32::lit("-0.14285714285714285"),
+            I32F32::lit("0.1111111111111111"),
+            I32F32::lit("-0.09090909090909091"),
+            I32F32::lit("0.07692307692307693"),
+            I32F32::lit("-0.06666666666666667"),
+        ];
+        // atan(x) = 2 atan(x / (1 + sqrt(1 + x^2))) brings the ratio under tan(pi/8), where the
+        // series converges quickly
+        let half = ratio / (I32F32::ONE + (I32F32::ONE + ratio * ratio).sqrt());
+        odd_series(half, &COEFFICIENTS) * 2
+    }
+
+    /// Returns the angle of the point `(x, y)`, in [-pi, pi]
+    pub fn atan2(y: I32F32, x: I32F32) -> I32F32 {
+        if x == I32F32::ZERO && y == I32F32::ZERO {
+            I32F32::ZERO
+        } else if x.abs() >= y.abs() {
+            let angle = atan(y / x);
+            if x > I32F32::ZERO {
+                angle
+            } else if y >= I32F32::ZERO {
+                angle + I32F32::PI
+            } else {
+                angle - I32F32::PI
+            }
+        } else {
+            let angle = -atan(x / y);
+            if y > I32F32::ZERO {
+                angle + I32F32::FRAC_PI_2
+            } else {
+                angle - I32F32::FRAC_PI_2
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! `Serialize` and `Deserialize` implementations for the geometry types
+//!
+//! The representations are kept compact for level and save files:
+//! - points are `(x, y)` tuples and sizes `(width, height)` tuples
+//! - rectangles are `(x, y, width, height)` tuples
+//! - polygons are lists of points
+//!
+//! Scalars are written as plain numbers with both the float and the fixed-point `Scalar`, so
+//! files can be shared between the two
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{Point, Polygon, Rect, Size};
+use self::scalar::Number;
+
+/// (De)serializes a `Scalar` as a plain number, for use with `#[serde(with = "...")]`
+pub(crate) mod scalar {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::Scalar;
+
+    /// A `Scalar` going through serde as a plain number
+    pub struct Number(pub Scalar);
+
+    #[cfg(not(feature = "fixed"))]
+    impl Serialize for Number {
+        fn serialize<Z: Serializer>(&self, serializer: Z) -> Result<Z::Ok, Z::Error> {
+            serializer.serialize_f32(self.0)
+        }
+    }
+
+    #[cfg(not(feature = "fixed"))]
+    impl<'de> Deserialize<'de> for Number {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Number, D::Error> {
+            f32::deserialize(deserializer).map(Number)
+        }
+    }
+
+    // A `f64` holds every fixed-point value of the map exactly (up to 2^21 in magnitude)
+    #[cfg(feature = "fixed")]
+    impl Serialize for Number {
+        fn serialize<Z: Serializer>(&self, serializer: Z) -> Result<Z::Ok, Z::Error> {
+            serializer.serialize_f64(self.0.to_num())
+        }
+    }
+
+    #[cfg(feature = "fixed")]
+    impl<'de> Deserialize<'de> for Number {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Number, D::Error> {
+            let value = f64::deserialize(deserializer)?;
+            Scalar::checked_from_num(value)
+                .map(Number)
+                .ok_or_else(|| serde::de::Error::custom(format!("{} is out of the fixed-point range", value)))
+        }
+    }
+
+    pub fn serialize<Z: Serializer>(value: &Scalar, serializer: Z) -> Result<Z::Ok, Z::Error> {
+        Number(*value).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Scalar, D::Error> {
+        Number::deserialize(deserializer).map(|Number(value)| value)
+    }
+}
+
+impl<S> Serialize for Point<S> {
+    fn serialize<Z: Serializer>(&self, serializer: Z) -> Result<Z::Ok, Z::Error> {
+        (Number(self.x), Number(self.y)).serialize(serializer)
+    }
+}
+
+impl<'de, S> Deserialize<'de> for Point<S> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Point<S>, D::Error> {
+        let (Number(x), Number(y)) = Deserialize::deserialize(deserializer)?;
+        Ok(Point::new(x, y))
+    }
+}
+
+impl<S> Serialize for Size<S> {
+    fn serialize<Z: Serializer>(&self, serializer: Z) -> Result<Z::Ok, Z::Error> {
+        (Number(self.width), Number(self.height)).serialize(serializer)
+    }
+}
+
+impl<'de, S> Deserialize<'de> for Size<S> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Size<S>, D::Error> {
+        let (Number(width), Number(height)) = Deserialize::deserialize(deserializer)?;
+        Ok(Size::new(width, height))
+    }
+}
+
+impl<S> Serialize for Rect<S> {
+    fn serialize<Z: Serializer>(&self, serializer: Z) -> Result<Z::Ok, Z::Error> {
+        (Number(self.x), Number(self.y), Number(self.width), Number(self.height)).serialize(serializer)
+    }
+}
+
+impl<'de, S> Deserialize<'de> for Rect<S> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Rect<S>, D::Error> {
+        let (Number(x), Number(y), Number(width), Number(height)) = Deserialize::deserialize(deserializer)?;
+        Ok(Rect::new(x, y, width, height))
+    }
+}
+
+impl<S> Serialize for Polygon<S> {
+    fn serialize<Z: Serializer>(&self, serializer: Z) -> Result<Z::Ok, Z::Error> {
+        self.vertices().serialize(serializer)
+    }
+}
+
+impl<'de, S> Deserialize<'de> for Polygon<S> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Polygon<S>, D::Error> {
+        let vertices = Vec::<Point<S>>::deserialize(deserializer)?;
+        if vertices.len() < 3 {
+            return Err(D::Error::custom("a polygon needs at least 3 vertices"));
+        }
+        Ok(Polygon::new(vertices))
+    }
+}
@@ -0,0 +1,180 @@
+//! Shapes used for collision detection
+//!
+//! Overlaps are found with the separating axis theorem, which works for any pair of convex shapes
+use super::{scalar, Point, Real, Scalar, WorldPoint, WorldSize};
+
+/// Information about how two shapes overlap
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Contact {
+    /// The unit vector pointing from the first shape to the second one
+    pub normal: WorldPoint,
+    /// How far the second shape has to move along the normal to stop overlapping
+    pub depth: Scalar,
+}
+
+/// The outline of an object, relative to its position
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Shape {
+    /// A circle of the given radius, centered on the position
+    Circle(#[cfg_attr(feature = "serde", serde(with = "crate::serialize::scalar"))] Scalar),
+    /// An axis-aligned box of the given size, centered on the position
+    Aabb(WorldSize),
+    /// A convex polygon, with its vertices relative to the position
+    Polygon(Vec<WorldPoint>),
+}
+
+impl Shape {
+    /// Returns the diamond outline of an isometric tile of the given size, centered on the position
+    pub fn diamond(size: WorldSize) -> Shape {
+        let (w, h) = (size.width / scalar!(2), size.height / scalar!(2));
+        Shape::Polygon(vec![
+            Point::new(Scalar::ZERO, -h),
+            Point::new(w, Scalar::ZERO),
+            Point::new(Scalar::ZERO, h),
+            Point::new(-w, Scalar::ZERO),
+        ])
+    }
+
+    /// Returns the radius of the smallest circle centered on the position containing the shape
+    pub fn bounding_radius(&self) -> Scalar {
+        match self {
+            Shape::Circle(radius) => *radius,
+            Shape::Aabb(size) => (size.width * size.width + size.height * size.height).sqrt() / scalar!(2),
+            Shape::Polygon(vertices) => vertices
+                .iter()
+                .map(|vertex| vertex.length())
+                .fold(Scalar::ZERO, Scalar::max),
+        }
+    }
+
+    /// Returns the vertices of the shape placed at the given position (none for a circle)
+    fn vertices(&self, position: WorldPoint) -> Vec<WorldPoint> {
+        match self {
+            Shape::Circle(_) => vec![],
+            Shape::Aabb(size) => {
+                let (w, h) = (size.width / scalar!(2), size.height / scalar!(2));
+                vec![
+                    Point::new(position.x - w, position.y - h),
+                    Point::new(position.x + w, position.y - h),
+                    Point::new(position.x + w, position.y + h),
+                    Point::new(position.x - w, position.y + h),
+                ]
+            }
+            Shape::Polygon(vertices) => vertices.iter().map(|&v| v + position).collect(),
+        }
+    }
+
+    /// Returns how this shape at `position` overlaps the `other` shape at `other_position`,
+    /// or `None` if they don't
+    pub fn contact(&self, position: WorldPoint, other: &Shape, other_position: WorldPoint) -> Option<Contact> {
+        match (self, other) {
+            (Shape::Circle(radius), Shape::Circle(other_radius)) => {
+                circle_circle(position, *radius, other_position, *other_radius)
+            }
+            (_, Shape::Circle(other_radius)) => {
+                polygon_circle(&self.vertices(position), other_position, *other_radius)
+            }
+            (Shape::Circle(radius), _) => {
+                polygon_circle(&other.vertices(other_position), position, *radius)
+                    .map(|contact| Contact { normal: -contact.normal, ..contact })
+            }
+            _ => polygon_polygon(&self.vertices(position), &other.vertices(other_position)),
+        }
+    }
+
+    /// Returns true if this shape at `position` overlaps the `other` shape at `other_position`
+    pub fn overlaps(&self, position: WorldPoint, other: &Shape, other_position: WorldPoint) -> bool {
+        self.contact(position, other, other_position).is_some()
+    }
+}
+
+fn circle_circle(center: WorldPoint, radius: Scalar, other_center: WorldPoint, other_radius: Scalar) -> Option<Contact> {
+    let radii = radius + other_radius;
+    let delta = other_center - center;
+    if delta.dot(delta) >= radii * radii {
+        return None;
+    }
+
+    let distance = delta.length();
+    let normal = if distance > Scalar::ZERO {
+        delta / distance
+    } else {
+        Point::new(Scalar::ONE, Scalar::ZERO)
+    };
+    Some(Contact { normal, depth: radii - distance })
+}
+
+fn polygon_circle(vertices: &[WorldPoint], center: WorldPoint, radius: Scalar) -> Option<Contact> {
+    // Besides the edge normals, the axis going through the closest vertex has to be checked
+    let closest = vertices
+        .iter()
+        .copied()
+        .min_by(|a, b| a.squared_distance_to(center).total_cmp(&b.squared_distance_to(center)))?;
+    let axes = edge_normals(vertices).chain(Some((center - closest).normalize()));
+
+    separating_axis_contact(axes, |axis| project(vertices, axis), |axis| {
+        let projection = center.dot(axis);
+        (projection - radius, projection + radius)
+    })
+    .map(|contact| orient(contact, centroid(vertices), center))
+}
+
+fn polygon_polygon(vertices: &[WorldPoint], other_vertices: &[WorldPoint]) -> Option<Contact> {
+    let axes = edge_normals(vertices).chain(edge_normals(other_vertices));
+
+    separating_axis_contact(axes, |axis| project(vertices, axis), |axis| project(other_vertices, axis))
+        .map(|contact| orient(contact, centroid(vertices), centroid(other_vertices)))
+}
+
+/// Projects both shapes on each axis, and returns the axis with the smallest overlap, unless one
+/// of them separates the shapes
+fn separating_axis_contact<A, F, G>(axes: A, project_first: F, project_second: G) -> Option<Contact>
+where
+    A: Iterator<Item = WorldPoint>,
+    F: Fn(WorldPoint) -> (Scalar, Scalar),
+    G: Fn(WorldPoint) -> (Scalar, Scalar),
+{
+    let mut best: Option<Contact> = None;
+    for axis in axes.filter(|axis| *axis != Point::default()) {
+        let (min, max) = project_first(axis);
+        let (other_min, other_max) = project_second(axis);
+        let depth = max.min(other_max) - min.max(other_min);
+        if depth <= Scalar::ZERO {
+            return None;
+        }
+        if best.is_none_or(|contact| depth < contact.depth) {
+            best = Some(Contact { normal: axis, depth });
+        }
+    }
+    best
+}
+
+/// Flips the contact normal if needed so that it points from `from` to `to`
+fn orient(contact: Contact, from: WorldPoint, to: WorldPoint) -> Contact {
+    if (to - from).dot(contact.normal) < Scalar::ZERO {
+        Contact { normal: -contact.normal, ..contact }
+    } else {
+        contact
+    }
+}
+
+/// Returns the unit normals of the edges of a polygon
+fn edge_normals(vertices: &[WorldPoint]) -> impl Iterator<Item = WorldPoint> + '_ {
+    vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(&a, &b)| (b - a).perpendicular().normalize())
+}
+
+/// Returns the interval covered by the polygon along the axis
+fn project(vertices: &[WorldPoint], axis: WorldPoint) -> (Scalar, Scalar) {
+    vertices.iter().fold((Scalar::MAX, Scalar::MIN), |(min, max), vertex| {
+        let projection = vertex.dot(axis);
+        (min.min(projection), max.max(projection))
+    })
+}
+
+fn centroid(vertices: &[WorldPoint]) -> WorldPoint {
+    vertices.iter().fold(Point::default(), |sum, &vertex| sum + vertex) / Scalar::from_f32(vertices.len() as f32)
+}
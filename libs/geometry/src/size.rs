@@ -1,36 +1,76 @@
+use std::fmt;
+use std::marker::PhantomData;
+
 use rand::Rng;
 
+use super::{Real, Scalar};
+
 /// A `Size` represents a region in space
-#[derive(Clone, Copy, Default, Debug)]
-pub struct Size {
-    pub width: f32,
-    pub height: f32,
+///
+/// The `S` marker tells which coordinate space the size lives in (see the `space` module)
+pub struct Size<S> {
+    pub width: Scalar,
+    pub height: Scalar,
+    space: PhantomData<S>,
 }
 
-impl Size {
+impl<S> Size<S> {
     /// Returns a new `Size` of the given dimensions
-    pub fn new(width: f32, height: f32) -> Size {
+    pub const fn new(width: Scalar, height: Scalar) -> Size<S> {
         Size {
             width,
             height,
+            space: PhantomData,
         }
     }
 
+    /// Reinterprets this size in another space, without converting it
+    pub fn cast<T>(self) -> Size<T> {
+        Size::new(self.width, self.height)
+    }
+
     /// Returns a random x coordinate within the bounds of this `Size`
-    pub fn random_x<R: Rng>(&self, rng: &mut R) -> f32 {
-        rng.gen_range(0.0..self.width)
+    pub fn random_x<R: Rng>(&self, rng: &mut R) -> Scalar {
+        Scalar::from_f32(rng.gen::<f32>()) * self.width
     }
 
     /// Returns a random y coordinate within the bounds of this `Size`
-    pub fn random_y<R: Rng>(&self, rng: &mut R) -> f32 {
-        rng.gen_range(0.0..self.height)
+    pub fn random_y<R: Rng>(&self, rng: &mut R) -> Scalar {
+        Scalar::from_f32(rng.gen::<f32>()) * self.height
     }
 
     /// Applies the given scale to this `Size`
-    pub fn scale(&self, scale: f32) -> Size {
-        Size {
-            width: self.width * scale,
-            height: self.height * scale,
-        }
+    pub fn scale(&self, scale: Scalar) -> Size<S> {
+        Size::new(self.width * scale, self.height * scale)
+    }
+}
+
+impl<S> Clone for Size<S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for Size<S> {}
+
+impl<S> Default for Size<S> {
+    fn default() -> Self {
+        Size::new(Scalar::ZERO, Scalar::ZERO)
+    }
+}
+
+/// Implements '==' for Size, as well as its inverse '!='
+impl<S> PartialEq for Size<S> {
+    fn eq(&self, _rhs: &Self) -> bool {
+        (self.width == _rhs.width) && (self.height == _rhs.height)
+    }
+}
+
+impl<S> fmt::Debug for Size<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Size")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .finish()
     }
 }
@@ -0,0 +1,45 @@
+//! Marker types for the coordinate spaces a `Point` or a `Size` can live in
+//!
+//! Positions from different spaces can't be mixed, going from one space to another has to be
+//! done explicitly, usually through an `IsoProjection`
+//!
+//! ```compile_fail
+//! use geometry::{ScreenPoint, WorldPoint};
+//!
+//! let _ = ScreenPoint::new(1.0, 2.0) + WorldPoint::new(3.0, 4.0);
+//! ```
+use super::{Point, Polygon, Rect, Size};
+
+/// Pixels on the window, scaled and offset by the map position
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ScreenSpace;
+
+/// Unscaled isometric pixels, relative to the map origin
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WorldSpace;
+
+/// Columns and rows of the map grid
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TileSpace;
+
+/// A `Point` on the screen
+pub type ScreenPoint = Point<ScreenSpace>;
+/// A `Point` in the world
+pub type WorldPoint = Point<WorldSpace>;
+/// A `Point` on the tile grid
+pub type TilePoint = Point<TileSpace>;
+
+/// A `Size` on the screen
+pub type ScreenSize = Size<ScreenSpace>;
+/// A `Size` in the world
+pub type WorldSize = Size<WorldSpace>;
+
+/// A `Rect` on the screen
+pub type ScreenRect = Rect<ScreenSpace>;
+/// A `Rect` in the world
+pub type WorldRect = Rect<WorldSpace>;
+
+/// A `Polygon` in the world
+pub type WorldPolygon = Polygon<WorldSpace>;
+/// A `Polygon` on the tile grid
+pub type TilePolygon = Polygon<TileSpace>;
@@ -0,0 +1,175 @@
+//! A broadphase index to quickly find the objects close to a position
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use super::{Real, Scalar, WorldPoint};
+
+/// The bounding circle of an object stored in the grid
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    position: WorldPoint,
+    radius: Scalar,
+}
+
+impl Entry {
+    fn overlaps(&self, position: WorldPoint, radius: Scalar) -> bool {
+        let radii = self.radius + radius;
+        self.position.squared_distance_to(position) < radii * radii
+    }
+}
+
+/// A `SpatialGrid` buckets objects by the square cells their bounding circle covers
+///
+/// Objects are identified by a key (usually their index in a `Vec`), and have to be inserted
+/// again each time they move
+pub struct SpatialGrid<K> {
+    cell_size: Scalar,
+    cells: HashMap<(i32, i32), Vec<K>>,
+    entries: HashMap<K, Entry>,
+}
+
+impl<K: Copy + Eq + Hash + Ord> SpatialGrid<K> {
+    /// Returns a new empty `SpatialGrid` with cells of the given size
+    pub fn new(cell_size: Scalar) -> SpatialGrid<K> {
+        assert!(cell_size > Scalar::ZERO);
+        SpatialGrid {
+            cell_size,
+            cells: HashMap::new(),
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Returns the number of objects in the grid
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the grid contains no objects
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes all the objects from the grid
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.entries.clear();
+    }
+
+    /// Inserts an object with the given bounding circle, or moves it if it is already there
+    ///
+    /// A moving object is only removed from the cells it leaves and added to the ones it enters.
+    pub fn insert(&mut self, key: K, position: WorldPoint, radius: Scalar) {
+        let bounds = self.cell_bounds(position, radius);
+        let old = self
+            .entries
+            .insert(key, Entry { position, radius })
+            .map(|old| self.cell_bounds(old.position, old.radius));
+        if old == Some(bounds) {
+            return;
+        }
+        if let Some(old) = old {
+            for cell in cells_in(old).filter(|&cell| !bounds_contain(bounds, cell)) {
+                self.remove_from_cell(cell, key);
+            }
+        }
+        for cell in cells_in(bounds).filter(|&cell| !old.is_some_and(|old| bounds_contain(old, cell))) {
+            self.cells.entry(cell).or_default().push(key);
+        }
+    }
+
+    /// Removes the objects whose key doesn't match the predicate
+    pub fn retain<F: FnMut(&K) -> bool>(&mut self, mut keep: F) {
+        let removed: Vec<K> = self.entries.keys().copied().filter(|key| !keep(key)).collect();
+        for key in removed {
+            self.remove(key);
+        }
+    }
+
+    /// Removes an object from the grid, returns false if it wasn't there
+    pub fn remove(&mut self, key: K) -> bool {
+        let Some(entry) = self.entries.remove(&key) else {
+            return false;
+        };
+        for cell in cells_in(self.cell_bounds(entry.position, entry.radius)) {
+            self.remove_from_cell(cell, key);
+        }
+        true
+    }
+
+    fn remove_from_cell(&mut self, cell: (i32, i32), key: K) {
+        if let Some(keys) = self.cells.get_mut(&cell) {
+            keys.retain(|&k| k != key);
+            if keys.is_empty() {
+                self.cells.remove(&cell);
+            }
+        }
+    }
+
+    /// Returns the objects overlapping the given circle, sorted by key
+    pub fn query_radius(&self, center: WorldPoint, radius: Scalar) -> Vec<K> {
+        let mut found: Vec<K> = self
+            .cells_covering(center, radius)
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .filter(|key| self.entries[key].overlaps(center, radius))
+            .collect::<HashSet<K>>()
+            .into_iter()
+            .collect();
+        found.sort();
+        found
+    }
+
+    /// Returns the other objects within `radius` of the given one, sorted by key
+    pub fn neighbours(&self, key: K, radius: Scalar) -> Vec<K> {
+        match self.entries.get(&key) {
+            Some(entry) => {
+                let mut found = self.query_radius(entry.position, entry.radius + radius);
+                found.retain(|&k| k != key);
+                found
+            }
+            None => vec![],
+        }
+    }
+
+    /// Returns every pair of objects whose bounding circles overlap, each pair being sorted and
+    /// reported once
+    pub fn colliding_pairs(&self) -> Vec<(K, K)> {
+        let mut pairs = HashSet::new();
+        for keys in self.cells.values() {
+            for (i, &a) in keys.iter().enumerate() {
+                let entry = &self.entries[&a];
+                for &b in &keys[i + 1..] {
+                    let other = &self.entries[&b];
+                    if entry.overlaps(other.position, other.radius) {
+                        pairs.insert((a.min(b), a.max(b)));
+                    }
+                }
+            }
+        }
+        let mut pairs: Vec<(K, K)> = pairs.into_iter().collect();
+        pairs.sort();
+        pairs
+    }
+
+    /// Returns the cells covered by the bounding box of the given circle
+    fn cells_covering(&self, center: WorldPoint, radius: Scalar) -> impl Iterator<Item = (i32, i32)> {
+        cells_in(self.cell_bounds(center, radius))
+    }
+
+    /// Returns the first and last cells covered by the bounding box of the given circle
+    fn cell_bounds(&self, center: WorldPoint, radius: Scalar) -> ((i32, i32), (i32, i32)) {
+        let cell = |value: Scalar| (value / self.cell_size).floor_i32();
+        ((cell(center.x - radius), cell(center.y - radius)), (cell(center.x + radius), cell(center.y + radius)))
+    }
+}
+
+/// Returns the cells between the given first and last cells
+fn cells_in(((min_x, min_y), (max_x, max_y)): ((i32, i32), (i32, i32))) -> impl Iterator<Item = (i32, i32)> {
+    (min_x..=max_x).flat_map(move |x| (min_y..=max_y).map(move |y| (x, y)))
+}
+
+/// Returns true if the given cell is between the given first and last cells
+fn bounds_contain(((min_x, min_y), (max_x, max_y)): ((i32, i32), (i32, i32)), (x, y): (i32, i32)) -> bool {
+    (min_x..=max_x).contains(&x) && (min_y..=max_y).contains(&y)
+}
@@ -0,0 +1,68 @@
+//! Steering behaviours for autonomous agents
+//!
+//! Each behaviour returns a steering force to give to `Vector::apply_force`. Forces can be
+//! weighted and summed to combine behaviours.
+use rand::Rng;
+
+use super::{scalar, Point, Real, Scalar, Vector, WorldPoint};
+
+/// Returns the force steering towards the target at full speed
+pub fn seek(vector: &Vector, target: WorldPoint, max_speed: Scalar) -> WorldPoint {
+    let desired = (target - vector.position).normalize() * max_speed;
+    desired - vector.velocity
+}
+
+/// Returns the force steering away from the threat at full speed
+pub fn flee(vector: &Vector, threat: WorldPoint, max_speed: Scalar) -> WorldPoint {
+    let desired = (vector.position - threat).normalize() * max_speed;
+    desired - vector.velocity
+}
+
+/// Returns the force steering towards the target, slowing down within `slowing_radius` so the
+/// vector stops on it
+pub fn arrive(vector: &Vector, target: WorldPoint, max_speed: Scalar, slowing_radius: Scalar) -> WorldPoint {
+    let offset = target - vector.position;
+    let distance = offset.length();
+    let speed = if distance < slowing_radius {
+        max_speed * distance / slowing_radius
+    } else {
+        max_speed
+    };
+    offset.normalize() * speed - vector.velocity
+}
+
+/// Returns a force making the vector roam around randomly
+///
+/// A target is picked on a circle of `radius` placed `distance` ahead of the vector, at most
+/// `jitter` radians away from the current direction
+pub fn wander<R: Rng>(vector: &Vector, rng: &mut R, max_speed: Scalar, distance: Scalar, radius: Scalar, jitter: Scalar) -> WorldPoint {
+    let angle = vector.direction + jitter * (Scalar::from_f32(rng.gen::<f32>()) * scalar!(2) - Scalar::ONE);
+    let center = vector.position + vector.heading() * distance;
+    let target = center + Point::new(angle.cos(), angle.sin()) * radius;
+    seek(vector, target, max_speed)
+}
+
+/// Returns the force pushing the vector away from its neighbours closer than `radius`, closer
+/// neighbours pushing harder
+pub fn separation(vector: &Vector, neighbours: &[WorldPoint], radius: Scalar, max_speed: Scalar) -> WorldPoint {
+    let push = neighbours
+        .iter()
+        .map(|&neighbour| vector.position - neighbour)
+        .filter(|offset| offset.length() < radius)
+        .fold(Point::default(), |push: WorldPoint, offset| {
+            push + offset.normalize() * (Scalar::ONE - offset.length() / radius)
+        });
+
+    if push == Point::default() {
+        push
+    } else {
+        push.normalize() * max_speed - vector.velocity
+    }
+}
+
+/// Returns the force steering towards where the target will be, given its current velocity
+pub fn pursuit(vector: &Vector, target: &Vector, max_speed: Scalar) -> WorldPoint {
+    let distance = (target.position - vector.position).length();
+    let lookahead = if max_speed > Scalar::ZERO { distance / max_speed } else { Scalar::ZERO };
+    seek(vector, target.position + target.velocity * lookahead, max_speed)
+}
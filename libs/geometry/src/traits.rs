@@ -1,41 +1,50 @@
 //! Traits used by the models
-use super::{Point};
+use super::{scalar, Contact, Point, Scalar, Shape, WorldPoint};
 
 /// A trait for objects that occupy a position in space
 pub trait Position {
     /// Returns the x coordinate of the object
-    fn x(&self) -> f32;
+    fn x(&self) -> Scalar;
 
     /// Returns a mutable reference to the x coordinate
-    fn x_mut(&mut self) -> &mut f32;
+    fn x_mut(&mut self) -> &mut Scalar;
 
     /// Returns the y coordinate of the object
-    fn y(&self) -> f32;
+    fn y(&self) -> Scalar;
 
     /// Returns a mutable reference to the y coordinate
-    fn y_mut(&mut self) -> &mut f32;
+    fn y_mut(&mut self) -> &mut Scalar;
 
-    /// Returns the position of the object
-    fn position(&self) -> Point {
+    /// Returns the position of the object, in world space
+    fn position(&self) -> WorldPoint {
         Point::new(self.x(), self.y())
     }
 }
 
-/// A trait that provides collision detection for objects with a position and a radius
+/// A trait that provides collision detection for objects with a position and a shape
 ///
-/// For collision purposes, all objects are treated as circles
+/// By default, objects are treated as circles of the given radius
 pub trait Collide: Position {
     /// Returns the radius of the object
-    fn radius(&self) -> f32;
+    fn radius(&self) -> Scalar;
 
     /// Returns the diameter of the objects
-    fn diameter(&self) -> f32 {
-        self.radius() * 2.0
+    fn diameter(&self) -> Scalar {
+        self.radius() * scalar!(2)
+    }
+
+    /// Returns the shape of the object, relative to its position
+    fn shape(&self) -> Shape {
+        Shape::Circle(self.radius())
+    }
+
+    /// Returns how the two objects overlap, or `None` if they don't collide
+    fn contact_with<O: Collide>(&self, other: &O) -> Option<Contact> {
+        self.shape().contact(self.position(), &other.shape(), other.position())
     }
 
     /// Returns true if the two objects collide and false otherwise
     fn collides_with<O: Collide>(&self, other: &O) -> bool {
-        let radii = self.radius() + other.radius();
-        self.position().squared_distance_to(other.position()) < radii * radii
+        self.contact_with(other).is_some()
     }
 }
@@ -1,38 +1,96 @@
-use std::f32;
-
 use rand::Rng;
 
-use super::{Point, Size};
+use super::{scalar, Point, Real, Scalar, WorldPoint, WorldSize};
 
-/// A `Vector`
+/// A `Vector` is the kinematic state of a moving object
 #[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vector {
     /// The position of the vector
-    pub position: Point,
+    pub position: WorldPoint,
     /// The direction angle, in radians
-    pub direction: f32,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialize::scalar"))]
+    pub direction: Scalar,
+    /// The velocity, in world pixels per second
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub velocity: WorldPoint,
+    /// The acceleration, in world pixels per second squared
+    ///
+    /// It is reset each time the vector advances, so forces have to be applied every update
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub acceleration: WorldPoint,
 }
 
 impl Vector {
-    /// Returns a new `Vector`
-    pub fn new(position: Point, direction: f32) -> Vector {
+    /// Returns a new `Vector` at rest
+    pub fn new(position: WorldPoint, direction: Scalar) -> Vector {
         Vector {
             position,
             direction,
+            velocity: Point::default(),
+            acceleration: Point::default(),
         }
     }
 
     /// Returns a random `Vector` within the given bounds
-    pub fn random<R: Rng>(rng: &mut R, bounds: Size) -> Vector {
+    pub fn random<R: Rng>(rng: &mut R, bounds: WorldSize) -> Vector {
         Vector::new(
             Point::random(rng, bounds),
-            rng.gen::<f32>() * 2.0 * f32::consts::PI,
+            Scalar::from_f32(rng.gen::<f32>()) * scalar!(2) * Scalar::PI,
         )
     }
 
     /// Consumes the vector and returns a new one with inverted direction
     pub fn invert(mut self) -> Vector {
-        self.direction -= f32::consts::PI;
+        self.direction -= Scalar::PI;
         self
     }
+
+    /// Returns the unit vector pointing in the direction the vector is facing
+    pub fn heading(&self) -> WorldPoint {
+        Point::new(self.direction.cos(), self.direction.sin())
+    }
+
+    /// Returns the current speed, in world pixels per second
+    pub fn speed(&self) -> Scalar {
+        self.velocity.length()
+    }
+
+    /// Adds a force to the acceleration of the next advance
+    pub fn apply_force(&mut self, force: WorldPoint) {
+        self.acceleration = self.acceleration + force;
+    }
+
+    /// Scales the velocity down if it is faster than `max_speed`
+    pub fn limit_speed(&mut self, max_speed: Scalar) {
+        if self.speed() > max_speed {
+            self.velocity = self.velocity.normalize() * max_speed;
+        }
+    }
+
+    /// Integrates the acceleration and velocity over `dt` seconds
+    ///
+    /// The direction follows the velocity whenever the vector is moving
+    pub fn advance(&mut self, dt: Scalar) {
+        self.velocity = self.velocity + self.acceleration * dt;
+        self.move_along(dt);
+    }
+
+    /// Integrates the acceleration over `dt` seconds, then limits the speed to `max_speed` before
+    /// moving, so that the vector never moves faster than it
+    pub fn advance_limited(&mut self, dt: Scalar, max_speed: Scalar) {
+        self.velocity = self.velocity + self.acceleration * dt;
+        self.limit_speed(max_speed);
+        self.move_along(dt);
+    }
+
+    /// Moves the position along the velocity over `dt` seconds and resets the acceleration
+    fn move_along(&mut self, dt: Scalar) {
+        self.position = self.position + self.velocity * dt;
+        self.acceleration = Point::default();
+
+        if self.velocity != Point::default() {
+            self.direction = self.velocity.y.atan2(self.velocity.x);
+        }
+    }
 }
@@ -0,0 +1,61 @@
+use geometry::{scalar, AStar, IsoProjection, Neighbourhood, WorldSize};
+
+use common::grid;
+
+mod common;
+
+#[test]
+fn straight_paths_without_obstacles() {
+    let map = ["....", "....", "...."];
+    let path = AStar::new(Neighbourhood::Four).find_path((0, 0), (3, 0), grid(&map)).unwrap();
+    assert_eq!(path.tiles, vec![(0, 0), (1, 0), (2, 0), (3, 0)]);
+    assert_eq!(path.cost, scalar!(3));
+}
+
+#[test]
+fn paths_go_around_walls() {
+    let map = [".#..", ".#..", "...."];
+    let path = AStar::new(Neighbourhood::Four).find_path((0, 0), (2, 0), grid(&map)).unwrap();
+    assert_eq!(path.tiles.len(), 7);
+    assert!(path.tiles.iter().all(|&(column, row)| map[row].as_bytes()[column] == b'.'));
+}
+
+#[test]
+fn costly_tiles_are_avoided_when_cheaper() {
+    let map = [".~.", "...", "..."];
+    let path = AStar::new(Neighbourhood::Four).find_path((0, 0), (2, 0), grid(&map)).unwrap();
+    assert_eq!(path.cost, scalar!(4));
+    assert!(!path.tiles.contains(&(1, 0)));
+}
+
+#[test]
+fn eight_neighbours_take_diagonals_but_not_through_corners() {
+    let map = ["...", "...", "..."];
+    let path = AStar::new(Neighbourhood::Eight).find_path((0, 0), (2, 2), grid(&map)).unwrap();
+    assert_eq!(path.tiles, vec![(0, 0), (1, 1), (2, 2)]);
+    assert!((path.cost - scalar!(2) * scalar!(2).sqrt()).abs() < scalar!(0.001));
+
+    let map = [".#", "#."];
+    assert_eq!(AStar::new(Neighbourhood::Eight).find_path((0, 0), (1, 1), grid(&map)), None);
+}
+
+#[test]
+fn unreachable_goals_and_budget() {
+    let map = ["..#.", "..#.", "..#."];
+    assert_eq!(AStar::new(Neighbourhood::Eight).find_path((0, 0), (3, 0), grid(&map)), None);
+
+    let map = ["........"];
+    let mut astar = AStar::new(Neighbourhood::Four);
+    astar.max_explored = 3;
+    assert_eq!(astar.find_path((0, 0), (7, 0), grid(&map)), None);
+}
+
+#[test]
+fn paths_become_tile_center_waypoints() {
+    let map = ["...."];
+    let path = AStar::new(Neighbourhood::Four).find_path((0, 0), (2, 0), grid(&map)).unwrap();
+    let projection = IsoProjection::new(WorldSize::new(scalar!(128), scalar!(64)), scalar!(1));
+    let waypoints = path.waypoints(&projection);
+    assert_eq!(waypoints.len(), 3);
+    assert_eq!(waypoints[1], projection.tile_center(1, 0));
+}
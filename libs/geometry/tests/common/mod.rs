@@ -0,0 +1,12 @@
+//! The fixtures shared by the tests of the searches over grids
+
+use geometry::{scalar, Scalar};
+
+/// Returns the cost of the tiles of a grid drawn with `.` (cost 1), `~` (cost 5) and `#` (blocked)
+pub fn grid<'a>(rows: &'a [&'a str]) -> impl Fn((usize, usize)) -> Option<Scalar> + 'a {
+    move |(column, row)| match rows.get(row)?.as_bytes().get(column)? {
+        b'.' => Some(scalar!(1)),
+        b'~' => Some(scalar!(5)),
+        _ => None,
+    }
+}
@@ -0,0 +1,135 @@
+use geometry::{scalar, AStar, FlowField, Neighbourhood, Real};
+
+use common::grid;
+
+mod common;
+
+/// Follows the field from the given tile until it stops
+fn follow(field: &FlowField, mut tile: (usize, usize)) -> Vec<(usize, usize)> {
+    let mut tiles = vec![tile];
+    while let Some(next) = field.next_tile(tile) {
+        tile = next;
+        tiles.push(tile);
+    }
+    tiles
+}
+
+#[test]
+fn distances_match_the_cheapest_paths() {
+    let map = ["..~...", ".#~##.", ".#....", "...#.."];
+    for neighbourhood in [Neighbourhood::Four, Neighbourhood::Eight] {
+        let mut field = FlowField::new(6, 4, neighbourhood);
+        assert!(field.update((5, 0), usize::MAX, grid(&map)));
+        for row in 0..4 {
+            for column in 0..6 {
+                let path = AStar::new(neighbourhood).find_path((column, row), (5, 0), grid(&map));
+                if grid(&map)((column, row)).is_some() {
+                    assert_eq!(field.distance((column, row)), path.map(|path| path.cost));
+                } else {
+                    assert_eq!(field.distance((column, row)), None);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn following_the_field_reaches_the_goal() {
+    let map = [".#...", ".#.#.", "...#."];
+    let mut field = FlowField::new(5, 3, Neighbourhood::Eight);
+    field.update((4, 0), usize::MAX, grid(&map));
+    let tiles = follow(&field, (0, 0));
+    assert_eq!(tiles.last(), Some(&(4, 0)));
+    assert!(tiles.iter().all(|&(column, row)| map[row].as_bytes()[column] == b'.'));
+    assert_eq!(field.next_tile((4, 0)), None);
+}
+
+#[test]
+fn unreachable_tiles_have_no_direction() {
+    let map = ["..#..", "..#.."];
+    let mut field = FlowField::new(5, 2, Neighbourhood::Eight);
+    field.update((0, 0), usize::MAX, grid(&map));
+    assert_eq!(field.distance((4, 1)), None);
+    assert_eq!(field.next_tile((4, 1)), None);
+}
+
+#[test]
+fn searches_are_spread_over_updates() {
+    let map = [".....", ".....", "....."];
+    let mut field = FlowField::new(5, 3, Neighbourhood::Four);
+    assert!(field.update((0, 0), usize::MAX, grid(&map)));
+
+    // The field keeps leading to the previous goal until the new search is complete
+    let mut updates = 1;
+    while !field.update((4, 2), 4, grid(&map)) {
+        assert_eq!(field.goal(), Some((0, 0)));
+        assert_eq!(field.next_tile((1, 0)), Some((0, 0)));
+        updates += 1;
+    }
+    assert!(updates > 1);
+    assert_eq!(field.goal(), Some((4, 2)));
+    assert_eq!(field.distance((0, 0)), Some(scalar!(6)));
+    // Nothing left to search while the goal stays put
+    assert!(field.update((4, 2), 0, grid(&map)));
+}
+
+/// Asserts that both fields give the same distances, but for rounding
+fn assert_same_distances(field: &FlowField, expected: &FlowField, (width, height): (usize, usize)) {
+    for row in 0..height {
+        for column in 0..width {
+            let distance = field.distance((column, row)).map(|distance| distance.to_f32());
+            let expected = expected.distance((column, row)).map(|distance| distance.to_f32());
+            match (distance, expected) {
+                (Some(distance), Some(expected)) => assert!((distance - expected).abs() < 1e-3, "{distance} != {expected}"),
+                (distance, expected) => assert_eq!(distance, expected, "at {:?}", (column, row)),
+            }
+        }
+    }
+}
+
+/// Returns the number of updates exploring `budget` tiles the field takes to lead to the goal
+fn updates(field: &mut FlowField, goal: (usize, usize), budget: usize, map: &[&str]) -> usize {
+    let mut updates = 1;
+    while !field.update(goal, budget, grid(map)) {
+        updates += 1;
+    }
+    updates
+}
+
+#[test]
+fn repaired_fields_match_new_ones() {
+    let map = ["..~...", ".#~##.", ".#....", "...#..", "~~..#."];
+    // Steps to the sides and across corners, onto a slower tile, onto a wall and out of it
+    let goals = [(5, 0), (4, 0), (3, 0), (2, 0), (3, 0), (3, 1), (2, 2), (3, 3), (4, 3), (4, 4), (5, 4), (0, 4), (1, 3)];
+    for neighbourhood in [Neighbourhood::Four, Neighbourhood::Eight] {
+        let mut field = FlowField::new(6, 5, neighbourhood);
+        for goal in goals {
+            assert!(field.update(goal, usize::MAX, grid(&map)));
+            let mut expected = FlowField::new(6, 5, neighbourhood);
+            expected.update(goal, usize::MAX, grid(&map));
+            assert_eq!(field.goal(), Some(goal));
+            assert_same_distances(&field, &expected, (6, 5));
+        }
+    }
+}
+
+#[test]
+fn repairs_explore_fewer_tiles() {
+    let map = vec!["...................."; 20];
+    let mut walled = map.clone();
+    walled[0] = "#...................";
+    for neighbourhood in [Neighbourhood::Four, Neighbourhood::Eight] {
+        let mut field = FlowField::new(20, 20, neighbourhood);
+        let searched = updates(&mut field, (10, 10), 10, &map);
+        let repaired = updates(&mut field, (11, 10), 10, &map);
+        let mut expected = FlowField::new(20, 20, neighbourhood);
+        expected.update((11, 10), usize::MAX, grid(&map));
+        assert_same_distances(&field, &expected, (20, 20));
+        // Only the tiles closer to the new goal than through the previous one are explored
+        assert!(repaired * 4 < searched * 3, "{repaired} updates to repair, {searched} to search");
+
+        // The costs changing, the next field is searched from scratch
+        let mut new = FlowField::new(20, 20, neighbourhood);
+        assert_eq!(updates(&mut field, (12, 10), 10, &walled), updates(&mut new, (12, 10), 10, &walled));
+    }
+}
@@ -0,0 +1,40 @@
+use geometry::{GridTraversal, Real, Scalar, TilePoint};
+
+fn cells(from: (f32, f32), to: (f32, f32)) -> Vec<(i32, i32)> {
+    let tile = |(x, y): (f32, f32)| TilePoint::new(Scalar::from_f32(x), Scalar::from_f32(y));
+    GridTraversal::new(tile(from), tile(to)).collect()
+}
+
+#[test]
+fn a_point_covers_a_single_cell() {
+    assert_eq!(cells((2.5, 3.5), (2.7, 3.1)), vec![(2, 3)]);
+}
+
+#[test]
+fn straight_segments_cross_each_cell_in_order() {
+    assert_eq!(cells((0.5, 1.5), (3.5, 1.5)), vec![(0, 1), (1, 1), (2, 1), (3, 1)]);
+    assert_eq!(cells((0.5, 1.5), (0.5, -1.5)), vec![(0, 1), (0, 0), (0, -1), (0, -2)]);
+}
+
+#[test]
+fn sloped_segments_cross_every_touched_cell() {
+    assert_eq!(
+        cells((0.5, 0.2), (3.5, 1.5)),
+        vec![(0, 0), (1, 0), (2, 0), (2, 1), (3, 1)],
+    );
+    assert_eq!(
+        cells((3.5, 1.5), (0.5, 0.2)),
+        vec![(3, 1), (2, 1), (2, 0), (1, 0), (0, 0)],
+    );
+}
+
+#[test]
+fn consecutive_cells_are_adjacent_and_end_on_the_target() {
+    let traversal = cells((-4.2, 7.9), (12.3, -3.4));
+    assert_eq!(traversal.first(), Some(&(-5, 7)));
+    assert_eq!(traversal.last(), Some(&(12, -4)));
+    for pair in traversal.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        assert_eq!((a.0 - b.0).abs() + (a.1 - b.1).abs(), 1);
+    }
+}
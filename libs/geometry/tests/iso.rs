@@ -0,0 +1,56 @@
+use geometry::{scalar, IsoProjection, Point, Real, Scalar, ScreenPoint, TilePoint, WorldPoint, WorldSize};
+
+const TILE_SIZE: WorldSize = WorldSize::new(scalar!(128), scalar!(64));
+const SCALE: Scalar = scalar!(0.6);
+
+fn assert_close<S>(a: Point<S>, b: Point<S>) {
+    assert!((a.x - b.x).abs() < scalar!(0.001) && (a.y - b.y).abs() < scalar!(0.001), "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn tile_to_world_follows_the_diamond_axes() {
+    let iso = IsoProjection::new(TILE_SIZE, SCALE);
+    assert_close(iso.tile_to_world(TilePoint::new(scalar!(0), scalar!(0))), WorldPoint::new(scalar!(0), scalar!(0)));
+    assert_close(iso.tile_to_world(TilePoint::new(scalar!(1), scalar!(0))), WorldPoint::new(scalar!(64), scalar!(32)));
+    assert_close(iso.tile_to_world(TilePoint::new(scalar!(0), scalar!(1))), WorldPoint::new(scalar!(-64), scalar!(32)));
+    assert_close(iso.tile_center(0, 0), WorldPoint::new(scalar!(0), scalar!(32)));
+}
+
+#[test]
+fn world_and_tile_round_trip() {
+    let iso = IsoProjection::centered(TILE_SIZE, SCALE, 16);
+    for &(x, y) in &[(0.0, 0.0), (3.25, 7.5), (15.9, 0.1), (-2.0, 4.0)] {
+        let tile = TilePoint::new(Scalar::from_f32(x), Scalar::from_f32(y));
+        assert_close(iso.world_to_tile(iso.tile_to_world(tile)), tile);
+    }
+}
+
+#[test]
+fn screen_and_world_round_trip() {
+    let iso = IsoProjection::centered(TILE_SIZE, SCALE, 16);
+    let screen_origin = ScreenPoint::new(scalar!(700), scalar!(500));
+    let world = WorldPoint::new(scalar!(-120), scalar!(48));
+    let screen = iso.world_to_screen(world, screen_origin);
+    assert_close(screen, ScreenPoint::new(scalar!(700) - scalar!(72), scalar!(500) + scalar!(28.8)));
+    assert_close(iso.screen_to_world(screen, screen_origin), world);
+}
+
+#[test]
+fn centered_projection_puts_the_map_center_at_the_origin() {
+    let iso = IsoProjection::centered(TILE_SIZE, SCALE, 16);
+    assert_close(iso.tile_to_world(TilePoint::new(scalar!(8), scalar!(8))), WorldPoint::new(scalar!(0), scalar!(0)));
+    assert_eq!(iso.tile_index(WorldPoint::new(scalar!(0), scalar!(1))), (8, 8));
+}
+
+#[test]
+fn tile_index_picks_the_tile_under_the_cursor() {
+    let iso = IsoProjection::centered(TILE_SIZE, SCALE, 16);
+    let screen_origin = ScreenPoint::new(scalar!(700), scalar!(500));
+    for column in 0..16 {
+        for row in 0..16 {
+            let center = iso.tile_to_screen(TilePoint::new(Scalar::from_f32(column as f32 + 0.5), Scalar::from_f32(row as f32 + 0.5)), screen_origin);
+            let world = iso.screen_to_world(center, screen_origin);
+            assert_eq!(iso.tile_index(world), (column, row));
+        }
+    }
+}
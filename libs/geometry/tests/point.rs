@@ -0,0 +1,8 @@
+use geometry::{scalar, WorldPoint};
+
+#[test]
+fn scaling_a_point_scales_both_coordinates() {
+    let point = WorldPoint::new(scalar!(2), scalar!(3));
+    assert_eq!(point * scalar!(2), WorldPoint::new(scalar!(4), scalar!(6)));
+    assert_eq!(point / scalar!(2), WorldPoint::new(scalar!(1), scalar!(1.5)));
+}
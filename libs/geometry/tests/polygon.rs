@@ -0,0 +1,51 @@
+use rand::{rngs::StdRng, SeedableRng};
+
+use geometry::{scalar, WorldPoint, WorldPolygon, WorldRect};
+
+fn diamond() -> WorldPolygon {
+    WorldPolygon::new(vec![
+        WorldPoint::new(scalar!(0), scalar!(-32)),
+        WorldPoint::new(scalar!(64), scalar!(0)),
+        WorldPoint::new(scalar!(0), scalar!(32)),
+        WorldPoint::new(scalar!(-64), scalar!(0)),
+    ])
+}
+
+#[test]
+fn contains_only_points_inside_the_outline() {
+    let diamond = diamond();
+    assert!(diamond.contains(WorldPoint::new(scalar!(0), scalar!(0))));
+    assert!(diamond.contains(WorldPoint::new(scalar!(30), scalar!(10))));
+    assert!(!diamond.contains(WorldPoint::new(scalar!(60), scalar!(28))));
+    assert!(!diamond.contains(WorldPoint::new(scalar!(-100), scalar!(0))));
+    assert_eq!(diamond.area(), scalar!(64) * scalar!(128) / scalar!(2));
+}
+
+#[test]
+fn closest_point_is_on_the_nearest_edge() {
+    let square = WorldPolygon::from(WorldRect::new(scalar!(0), scalar!(0), scalar!(10), scalar!(10)));
+    assert_eq!(square.closest_point(WorldPoint::new(scalar!(15), scalar!(5))), WorldPoint::new(scalar!(10), scalar!(5)));
+    assert_eq!(square.closest_point(WorldPoint::new(scalar!(-3), scalar!(-4))), WorldPoint::new(scalar!(0), scalar!(0)));
+    assert_eq!(square.confine(WorldPoint::new(scalar!(2), scalar!(3))), WorldPoint::new(scalar!(2), scalar!(3)));
+}
+
+#[test]
+fn random_points_land_inside() {
+    let mut rng = StdRng::seed_from_u64(3);
+    let diamond = diamond();
+    for _ in 0..1000 {
+        let point = diamond.random_point(&mut rng);
+        assert!(diamond.contains(point) || diamond.closest_point(point).squared_distance_to(point) < scalar!(0.000001));
+    }
+}
+
+#[test]
+fn clipping_keeps_the_overlapping_part() {
+    let square = WorldPolygon::from(WorldRect::new(scalar!(0), scalar!(-100), scalar!(100), scalar!(200)));
+    let clipped = diamond().clip(&square).unwrap();
+    assert_eq!(clipped.area(), diamond().area() / scalar!(2));
+    assert!(clipped.vertices().iter().all(|vertex| vertex.x >= scalar!(0)));
+
+    let far = WorldPolygon::from(WorldRect::new(scalar!(500), scalar!(500), scalar!(10), scalar!(10)));
+    assert_eq!(diamond().clip(&far), None);
+}
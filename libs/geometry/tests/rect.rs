@@ -0,0 +1,67 @@
+use geometry::{scalar, WorldPoint, WorldRect, WorldSize};
+
+#[test]
+fn overlapping_rects_intersect_and_unite() {
+    let a = WorldRect::new(scalar!(0), scalar!(0), scalar!(10), scalar!(10));
+    let b = WorldRect::new(scalar!(5), scalar!(-5), scalar!(10), scalar!(10));
+    assert!(a.intersects(&b));
+    assert_eq!(a.intersection(&b), Some(WorldRect::new(scalar!(5), scalar!(0), scalar!(5), scalar!(5))));
+    assert_eq!(a.union(&b), WorldRect::new(scalar!(0), scalar!(-5), scalar!(15), scalar!(15)));
+}
+
+#[test]
+fn touching_rects_do_not_intersect() {
+    let a = WorldRect::new(scalar!(0), scalar!(0), scalar!(10), scalar!(10));
+    let b = WorldRect::new(scalar!(10), scalar!(0), scalar!(10), scalar!(10));
+    assert!(!a.intersects(&b));
+    assert_eq!(a.intersection(&b), None);
+}
+
+#[test]
+fn points_are_contained_and_clamped() {
+    let rect = WorldRect::from_center(WorldPoint::new(scalar!(0), scalar!(0)), WorldSize::new(scalar!(20), scalar!(10)));
+    assert_eq!(rect.min(), WorldPoint::new(scalar!(-10), scalar!(-5)));
+    assert!(rect.contains(WorldPoint::new(scalar!(10), scalar!(5))));
+    assert!(!rect.contains(WorldPoint::new(scalar!(10.1), scalar!(0))));
+    assert_eq!(rect.clamp(WorldPoint::new(scalar!(30), scalar!(-1))), WorldPoint::new(scalar!(10), scalar!(-1)));
+    assert_eq!(rect.expand(scalar!(1)).size(), WorldSize::new(scalar!(22), scalar!(12)));
+}
+
+#[test]
+fn bounding_rect_covers_all_points() {
+    let diamond = [
+        WorldPoint::new(scalar!(0), scalar!(-32)),
+        WorldPoint::new(scalar!(64), scalar!(0)),
+        WorldPoint::new(scalar!(0), scalar!(32)),
+        WorldPoint::new(scalar!(-64), scalar!(0)),
+    ];
+    assert_eq!(WorldRect::bounding(diamond), Some(WorldRect::new(scalar!(-64), scalar!(-32), scalar!(128), scalar!(64))));
+    assert_eq!(WorldRect::bounding(Vec::new()), None);
+}
+
+#[cfg(feature = "mint")]
+#[test]
+fn screen_points_and_sizes_round_trip_through_mint() {
+    use geometry::{ScreenPoint, ScreenSize};
+
+    let point = ScreenPoint::new(scalar!(12.5), scalar!(-3));
+    let mint_point: mint::Point2<f32> = point.into();
+    assert_eq!((mint_point.x, mint_point.y), (12.5, -3.0));
+    assert_eq!(ScreenPoint::from(mint_point), point);
+
+    let size = ScreenSize::new(scalar!(800), scalar!(600));
+    let mint_size: mint::Vector2<f32> = size.into();
+    assert_eq!((mint_size.x, mint_size.y), (800.0, 600.0));
+    assert_eq!(ScreenSize::from(mint_size), size);
+}
+
+#[cfg(feature = "ggez")]
+#[test]
+fn screen_rects_round_trip_through_ggez() {
+    use geometry::ScreenRect;
+
+    let rect = ScreenRect::new(scalar!(10), scalar!(20), scalar!(30.5), scalar!(40));
+    let ggez_rect: ggez::graphics::Rect = rect.into();
+    assert_eq!(ggez_rect, ggez::graphics::Rect::new(10.0, 20.0, 30.5, 40.0));
+    assert_eq!(ScreenRect::from(ggez_rect), rect);
+}
@@ -0,0 +1,54 @@
+use rand::{rngs::StdRng, SeedableRng};
+
+use geometry::{scalar, steering, Real, Scalar, Vector, WorldPoint, WorldSize};
+
+fn assert_close(a: Scalar, b: f32) {
+    assert!((a.to_f32() - b).abs() < 1e-4, "{} != {}", a, b);
+}
+
+#[test]
+fn trigonometry_matches_the_float_functions() {
+    for step in -40..=40 {
+        let angle = step as f32 * 0.2;
+        assert_close(Scalar::from_f32(angle).sin(), angle.sin());
+        assert_close(Scalar::from_f32(angle).cos(), angle.cos());
+    }
+    for &(y, x) in &[(1.0, 2.0), (-3.0, 0.5), (0.25, -4.0), (-1.0, -1.0), (2.0, 0.0), (0.0, -2.0)] {
+        let (y, x): (f32, f32) = (y, x);
+        assert_close(Scalar::from_f32(y).atan2(Scalar::from_f32(x)), y.atan2(x));
+    }
+}
+
+/// Runs a few seconds of agents wandering around and chasing each other, and hashes the result
+fn simulate(seed: u64) -> Vec<u64> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let bounds = WorldSize::new(scalar!(800), scalar!(600));
+    let mut agents: Vec<Vector> = (0..20).map(|_| Vector::random(&mut rng, bounds)).collect();
+    let (dt, max_speed) = (scalar!(0.016), scalar!(120));
+
+    for _ in 0..300 {
+        let leader = agents[0].clone();
+        for agent in &mut agents[1..] {
+            let force = steering::pursuit(agent, &leader, max_speed)
+                + steering::wander(agent, &mut rng, max_speed, scalar!(40), scalar!(20), scalar!(0.5));
+            agent.apply_force(force);
+            agent.limit_speed(max_speed);
+            agent.advance(dt);
+        }
+        let force = steering::seek(&agents[0], WorldPoint::new(scalar!(400), scalar!(300)), max_speed);
+        agents[0].apply_force(force);
+        agents[0].advance(dt);
+    }
+
+    agents
+        .iter()
+        .flat_map(|agent| [agent.position.x, agent.position.y, agent.direction])
+        .map(Real::bits)
+        .collect()
+}
+
+#[test]
+fn seeded_simulations_give_the_same_results() {
+    assert_eq!(simulate(42), simulate(42));
+    assert_ne!(simulate(42), simulate(43));
+}
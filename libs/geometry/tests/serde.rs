@@ -0,0 +1,66 @@
+#![cfg(feature = "serde")]
+
+use std::fmt::Debug;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use geometry::{scalar, IsoProjection, Point, Shape, Vector, WorldPoint, WorldPolygon, WorldRect, WorldSize};
+
+/// Writes the value to RON, reads it back and returns the RON text
+fn round_trip<T: Serialize + DeserializeOwned + PartialEq + Debug>(value: &T) -> String {
+    let text = ron::to_string(value).unwrap();
+    let back: T = ron::from_str(&text).unwrap();
+    assert_eq!(&back, value);
+    text
+}
+
+#[test]
+fn points_sizes_and_rects_are_tuples() {
+    let point = WorldPoint::new(scalar!(1.5), scalar!(-2));
+    assert_eq!(round_trip(&point), "(1.5,-2.0)");
+
+    let size = WorldSize::new(scalar!(128), scalar!(64));
+    assert_eq!(round_trip(&size), "(128.0,64.0)");
+
+    let rect = WorldRect::new(scalar!(-4), scalar!(8), scalar!(16), scalar!(0.25));
+    assert_eq!(round_trip(&rect), "(-4.0,8.0,16.0,0.25)");
+}
+
+#[test]
+fn polygons_and_shapes_round_trip() {
+    let polygon = WorldPolygon::new(vec![
+        Point::new(scalar!(0), scalar!(-32)),
+        Point::new(scalar!(64), scalar!(0)),
+        Point::new(scalar!(0), scalar!(32)),
+    ]);
+    assert_eq!(round_trip(&polygon), "[(0.0,-32.0),(64.0,0.0),(0.0,32.0)]");
+
+    round_trip(&Shape::Circle(scalar!(12.5)));
+    round_trip(&Shape::diamond(WorldSize::new(scalar!(128), scalar!(64))));
+}
+
+#[test]
+fn polygons_need_three_vertices() {
+    assert!(ron::from_str::<WorldPolygon>("[(0.0, 0.0), (1.0, 1.0)]").is_err());
+}
+
+#[test]
+fn vectors_round_trip_and_default_their_motion() {
+    let mut vector = Vector::new(Point::new(scalar!(10), scalar!(20)), scalar!(0.5));
+    vector.velocity = Point::new(scalar!(3), scalar!(-4));
+    let text = ron::to_string(&vector).unwrap();
+    let back: Vector = ron::from_str(&text).unwrap();
+    assert_eq!((back.position, back.direction, back.velocity), (vector.position, vector.direction, vector.velocity));
+
+    let resting: Vector = ron::from_str("(position: (1.0, 2.0), direction: 0.0)").unwrap();
+    assert_eq!(resting.velocity, Point::default());
+    assert_eq!(resting.acceleration, Point::default());
+}
+
+#[test]
+fn projections_round_trip() {
+    let projection = IsoProjection::centered(WorldSize::new(scalar!(128), scalar!(64)), scalar!(0.5), 20);
+    let text = ron::to_string(&projection).unwrap();
+    let back: IsoProjection = ron::from_str(&text).unwrap();
+    assert_eq!((back.tile_size, back.scale, back.origin), (projection.tile_size, projection.scale, projection.origin));
+}
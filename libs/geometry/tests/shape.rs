@@ -0,0 +1,44 @@
+use geometry::{scalar, Real, Scalar, Shape, WorldPoint, WorldSize};
+
+fn point(x: f32, y: f32) -> WorldPoint {
+    WorldPoint::new(Scalar::from_f32(x), Scalar::from_f32(y))
+}
+
+#[test]
+fn circles_overlap_when_closer_than_their_radii() {
+    let circle = Shape::Circle(scalar!(6));
+    let contact = circle.contact(point(0.0, 0.0), &circle, point(10.0, 0.0)).unwrap();
+    assert_eq!(contact.normal, point(1.0, 0.0));
+    assert!((contact.depth - scalar!(2)).abs() < scalar!(0.00001));
+    assert!(!circle.overlaps(point(0.0, 0.0), &circle, point(12.0, 0.0)));
+}
+
+#[test]
+fn boxes_separate_along_the_shallowest_axis() {
+    let square = Shape::Aabb(WorldSize::new(scalar!(10), scalar!(10)));
+    let contact = square.contact(point(0.0, 0.0), &square, point(8.0, 3.0)).unwrap();
+    assert_eq!(contact.normal, point(1.0, 0.0));
+    assert!((contact.depth - scalar!(2)).abs() < scalar!(0.00001));
+    assert!(!square.overlaps(point(0.0, 0.0), &square, point(0.0, 10.5)));
+}
+
+#[test]
+fn diamonds_only_collide_inside_their_outline() {
+    let tile = Shape::diamond(WorldSize::new(scalar!(128), scalar!(64)));
+    let circle = Shape::Circle(scalar!(5));
+    // The corner of the bounding box of the tile is outside of the diamond
+    assert!(!tile.overlaps(point(0.0, 0.0), &circle, point(60.0, 28.0)));
+    assert!(tile.overlaps(point(0.0, 0.0), &circle, point(30.0, 10.0)));
+}
+
+#[test]
+fn contact_normal_points_from_the_first_shape_to_the_second() {
+    let circle = Shape::Circle(scalar!(5));
+    let square = Shape::Aabb(WorldSize::new(scalar!(10), scalar!(10)));
+    let contact = circle.contact(point(0.0, -8.0), &square, point(0.0, 0.0)).unwrap();
+    assert_eq!(contact.normal, point(0.0, 1.0));
+    assert!((contact.depth - scalar!(2)).abs() < scalar!(0.00001));
+
+    let contact = square.contact(point(0.0, 0.0), &circle, point(0.0, -8.0)).unwrap();
+    assert_eq!(contact.normal, point(0.0, -1.0));
+}
@@ -0,0 +1,100 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use geometry::{scalar, Real, Scalar, SpatialGrid, WorldPoint};
+
+fn random_circles(rng: &mut StdRng, count: usize) -> Vec<(WorldPoint, Scalar)> {
+    (0..count)
+        .map(|_| {
+            let mut random = |min: f32, max: f32| Scalar::from_f32(rng.gen_range(min..max));
+            let position = WorldPoint::new(random(-500.0, 500.0), random(-500.0, 500.0));
+            (position, random(1.0, 40.0))
+        })
+        .collect()
+}
+
+fn overlap(a: &(WorldPoint, Scalar), b: &(WorldPoint, Scalar)) -> bool {
+    let radii = a.1 + b.1;
+    a.0.squared_distance_to(b.0) < radii * radii
+}
+
+fn grid_of(circles: &[(WorldPoint, Scalar)]) -> SpatialGrid<usize> {
+    let mut grid = SpatialGrid::new(scalar!(32));
+    for (index, &(position, radius)) in circles.iter().enumerate() {
+        grid.insert(index, position, radius);
+    }
+    grid
+}
+
+#[test]
+fn colliding_pairs_match_brute_force() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let circles = random_circles(&mut rng, 500);
+
+    let mut expected = vec![];
+    for a in 0..circles.len() {
+        for b in a + 1..circles.len() {
+            if overlap(&circles[a], &circles[b]) {
+                expected.push((a, b));
+            }
+        }
+    }
+
+    assert!(!expected.is_empty());
+    assert_eq!(grid_of(&circles).colliding_pairs(), expected);
+}
+
+#[test]
+fn radius_queries_match_brute_force() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let circles = random_circles(&mut rng, 300);
+    let grid = grid_of(&circles);
+
+    for query in random_circles(&mut rng, 50) {
+        let expected: Vec<usize> = (0..circles.len())
+            .filter(|&index| overlap(&circles[index], &query))
+            .collect();
+        assert_eq!(grid.query_radius(query.0, query.1), expected);
+    }
+}
+
+#[test]
+fn moved_and_removed_objects_are_kept_up_to_date() {
+    let mut grid = SpatialGrid::new(scalar!(32));
+    grid.insert(1, WorldPoint::new(scalar!(0), scalar!(0)), scalar!(5));
+    grid.insert(2, WorldPoint::new(scalar!(8), scalar!(0)), scalar!(5));
+    assert_eq!(grid.colliding_pairs(), vec![(1, 2)]);
+    assert_eq!(grid.neighbours(1, scalar!(0)), vec![2]);
+
+    grid.insert(2, WorldPoint::new(scalar!(300), scalar!(0)), scalar!(5));
+    assert!(grid.colliding_pairs().is_empty());
+    assert!(grid.query_radius(WorldPoint::new(scalar!(8), scalar!(0)), scalar!(1)).is_empty());
+
+    assert!(grid.remove(1));
+    assert!(!grid.remove(1));
+    assert_eq!(grid.len(), 1);
+    assert_eq!(grid.query_radius(WorldPoint::new(scalar!(300), scalar!(0)), scalar!(1)), vec![2]);
+}
+
+#[test]
+fn moving_every_object_matches_a_rebuilt_grid() {
+    let mut rng = StdRng::seed_from_u64(3);
+    let circles = random_circles(&mut rng, 300);
+    let mut grid = grid_of(&circles);
+
+    // Small steps keep most objects in their cells, a few cross into the next ones
+    let moved: Vec<(WorldPoint, Scalar)> = circles
+        .iter()
+        .map(|&(position, radius)| {
+            let step = Scalar::from_f32(rng.gen_range(-20.0..20.0));
+            (WorldPoint::new(position.x + step, position.y - step), radius)
+        })
+        .collect();
+    for (index, &(position, radius)) in moved.iter().enumerate() {
+        grid.insert(index, position, radius);
+    }
+    assert_eq!(grid.colliding_pairs(), grid_of(&moved).colliding_pairs());
+
+    grid.retain(|&index| index < 100);
+    assert_eq!(grid.len(), 100);
+    assert_eq!(grid.colliding_pairs(), grid_of(&moved[..100]).colliding_pairs());
+}
@@ -0,0 +1,95 @@
+use geometry::{scalar, steering, Real, Scalar, Vector, WorldPoint};
+
+fn assert_close(a: Scalar, b: f32) {
+    assert!((a.to_f32() - b).abs() < 1e-3, "{} != {}", a, b);
+}
+
+fn point(x: f32, y: f32) -> WorldPoint {
+    WorldPoint::new(Scalar::from_f32(x), Scalar::from_f32(y))
+}
+
+/// A vector at the origin, moving at the given velocity
+fn moving(x: f32, y: f32) -> Vector {
+    let mut vector = Vector::new(point(0.0, 0.0), Scalar::ZERO);
+    vector.velocity = point(x, y);
+    vector
+}
+
+#[test]
+fn seek_steers_towards_the_target_at_full_speed() {
+    let force = steering::seek(&moving(0.0, 10.0), point(100.0, 0.0), scalar!(50));
+    // The desired velocity (50, 0) minus the current one
+    assert_close(force.x, 50.0);
+    assert_close(force.y, -10.0);
+}
+
+#[test]
+fn flee_steers_away_from_the_threat_at_full_speed() {
+    let force = steering::flee(&moving(0.0, 0.0), point(0.0, 30.0), scalar!(50));
+    assert_close(force.x, 0.0);
+    assert_close(force.y, -50.0);
+}
+
+#[test]
+fn arrive_slows_down_within_the_slowing_radius() {
+    let far = steering::arrive(&moving(0.0, 0.0), point(200.0, 0.0), scalar!(50), scalar!(100));
+    assert_close(far.x, 50.0);
+
+    // Halfway into the slowing radius, the desired speed is halved
+    let near = steering::arrive(&moving(0.0, 0.0), point(50.0, 0.0), scalar!(50), scalar!(100));
+    assert_close(near.x, 25.0);
+
+    // On the target at rest, there is nothing to do
+    let there = steering::arrive(&moving(0.0, 0.0), point(0.0, 0.0), scalar!(50), scalar!(100));
+    assert_close(there.length(), 0.0);
+}
+
+#[test]
+fn separation_pushes_away_from_close_neighbours_only() {
+    let vector = moving(0.0, 0.0);
+    let force = steering::separation(&vector, &[point(10.0, 0.0), point(0.0, 500.0)], scalar!(24), scalar!(50));
+    assert_close(force.x, -50.0);
+    assert_close(force.y, 0.0);
+
+    let alone = steering::separation(&vector, &[point(100.0, 0.0)], scalar!(24), scalar!(50));
+    assert_close(alone.length(), 0.0);
+}
+
+#[test]
+fn advance_integrates_the_acceleration_then_the_velocity() {
+    let mut vector = moving(10.0, 0.0);
+    vector.apply_force(point(0.0, 20.0));
+    vector.advance(scalar!(0.5));
+
+    assert_close(vector.velocity.x, 10.0);
+    assert_close(vector.velocity.y, 10.0);
+    assert_close(vector.position.x, 5.0);
+    assert_close(vector.position.y, 5.0);
+    // The direction follows the velocity, and forces only last one advance
+    assert_close(vector.direction, std::f32::consts::FRAC_PI_4);
+    assert_close(vector.acceleration.length(), 0.0);
+}
+
+#[test]
+fn limit_speed_keeps_the_direction_of_the_velocity() {
+    let mut vector = moving(30.0, 40.0);
+    vector.limit_speed(scalar!(10));
+    assert_close(vector.velocity.x, 6.0);
+    assert_close(vector.velocity.y, 8.0);
+
+    vector.limit_speed(scalar!(100));
+    assert_close(vector.speed(), 10.0);
+}
+
+#[test]
+fn advance_limited_never_moves_faster_than_the_limit() {
+    let mut vector = moving(0.0, 0.0);
+    for _ in 0..10 {
+        vector.apply_force(point(1000.0, 0.0));
+        let before = vector.position;
+        vector.advance_limited(scalar!(0.1), scalar!(20));
+        assert!((vector.position - before).length() <= scalar!(2.001));
+        assert!(vector.speed() <= scalar!(20.001));
+    }
+    assert_close(vector.position.x, 20.0);
+}
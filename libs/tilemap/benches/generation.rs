@@ -0,0 +1,51 @@
+//! Time taken to build a generated map, when the game starts and when it is reset after a game
+//! over
+//!
+//! The game starts with `Map::new` and resets by building the map again from its source, with the
+//! same parameters.
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use geometry::{scalar, IsoProjection, Scalar, WorldSize};
+use tilemap::{GeneratorKind, Map, MapAssets, MapGenConfig, MapSource};
+
+const TILE_SIZE: WorldSize = WorldSize::new(scalar!(128.0), scalar!(64.0));
+const SCALE: Scalar = scalar!(0.6);
+/// The number of columns and rows of the island
+const SIZE: usize = 256;
+
+fn assets() -> Arc<MapAssets> {
+    Arc::new(MapAssets::load(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../resources")))
+}
+
+fn projection() -> IsoProjection {
+    IsoProjection::new(TILE_SIZE, SCALE)
+}
+
+fn generation(c: &mut Criterion) {
+    let assets = assets();
+    let endless = MapGenConfig { seed: Some(42), ..MapGenConfig::default() };
+    let island = MapSource::Generated(MapGenConfig {
+        seed: Some(42),
+        generator: GeneratorKind::Island,
+        size: (SIZE, SIZE),
+        ..MapGenConfig::default()
+    });
+
+    let mut group = c.benchmark_group("map generation");
+    group.sample_size(10);
+    group.bench_function("endless start", |b| {
+        b.iter(|| Map::new(black_box(&endless), assets.clone(), projection(), &mut StdRng::seed_from_u64(0)))
+    });
+    group.bench_function(format!("island {}x{} reset", SIZE, SIZE), |b| {
+        b.iter(|| Map::from_source(black_box(&island), assets.clone(), projection(), &mut StdRng::seed_from_u64(0)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, generation);
+criterion_main!(benches);
@@ -0,0 +1,144 @@
+//! Memory and build time of the tiles of a 1024x1024 map, stored as frame IDs in flat layers
+//! against the former layout of a full tile per cell
+//!
+//! Both build the landscape layers of every chunk from the same map file, and nothing else: the
+//! towers and the rest of the map are left out of the timing.
+//!
+//! The former layout kept, for each cell of each chunk, the position of the tile, the name of its
+//! frame, its sprite and what it means to the game, in nested vectors.
+use std::mem::size_of;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use geometry::{scalar, Real, Scalar, TilePoint};
+use tilemap::{AtlasContent, Chunk, Layer, LayerFile, MapAssets, MapFile, TileInfo, CHUNK_SIZE};
+
+const SIZE: usize = 1024;
+const SCALE: Scalar = scalar!(0.6);
+
+/// The sprite the former tiles held: a rectangle of the atlas, a scale and two dimensions
+#[allow(dead_code)]
+struct LegacySprite {
+    rect: [f32; 4],
+    scale: [f32; 2],
+    width: f32,
+    height: f32,
+}
+
+/// A tile of the former layout
+#[allow(dead_code)]
+struct LegacyTile {
+    position: TilePoint,
+    frame: String,
+    sprite: LegacySprite,
+    info: TileInfo,
+}
+
+/// A layer of a chunk in the former layout, by column then row
+type LegacyLayer = Vec<Vec<Option<LegacyTile>>>;
+
+fn assets() -> Arc<MapAssets> {
+    Arc::new(MapAssets::load(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../resources")))
+}
+
+/// A map file covered with every landscape frame, spread over the map
+fn map_file(assets: &MapAssets) -> MapFile {
+    let mut file = MapFile::new(None, (0, 0), (SIZE, SIZE));
+    file.frames = assets.atlas(AtlasContent::Landscape).frames.iter().map(|(name, _)| name.clone()).collect();
+    let frames = file.frames.len();
+    let tiles = (0..SIZE)
+        .map(|row| (0..SIZE).map(|column| ((column * 7919 + row * 104729) % frames + 1) as u16).collect())
+        .collect();
+    file.layers.push(LayerFile { level: 0, tiles });
+    file
+}
+
+/// The index of every chunk of the map with each layer of the file
+fn chunk_layers(file: &MapFile) -> impl Iterator<Item = ((i32, i32), &LayerFile)> {
+    let chunks = (SIZE / CHUNK_SIZE) as i32;
+    (0..chunks)
+        .flat_map(move |cx| (0..chunks).map(move |cy| (cx, cy)))
+        .flat_map(|index| file.layers.iter().map(move |layer| (index, layer)))
+}
+
+/// Builds the landscape layers of the chunks of the map file, as frame IDs
+fn compact_chunks(file: &MapFile, assets: &MapAssets) -> Vec<Layer> {
+    chunk_layers(file)
+        .map(|(index, layer)| {
+            let (left, top) = Chunk::origin_of(index);
+            Chunk::landscape_layer(layer.level, |x, y| {
+                let frame = file.frame(layer, (left + x as i32, top + y as i32))?;
+                assets.frames.id(AtlasContent::Landscape, frame)
+            })
+        })
+        .collect()
+}
+
+/// Builds the chunks of the map file in the former layout
+fn legacy_chunks(file: &MapFile, assets: &MapAssets) -> Vec<LegacyLayer> {
+    let landscape = assets.atlas(AtlasContent::Landscape);
+    chunk_layers(file)
+        .map(|(index, layer)| {
+            let (left, top) = Chunk::origin_of(index);
+            (0..CHUNK_SIZE as i32)
+                .map(|x| {
+                    (0..CHUNK_SIZE as i32)
+                        .map(|y| {
+                            let (column, row) = (left + x, top + y);
+                            let frame = file.frame(layer, (column, row))?;
+                            let &(_, (fx, fy)) = landscape.frames.iter().find(|(name, _)| name == frame)?;
+                            Some(LegacyTile {
+                                position: TilePoint::new(Scalar::from_f32(column as f32), Scalar::from_f32(row as f32)),
+                                frame: frame.to_string(),
+                                sprite: LegacySprite {
+                                    rect: [fx as f32, fy as f32, 128.0, 128.0],
+                                    scale: [SCALE.to_f32(); 2],
+                                    width: 128.0,
+                                    height: 128.0,
+                                },
+                                info: assets.tiles.get(frame),
+                            })
+                        })
+                        .collect()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The bytes held by the layers of the former layout
+fn legacy_bytes(layers: &[LegacyLayer]) -> usize {
+    layers
+        .iter()
+        .flatten()
+        .map(|column| {
+            size_of::<Vec<Option<LegacyTile>>>()
+                + column.capacity() * size_of::<Option<LegacyTile>>()
+                + column.iter().flatten().map(|tile| tile.frame.capacity()).sum::<usize>()
+        })
+        .sum()
+}
+
+/// The bytes held by the layers of frame IDs
+fn compact_bytes(layers: &[Layer]) -> usize {
+    layers.iter().map(|layer| layer.tiles.capacity() * size_of::<u16>()).sum()
+}
+
+fn layout(c: &mut Criterion) {
+    let assets = assets();
+    let file = map_file(&assets);
+
+    let legacy = legacy_bytes(&legacy_chunks(&file, &assets));
+    let compact = compact_bytes(&compact_chunks(&file, &assets));
+    println!("tiles of a {}x{} map: {:.1} MB as full tiles, {:.1} MB as frame IDs", SIZE, SIZE, legacy as f64 / 1e6, compact as f64 / 1e6);
+
+    let mut group = c.benchmark_group("map layout 1024x1024");
+    group.sample_size(10);
+    group.bench_function("full tiles", |b| b.iter(|| legacy_chunks(black_box(&file), &assets)));
+    group.bench_function("frame ids", |b| b.iter(|| compact_chunks(black_box(&file), &assets)));
+    group.finish();
+}
+
+criterion_group!(benches, layout);
+criterion_main!(benches);
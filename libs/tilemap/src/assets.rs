@@ -0,0 +1,111 @@
+use std::fs::File;
+use std::path::Path;
+
+use ron::de::from_reader;
+use serde::Deserialize;
+
+use crate::{BiomeTable, Faction, FrameTable, TileTable};
+
+/// An atlas of the game, with the name of each of its frames
+#[derive(Debug, Clone)]
+pub struct Atlas {
+    /// The path of the atlas image, only its file name is compared with the tileset images of
+    /// Tiled maps
+    pub image: String,
+    /// What the frames of the atlas are
+    pub content: AtlasContent,
+    /// The name of each frame, with the top left corner of the frame in the image
+    pub frames: Vec<(String, (i32, i32))>,
+}
+
+impl Atlas {
+    /// Returns true if the atlas has a frame of the given name
+    pub fn has_frame(&self, name: &str) -> bool {
+        self.frames.iter().any(|(frame, _)| frame == name)
+    }
+
+    /// Returns the name of the frame at the given index
+    pub fn frame_name(&self, index: usize) -> Option<&str> {
+        self.frames.get(index).map(|(frame, _)| frame.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AtlasContent {
+    /// Tiles of the landscape layers
+    Landscape,
+    /// Towers of the given faction
+    Towers(Faction),
+}
+
+/// What maps are made of, loaded from the resources directory without any image: the frames of
+/// the atlases (`atlas.ron`), the biome table (`biomes.ron`) and the tile table (`tiles.ron`)
+#[derive(Debug)]
+pub struct MapAssets {
+    pub atlases: Vec<Atlas>,
+    pub biomes: BiomeTable,
+    pub tiles: TileTable,
+    /// The frames of every atlas, numbered for the layers to store frame IDs
+    pub frames: FrameTable,
+}
+
+impl MapAssets {
+    /// Loads the assets from the given resources directory
+    pub fn load(directory: impl AsRef<Path>) -> MapAssets {
+        #[derive(Deserialize)]
+        enum Type {
+            Landscape,
+            TowersBrown,
+            TowersGrey,
+            TowersRed,
+        }
+        #[derive(Deserialize)]
+        struct FrameInfo {
+            name: String,
+            x: i32,
+            y: i32,
+        }
+        #[derive(Deserialize)]
+        struct AtlasInfo {
+            name: Type,
+            path: String,
+            frames: Vec<FrameInfo>,
+        }
+
+        let directory = directory.as_ref();
+        let file = File::open(directory.join("atlas.ron")).expect("Atlas file not found");
+        let infos: Vec<AtlasInfo> = from_reader(file).expect("Failed to parse the atlas file");
+        let atlases: Vec<Atlas> = infos
+            .into_iter()
+            .map(|info| Atlas {
+                image: info.path,
+                content: match info.name {
+                    Type::Landscape => AtlasContent::Landscape,
+                    Type::TowersBrown => AtlasContent::Towers(Faction::Brown),
+                    Type::TowersGrey => AtlasContent::Towers(Faction::Grey),
+                    Type::TowersRed => AtlasContent::Towers(Faction::Red),
+                },
+                frames: info.frames.into_iter().map(|frame| (frame.name, (frame.x, frame.y))).collect(),
+            })
+            .collect();
+
+        let path = |name: &str| directory.join(name).to_str().expect("Invalid resources directory").to_string();
+        let tiles = TileTable::load(&path("tiles.ron"));
+        MapAssets {
+            frames: FrameTable::new(&atlases, &tiles),
+            atlases,
+            biomes: BiomeTable::load(&path("biomes.ron")),
+            tiles,
+        }
+    }
+
+    /// Returns the atlas of the given content
+    ///
+    /// Panics if there is none.
+    pub fn atlas(&self, content: AtlasContent) -> &Atlas {
+        self.atlases
+            .iter()
+            .find(|atlas| atlas.content == content)
+            .unwrap_or_else(|| panic!("No atlas of {:?}", content))
+    }
+}
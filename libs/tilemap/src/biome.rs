@@ -0,0 +1,106 @@
+use std::fs::File;
+
+use rand::Rng;
+use ron::de::from_reader;
+use serde::Deserialize;
+
+/// The kinds of terrain of the map
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize)]
+pub enum Biome {
+    Ocean,
+    Beach,
+    Grassland,
+    Forest,
+    Mountain,
+    CrystalFields,
+}
+
+/// The values of the noise maps at a tile, mostly between -1.5 and 1.5
+#[derive(Debug, Clone, Copy)]
+pub struct Climate {
+    pub elevation: f64,
+    pub moisture: f64,
+    pub temperature: f64,
+}
+
+/// A rule of the biome table: where a biome lies and which tiles it is made of
+#[derive(Debug, Clone, Deserialize)]
+pub struct BiomeRule {
+    pub biome: Biome,
+    /// The `(min, max)` range of elevation of the biome, measured from the sea level, any
+    /// elevation when missing
+    #[serde(default)]
+    pub elevation: Option<(f64, f64)>,
+    /// The `(min, max)` range of moisture of the biome, any moisture when missing
+    #[serde(default)]
+    pub moisture: Option<(f64, f64)>,
+    /// The `(min, max)` range of temperature of the biome, any temperature when missing
+    #[serde(default)]
+    pub temperature: Option<(f64, f64)>,
+    /// The landscape frames of the biome, with their weights
+    pub tiles: Vec<(String, u32)>,
+}
+
+impl BiomeRule {
+    /// Returns true if the climate is within the ranges of the rule
+    pub fn matches(&self, climate: &Climate) -> bool {
+        let within = |range: Option<(f64, f64)>, value: f64| range.is_none_or(|(min, max)| min <= value && value < max);
+        within(self.elevation, climate.elevation)
+            && within(self.moisture, climate.moisture)
+            && within(self.temperature, climate.temperature)
+    }
+
+    /// Picks the frame of a tile, the variants with a bigger weight being more likely
+    pub fn pick_tile<R: Rng>(&self, rng: &mut R) -> &str {
+        pick_frame(rng, &self.tiles)
+    }
+}
+
+/// Picks one of the given frames, the ones with a bigger weight being more likely
+pub fn pick_frame<'a, R: Rng>(rng: &mut R, frames: &'a [(String, u32)]) -> &'a str {
+    let total: u32 = frames.iter().map(|&(_, weight)| weight).sum();
+    let mut pick = rng.gen_range(0..total.max(1));
+    for (frame, weight) in frames {
+        if pick < *weight {
+            return frame;
+        }
+        pick -= weight;
+    }
+    &frames[0].0
+}
+
+/// The biome table, loaded from `resources/biomes.ron`
+///
+/// The rules are tried in order and the first one matching the climate of a tile gives its biome,
+/// except under the sea level where tiles always belong to the `Ocean`. Elevations are measured
+/// from the sea level, so that the coasts follow it when it changes
+#[derive(Debug, Clone, Deserialize)]
+#[serde(transparent)]
+pub struct BiomeTable {
+    rules: Vec<BiomeRule>,
+}
+
+impl BiomeTable {
+    /// Loads the biome table from the given RON file
+    pub fn load(path: &str) -> BiomeTable {
+        let file = File::open(path).expect("Biome table not found");
+        let table: BiomeTable = from_reader(file).expect("Failed to parse the biome table");
+        assert!(
+            table.rules.iter().all(|rule| !rule.tiles.is_empty()),
+            "every biome needs at least one tile"
+        );
+        table
+    }
+
+    /// Returns the rule of the biome of a tile with the given climate
+    pub fn rule(&self, climate: &Climate, sea_level: f64) -> &BiomeRule {
+        let climate = Climate { elevation: climate.elevation - sea_level, ..*climate };
+        let ocean = climate.elevation < 0.0;
+        self.rules
+            .iter()
+            .filter(|rule| (rule.biome == Biome::Ocean) == ocean)
+            .find(|rule| ocean || rule.matches(&climate))
+            .or(self.rules.last())
+            .expect("The biome table is empty")
+    }
+}
@@ -0,0 +1,213 @@
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+
+use geometry::IsoProjection;
+
+use crate::{AtlasContent, Climate, ClimateNoise, Faction, FrameTable, Layer, MapAssets, MapGenConfig, Tile, Tower};
+
+/// The number of tiles along each side of a chunk
+pub const CHUNK_SIZE: usize = 16;
+/// The fraction of the dry ground tiles, from the highest, where towers can be built
+const HIGH_GROUND_RATIO: f32 = 0.25;
+/// The minimum distance between two towers, in tiles
+pub(crate) const TOWER_SPACING: f32 = 6.0;
+/// Towers are kept this many tiles away from the sides of their chunk, so that the towers of
+/// neighbouring chunks are spaced apart too
+const TOWER_MARGIN: usize = 3;
+/// The number of tiles of a chunk for each tower, at most
+pub(crate) const TILES_PER_TOWER: usize = 64;
+
+/// A `Chunk` is a square of `CHUNK_SIZE` tiles of the map, generated on its own from the seed of
+/// the map so that it can be dropped and generated again, or built from a map file
+#[derive(Debug)]
+pub struct Chunk {
+    /// The index of the chunk, the chunk (0, 0) holding the tiles (0, 0) to (15, 15)
+    pub index: (i32, i32),
+    /// The layers of the chunk, from the ground up
+    pub layers: Vec<Layer>,
+    /// The towers standing on the chunk
+    pub towers: Vec<Tower>,
+}
+
+impl Chunk {
+    /// Generates the chunk at the given index, which is always the same for the same seed
+    pub fn generate(
+        config: &MapGenConfig,
+        noise: &ClimateNoise,
+        seed: u32,
+        index: (i32, i32),
+        assets: &MapAssets,
+        projection: &IsoProjection,
+    ) -> Chunk {
+        // Everything random in the chunk comes from the seed and the index of the chunk, so that
+        // it doesn't depend on the order the chunks are generated in
+        let rng = &mut StdRng::seed_from_u64(Self::seed(seed, index));
+
+        let (ids, elevation) = Self::generate_ground(rng, config, noise, index, assets);
+        let ground = Self::landscape_layer(0, |column, row| Some(ids[column][row]));
+        let towers = Self::place_towers(rng, index, &ground, &elevation, assets, projection);
+
+        Self::build(index, vec![ground], towers, &assets.frames)
+    }
+
+    /// Builds a chunk from its landscape layers and its towers, adding a layer for the towers of
+    /// each faction
+    ///
+    /// Panics if a tower frame is missing from the frame table.
+    pub fn build(index: (i32, i32), mut layers: Vec<Layer>, towers: Vec<Tower>, frames: &FrameTable) -> Chunk {
+        layers.extend(Self::tower_layers(&towers, frames));
+        Chunk { index, layers, towers }
+    }
+
+    /// Builds a layer of landscape tiles of a chunk, from the frame ID of each tile of the chunk
+    /// (column and row in the chunk), `None` leaving the tile empty
+    pub fn landscape_layer<F>(level: i32, id: F) -> Layer
+    where
+        F: Fn(usize, usize) -> Option<u16>,
+    {
+        let tiles = (0..CHUNK_SIZE)
+            .flat_map(|x| (0..CHUNK_SIZE).map(move |y| (x, y)))
+            .map(|(x, y)| id(x, y).unwrap_or(0))
+            .collect();
+        Layer { level, atlas: AtlasContent::Landscape, tiles }
+    }
+
+    /// Returns the chunk holding the tile at the given index, and the index of the tile in it
+    pub fn locate((column, row): (i32, i32)) -> ((i32, i32), (usize, usize)) {
+        let size = CHUNK_SIZE as i32;
+        (
+            (column.div_euclid(size), row.div_euclid(size)),
+            (column.rem_euclid(size) as usize, row.rem_euclid(size) as usize),
+        )
+    }
+
+    /// Returns the index of the map tile at the top corner of the chunk at the given index
+    pub fn origin_of((x, y): (i32, i32)) -> (i32, i32) {
+        (x * CHUNK_SIZE as i32, y * CHUNK_SIZE as i32)
+    }
+
+    /// Returns the layers of landscape tiles of the chunk, from the ground up
+    pub fn landscape_layers(&self) -> impl Iterator<Item = &Layer> {
+        self.layers.iter().filter(|layer| layer.atlas == AtlasContent::Landscape)
+    }
+
+    /// Returns the tiles of every layer at the given index in the chunk, from the ground up
+    pub fn tiles<'a>(&'a self, (column, row): (usize, usize), frames: &'a FrameTable) -> impl Iterator<Item = Tile<'a>> {
+        let (left, top) = Self::origin_of(self.index);
+        let tile = (left + column as i32, top + row as i32);
+        self.layers.iter().filter_map(move |layer| frames.tile(layer.id(column, row), tile))
+    }
+
+    /// Mixes the seed of the map with the index of a chunk
+    fn seed(seed: u32, (x, y): (i32, i32)) -> u64 {
+        let index = (x as u32 as u64) << 32 | y as u32 as u64;
+        (seed as u64) ^ index.wrapping_mul(0x9E37_79B9_7F4A_7C15)
+    }
+
+    /// Picks the frame IDs of the ground tiles of the chunk, by column then row, and returns them
+    /// with the elevation of each tile
+    ///
+    /// Panics if a biome tile is missing from the landscape atlas.
+    fn generate_ground<R: Rng>(
+        rng: &mut R,
+        config: &MapGenConfig,
+        noise: &ClimateNoise,
+        index: (i32, i32),
+        assets: &MapAssets,
+    ) -> (Vec<Vec<u16>>, Vec<Vec<f64>>) {
+        let biomes = &assets.biomes;
+
+        // Sample the climate noises at each tile, the noises going on from a chunk to the next
+        let (left, top) = Self::origin_of(index);
+        let climates: Vec<Vec<Climate>> = (0..CHUNK_SIZE as i32)
+            .map(|x| (0..CHUNK_SIZE as i32).map(|y| noise.at(left + x, top + y)).collect())
+            .collect();
+
+        // The climate of each tile gives its biome, which picks one of its tiles
+        let ids = climates
+            .iter()
+            .map(|column| {
+                column
+                    .iter()
+                    .map(|climate| {
+                        let frame = biomes.rule(climate, config.sea_level).pick_tile(rng);
+                        assets
+                            .frames
+                            .id(AtlasContent::Landscape, frame)
+                            .unwrap_or_else(|| panic!("Unknown landscape frame {:?} in the biome table", frame))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let elevation = climates
+            .iter()
+            .map(|column| column.iter().map(|climate| climate.elevation).collect())
+            .collect();
+
+        (ids, elevation)
+    }
+
+    /// Picks the tiles of the towers: on the high ground, never on water nor obstacles, and
+    /// spaced apart
+    fn place_towers<R: Rng>(
+        rng: &mut R,
+        index: (i32, i32),
+        ground: &Layer,
+        elevation: &[Vec<f64>],
+        assets: &MapAssets,
+        projection: &IsoProjection,
+    ) -> Vec<Tower> {
+        let inner = TOWER_MARGIN..CHUNK_SIZE - TOWER_MARGIN;
+        let mut candidates: Vec<(usize, usize)> = inner
+            .clone()
+            .flat_map(|column| inner.clone().map(move |row| (column, row)))
+            .filter(|&(column, row)| assets.frames.get(ground.id(column, row)).is_some_and(|frame| frame.info.is_buildable()))
+            .collect();
+
+        // Keep the highest tiles, shuffled first so that ties are broken randomly
+        candidates.shuffle(rng);
+        candidates.sort_by(|&(c1, r1), &(c2, r2)| elevation[c2][r2].total_cmp(&elevation[c1][r1]));
+        candidates.truncate((candidates.len() as f32 * HIGH_GROUND_RATIO).ceil() as usize);
+
+        let (left, top) = Self::origin_of(index);
+        let mut towers: Vec<Tower> = Vec::new();
+        for (column, row) in candidates {
+            if towers.len() >= CHUNK_SIZE * CHUNK_SIZE / TILES_PER_TOWER {
+                break;
+            }
+            let (column, row) = (left + column as i32, top + row as i32);
+            let spaced = towers.iter().all(|tower| {
+                let (dx, dy) = ((tower.tile.0 - column) as f32, (tower.tile.1 - row) as f32);
+                (dx * dx + dy * dy).sqrt() >= TOWER_SPACING
+            });
+            if spaced {
+                // A random faction, with a random tower frame of its atlas
+                let faction = Faction::random(rng);
+                let atlas = assets.atlas(AtlasContent::Towers(faction));
+                let frame = atlas.frame_name(rng.gen_range(0..atlas.frames.len())).unwrap();
+                towers.push(Tower::new((column, row), projection.tile_center(column, row), faction, frame.to_string()));
+            }
+        }
+        towers
+    }
+
+    /// Builds a layer for each faction holding towers, with the frame of each tower
+    fn tower_layers(towers: &[Tower], frames: &FrameTable) -> Vec<Layer> {
+        Faction::ALL
+            .iter()
+            .filter(|&&faction| towers.iter().any(|tower| tower.faction == faction))
+            .map(|&faction| {
+                let atlas = AtlasContent::Towers(faction);
+                let mut layer = Layer::empty(1, atlas);
+                for tower in towers.iter().filter(|tower| tower.faction == faction) {
+                    let id = frames
+                        .id(atlas, &tower.frame)
+                        .unwrap_or_else(|| panic!("Unknown tower frame {:?}", tower.frame));
+                    let (_, (x, y)) = Self::locate(tower.tile);
+                    layer.set(x, y, id);
+                }
+                layer
+            })
+            .collect()
+    }
+}
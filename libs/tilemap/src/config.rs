@@ -0,0 +1,102 @@
+use std::fs::File;
+
+use noise::{Fbm, MultiFractal, NoiseFn, Perlin};
+use ron::de::from_reader;
+use serde::Deserialize;
+
+use crate::{Climate, GeneratorKind, Palette};
+
+/// The parameters of the map generation, loaded from `resources/mapgen.ron`
+///
+/// The elevation, moisture and temperature of each tile come from fractal Perlin noises, and
+/// pick the biome of the tile in the biome table, unless another generator is chosen. The same
+/// parameters with the same seed always give the same map.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct MapGenConfig {
+    /// The seed of the map, a random one is drawn when there is none
+    pub seed: Option<u32>,
+    /// The number of noise layers added together, more octaves give more details
+    pub octaves: usize,
+    /// How much the frequency grows from an octave to the next
+    pub lacunarity: f64,
+    /// How much the amplitude shrinks from an octave to the next
+    pub persistence: f64,
+    /// The size of the landscape features of the first octave, in tiles
+    pub scale: f64,
+    /// The size of the moisture and temperature features, in tiles
+    pub climate_scale: f64,
+    /// The elevation under which tiles are water, elevations being mostly between -1.5 and 1.5
+    pub sea_level: f64,
+    /// The generator of the map
+    pub generator: GeneratorKind,
+    /// The number of columns and rows of the map, for every generator but the endless one
+    pub size: (usize, usize),
+    /// The frames of the dungeon and cave generators
+    pub palette: Palette,
+    /// The tile adjacency rules of the wave function collapse generator
+    pub wfc_rules: String,
+}
+
+impl Default for MapGenConfig {
+    fn default() -> MapGenConfig {
+        MapGenConfig {
+            seed: None,
+            octaves: 4,
+            lacunarity: 2.0,
+            persistence: 0.5,
+            scale: 25.0,
+            climate_scale: 40.0,
+            sea_level: -0.5,
+            generator: GeneratorKind::Endless,
+            size: (64, 64),
+            palette: Palette::default(),
+            wfc_rules: "resources/wfc.ron".to_string(),
+        }
+    }
+}
+
+impl MapGenConfig {
+    /// Loads the configuration from the given RON file, missing fields keep their default value
+    pub fn load(path: &str) -> MapGenConfig {
+        let file = File::open(path).expect("Map generation config not found");
+        from_reader(file).expect("Failed to parse the map generation config")
+    }
+
+    /// Returns the noises giving the climate of the tiles for the given seed
+    pub fn climate_noise(&self, seed: u32) -> ClimateNoise {
+        ClimateNoise {
+            elevation: self.fractal_noise(seed, self.scale),
+            moisture: self.fractal_noise(seed.wrapping_add(1), self.climate_scale),
+            temperature: self.fractal_noise(seed.wrapping_add(2), self.climate_scale),
+        }
+    }
+
+    fn fractal_noise(&self, seed: u32, scale: f64) -> Fbm<Perlin> {
+        Fbm::<Perlin>::new(seed)
+            .set_octaves(self.octaves)
+            .set_frequency(1.0 / scale)
+            .set_lacunarity(self.lacunarity)
+            .set_persistence(self.persistence)
+    }
+}
+
+/// The elevation, moisture and temperature noises of a map
+#[derive(Debug)]
+pub struct ClimateNoise {
+    elevation: Fbm<Perlin>,
+    moisture: Fbm<Perlin>,
+    temperature: Fbm<Perlin>,
+}
+
+impl ClimateNoise {
+    /// Returns the climate of the tile at the given index
+    pub fn at(&self, column: i32, row: i32) -> Climate {
+        let point = [column as f64, row as f64];
+        Climate {
+            elevation: self.elevation.get(point),
+            moisture: self.moisture.get(point),
+            temperature: self.temperature.get(point),
+        }
+    }
+}
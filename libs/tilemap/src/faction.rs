@@ -0,0 +1,18 @@
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+/// The factions owning the towers, one for each tower atlas colour
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum Faction {
+    Brown,
+    Grey,
+    Red,
+}
+
+impl Faction {
+    pub const ALL: [Faction; 3] = [Faction::Brown, Faction::Grey, Faction::Red];
+
+    pub fn random<R: Rng>(rng: &mut R) -> Faction {
+        *Faction::ALL.choose(rng).unwrap()
+    }
+}
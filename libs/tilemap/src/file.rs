@@ -0,0 +1,205 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File};
+use std::io;
+
+use ron::de::from_reader;
+use ron::ser::{to_string_pretty, PrettyConfig};
+use serde::{Deserialize, Serialize};
+
+use geometry::{TilePoint, TilePolygon};
+
+use crate::Faction;
+
+/// The version of the map files written by the game
+const VERSION: u32 = 1;
+
+/// Why a map can't be played: a file that can't be read, tiles that don't fit together, or a
+/// generator that can't make a map out of its config
+#[derive(Debug)]
+pub struct MapFileError(String);
+
+impl MapFileError {
+    pub(crate) fn new(message: impl Into<String>) -> MapFileError {
+        MapFileError(message.into())
+    }
+}
+
+impl fmt::Display for MapFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for MapFileError {}
+
+/// A map saved as a RON file, generated and saved by the game or written by hand
+///
+/// The tiles of each layer are numbers in the frame table, so that the names of the landscape
+/// frames are written once: 0 is an empty tile and n the frame n - 1 of the table.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MapFile {
+    pub header: MapHeader,
+    /// The names of the landscape frames the tiles of the layers refer to
+    pub frames: Vec<String>,
+    /// The landscape layers, from the ground up
+    pub layers: Vec<LayerFile>,
+    #[serde(default)]
+    pub towers: Vec<TowerFile>,
+    #[serde(default)]
+    pub objects: Vec<MapObject>,
+}
+
+/// What a map file holds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MapHeader {
+    /// The version of the format of the file
+    pub version: u32,
+    /// The seed the map was generated from, if it was
+    #[serde(default)]
+    pub seed: Option<u32>,
+    /// The index of the top tile of the map
+    #[serde(default)]
+    pub origin: (i32, i32),
+    /// The number of columns and rows of the map
+    pub size: (usize, usize),
+}
+
+/// A landscape layer of a map file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayerFile {
+    pub level: i32,
+    /// The tiles of the layer, by row then column, as numbers in the frame table
+    pub tiles: Vec<Vec<u16>>,
+}
+
+/// A tower of a map file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TowerFile {
+    pub tile: (i32, i32),
+    pub faction: Faction,
+    /// The name of the frame of the tower in the atlas of its faction
+    pub frame: String,
+}
+
+/// Something placed on a map that isn't a tile, like where the player starts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MapObject {
+    pub kind: ObjectKind,
+    /// The position of the object, in tile space
+    pub position: TilePoint,
+    /// The name given to the object in the editor
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    /// The area covered by the object, in tile space, for the trigger zones
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub area: Option<TilePolygon>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ObjectKind {
+    /// Where the player starts
+    PlayerSpawn,
+    /// Where the enemies come from
+    EnemySpawn,
+    /// An area where something happens when the player enters it
+    Trigger,
+}
+
+impl MapObject {
+    /// Returns a new object at the given position, without name nor area
+    pub fn new(kind: ObjectKind, position: TilePoint) -> MapObject {
+        MapObject { kind, position, name: String::new(), area: None }
+    }
+}
+
+impl MapFile {
+    /// Returns an empty map file of the given size
+    pub fn new(seed: Option<u32>, origin: (i32, i32), size: (usize, usize)) -> MapFile {
+        MapFile {
+            header: MapHeader { version: VERSION, seed, origin, size },
+            frames: vec![],
+            layers: vec![],
+            towers: vec![],
+            objects: vec![],
+        }
+    }
+
+    /// Loads a map file from the given path, and checks that its tiles fit together
+    pub fn load(path: &str) -> Result<MapFile, MapFileError> {
+        let file = File::open(path).map_err(|error| MapFileError::new(format!("{}: {}", path, error)))?;
+        let map: MapFile = from_reader(file).map_err(|error| MapFileError::new(format!("{}: {}", path, error)))?;
+        map.validate()?;
+        Ok(map)
+    }
+
+    /// Checks that the file can be read by the game: a supported version, layers with the rows
+    /// and columns of the header, tiles within the frame table and towers on the map
+    ///
+    /// Whether the frames exist in the atlases is checked when building the map.
+    pub fn validate(&self) -> Result<(), MapFileError> {
+        let MapHeader { version, origin, size, .. } = self.header;
+        if version > VERSION {
+            return Err(MapFileError::new(format!("The map file version {} is not supported", version)));
+        }
+        if size.0 == 0 || size.1 == 0 {
+            return Err(MapFileError::new("The map file has no tiles"));
+        }
+        for (n, layer) in self.layers.iter().enumerate() {
+            if layer.tiles.len() != size.1 {
+                return Err(MapFileError::new(format!("The layer {} has {} rows instead of {}", n, layer.tiles.len(), size.1)));
+            }
+            for (row, tiles) in layer.tiles.iter().enumerate() {
+                if tiles.len() != size.0 {
+                    let message = format!("The row {} of the layer {} has {} tiles instead of {}", row, n, tiles.len(), size.0);
+                    return Err(MapFileError::new(message));
+                }
+                if let Some(column) = tiles.iter().position(|&id| id as usize > self.frames.len()) {
+                    let message = format!(
+                        "The tile {} at ({}, {}) of the layer {} is out of the frame table of {} frames",
+                        tiles[column], column, row, n, self.frames.len()
+                    );
+                    return Err(MapFileError::new(message));
+                }
+            }
+        }
+        let (left, top) = origin;
+        let on_map = |(column, row): (i32, i32)| {
+            (left..left + size.0 as i32).contains(&column) && (top..top + size.1 as i32).contains(&row)
+        };
+        if let Some(tower) = self.towers.iter().find(|tower| !on_map(tower.tile)) {
+            return Err(MapFileError::new(format!("The tower at {:?} is out of the map", tower.tile)));
+        }
+        Ok(())
+    }
+
+    /// Saves the map file to the given path, a row of tiles on each line
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let config = PrettyConfig::new().depth_limit(4);
+        let text = to_string_pretty(self, config).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        fs::write(path, text)
+    }
+
+    /// Returns the frame of the tile of a layer at the given map index, if there is one
+    ///
+    /// Tiles out of the frame table have no frame, `validate` reports them.
+    pub fn frame(&self, layer: &LayerFile, (column, row): (i32, i32)) -> Option<&str> {
+        let (left, top) = self.header.origin;
+        let row = layer.tiles.get(usize::try_from(row - top).ok()?)?;
+        let id = *row.get(usize::try_from(column - left).ok()?)?;
+        let index = (id as usize).checked_sub(1)?;
+        self.frames.get(index).map(String::as_str)
+    }
+
+    /// Returns the number of the given frame in the frame table, adding it if it is new
+    pub fn frame_id(&mut self, frame: &str) -> u16 {
+        let index = match self.frames.iter().position(|name| name == frame) {
+            Some(index) => index,
+            None => {
+                self.frames.push(frame.to_string());
+                self.frames.len() - 1
+            }
+        };
+        u16::try_from(index + 1).expect("Too many frames for a map file")
+    }
+}
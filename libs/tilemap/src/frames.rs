@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+use geometry::{Real, Scalar, TilePoint};
+
+use crate::{Atlas, AtlasContent, Tile, TileInfo, TileTable};
+
+/// A frame of the frame table
+#[derive(Debug, Clone)]
+pub struct FrameInfo {
+    /// The atlas holding the frame
+    pub atlas: AtlasContent,
+    /// The name of the frame in its atlas
+    pub name: String,
+    /// What the tiles of the frame mean to the game
+    pub info: TileInfo,
+}
+
+/// The frames of every atlas, numbered from 1 so that the layers store their tiles as 16 bit IDs,
+/// 0 being an empty tile
+#[derive(Debug)]
+pub struct FrameTable {
+    frames: Vec<FrameInfo>,
+    /// The ID of each frame, by atlas then name
+    ids: HashMap<AtlasContent, HashMap<String, u16>>,
+}
+
+impl FrameTable {
+    /// Numbers the frames of the given atlases, landscape frames meaning what the tile table
+    /// says and tower frames being towers
+    pub fn new(atlases: &[Atlas], tiles: &TileTable) -> FrameTable {
+        let mut table = FrameTable { frames: vec![], ids: HashMap::new() };
+        for atlas in atlases {
+            for (name, _) in &atlas.frames {
+                let info = match atlas.content {
+                    AtlasContent::Landscape => tiles.get(name),
+                    AtlasContent::Towers(_) => TileInfo::TOWER,
+                };
+                table.frames.push(FrameInfo { atlas: atlas.content, name: name.clone(), info });
+                let id = u16::try_from(table.frames.len()).expect("Too many frames for 16 bit tile IDs");
+                table.ids.entry(atlas.content).or_default().insert(name.clone(), id);
+            }
+        }
+        table
+    }
+
+    /// Returns the ID of the frame of the given atlas and name
+    pub fn id(&self, atlas: AtlasContent, name: &str) -> Option<u16> {
+        self.ids.get(&atlas)?.get(name).copied()
+    }
+
+    /// Returns the frame of the given ID, `None` for the empty tile
+    pub fn get(&self, id: u16) -> Option<&FrameInfo> {
+        self.frames.get((id as usize).checked_sub(1)?)
+    }
+
+    /// Returns the frames with their ID
+    pub fn iter(&self) -> impl Iterator<Item = (u16, &FrameInfo)> {
+        self.frames.iter().enumerate().map(|(index, frame)| (index as u16 + 1, frame))
+    }
+
+    /// Returns the tile of the given ID at the given map index, `None` for the empty tile
+    pub fn tile(&self, id: u16, (column, row): (i32, i32)) -> Option<Tile<'_>> {
+        let frame = self.get(id)?;
+        Some(Tile {
+            position: TilePoint::new(Scalar::from_f32(column as f32), Scalar::from_f32(row as f32)),
+            id,
+            frame: &frame.name,
+            info: frame.info,
+        })
+    }
+}
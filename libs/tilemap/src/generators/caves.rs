@@ -0,0 +1,120 @@
+use rand::{rngs::StdRng, Rng};
+
+use crate::biome::pick_frame;
+use crate::{MapAssets, MapFileError};
+
+use super::{neighbours, MapGenerator, Palette};
+
+/// The share of the cells starting as rock
+const FILL_RATIO: f64 = 0.45;
+/// The number of smoothing steps of the automaton
+const STEPS: usize = 5;
+/// An open cell turns to rock with at least this many rock cells around it
+const BIRTH: usize = 5;
+/// A rock cell stays rock with at least this many rock cells around it
+const SURVIVAL: usize = 4;
+
+/// Generates caves: cells start as rock or open at random, then each step turns the cells
+/// surrounded by rock to rock and the other ones open (a cellular automaton), which smooths the
+/// noise into caves. Only the largest cave is kept, so that it can all be walked through.
+#[derive(Debug)]
+pub struct CaveGenerator {
+    palette: Palette,
+}
+
+impl CaveGenerator {
+    pub fn new(palette: Palette) -> CaveGenerator {
+        CaveGenerator { palette }
+    }
+
+    /// Returns the number of rock cells around the given one, the cells outside the map being rock
+    fn rock_around(rock: &[Vec<bool>], cell: (usize, usize), size: (usize, usize)) -> usize {
+        let inside = neighbours(cell, size).count();
+        8 - inside + neighbours(cell, size).filter(|&(column, row)| rock[row][column]).count()
+    }
+
+    /// Fills every open cave but the largest one with rock
+    fn keep_largest_cave(rock: &mut [Vec<bool>], (width, height): (usize, usize)) {
+        // Give each cave a number by filling it from one of its cells
+        let mut caves = vec![vec![None; width]; height];
+        let mut sizes = vec![];
+        for row in 0..height {
+            for column in 0..width {
+                if rock[row][column] || caves[row][column].is_some() {
+                    continue;
+                }
+                let cave = sizes.len();
+                let mut size = 0;
+                let mut stack = vec![(column, row)];
+                caves[row][column] = Some(cave);
+                while let Some((column, row)) = stack.pop() {
+                    size += 1;
+                    let sides = [(column.wrapping_sub(1), row), (column + 1, row), (column, row.wrapping_sub(1)), (column, row + 1)];
+                    for (column, row) in sides {
+                        if column < width && row < height && !rock[row][column] && caves[row][column].is_none() {
+                            caves[row][column] = Some(cave);
+                            stack.push((column, row));
+                        }
+                    }
+                }
+                sizes.push(size);
+            }
+        }
+
+        // Without any cave, the rock is left as it is
+        let Some(largest) = (0..sizes.len()).max_by_key(|&cave| sizes[cave]) else {
+            return;
+        };
+        for (rock, caves) in rock.iter_mut().zip(&caves) {
+            for (rock, &cave) in rock.iter_mut().zip(caves) {
+                *rock = cave != Some(largest);
+            }
+        }
+    }
+}
+
+impl MapGenerator for CaveGenerator {
+    fn generate_ground(&self, rng: &mut StdRng, size: (usize, usize), _assets: &MapAssets) -> Result<Vec<Vec<Option<String>>>, MapFileError> {
+        let (width, height) = size;
+        // The sides of the map are always rock
+        let mut rock: Vec<Vec<bool>> = (0..height)
+            .map(|row| {
+                (0..width)
+                    .map(|column| column == 0 || row == 0 || column == width - 1 || row == height - 1 || rng.gen_bool(FILL_RATIO))
+                    .collect()
+            })
+            .collect();
+
+        for _ in 0..STEPS {
+            rock = (0..height)
+                .map(|row| {
+                    (0..width)
+                        .map(|column| {
+                            let around = Self::rock_around(&rock, (column, row), size);
+                            around >= if rock[row][column] { SURVIVAL } else { BIRTH }
+                        })
+                        .collect()
+                })
+                .collect();
+        }
+        Self::keep_largest_cave(&mut rock, size);
+
+        // The rock next to the cave is a wall, the deeper one stays empty
+        Ok((0..height)
+            .map(|row| {
+                (0..width)
+                    .map(|column| {
+                        let frames = if !rock[row][column] {
+                            &self.palette.floor
+                        } else if neighbours((column, row), size).any(|(column, row)| !rock[row][column]) {
+                            &self.palette.wall
+                        } else {
+                            return None;
+                        };
+                        Some(pick_frame(rng, frames).to_string())
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+}
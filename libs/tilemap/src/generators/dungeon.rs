@@ -0,0 +1,139 @@
+use rand::{rngs::StdRng, Rng};
+
+use crate::biome::pick_frame;
+use crate::{MapAssets, MapFileError};
+
+use super::{neighbours, MapGenerator, Palette};
+
+/// The smallest side of a partition of the map
+const MIN_LEAF: usize = 8;
+/// Partitions with a side longer than this are always split
+const MAX_LEAF: usize = 20;
+/// The smallest side of a room
+const MIN_ROOM: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Cell {
+    Void,
+    Wall,
+    Floor,
+    Corridor,
+}
+
+/// A rectangle of cells, from its top left cell
+#[derive(Debug, Clone, Copy)]
+struct Area {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+/// Generates a dungeon: the map is split in two again and again (binary space partitioning), a
+/// room is dug in each partition and the rooms of the two halves of each split are joined by a
+/// corridor
+#[derive(Debug)]
+pub struct DungeonGenerator {
+    palette: Palette,
+}
+
+impl DungeonGenerator {
+    pub fn new(palette: Palette) -> DungeonGenerator {
+        DungeonGenerator { palette }
+    }
+
+    /// Digs the rooms of the area and joins them, returns a cell of one of the rooms
+    fn split(rng: &mut StdRng, area: Area, cells: &mut [Vec<Cell>]) -> (usize, usize) {
+        let (can_split_x, can_split_y) = (area.width >= 2 * MIN_LEAF, area.height >= 2 * MIN_LEAF);
+        let big = area.width > MAX_LEAF || area.height > MAX_LEAF;
+        if !(can_split_x || can_split_y) || (!big && rng.gen_bool(0.25)) {
+            return Self::dig_room(rng, area, cells);
+        }
+
+        // Split across the longer side
+        let vertical = if can_split_x && can_split_y { area.width >= area.height } else { can_split_x };
+        let (first, second) = if vertical {
+            let cut = rng.gen_range(MIN_LEAF..=area.width - MIN_LEAF);
+            (Area { width: cut, ..area }, Area { x: area.x + cut, width: area.width - cut, ..area })
+        } else {
+            let cut = rng.gen_range(MIN_LEAF..=area.height - MIN_LEAF);
+            (Area { height: cut, ..area }, Area { y: area.y + cut, height: area.height - cut, ..area })
+        };
+        let first = Self::split(rng, first, cells);
+        let second = Self::split(rng, second, cells);
+        Self::dig_corridor(rng, first, second, cells);
+
+        // Either room stands for the area when joining it to its neighbour
+        if rng.gen() {
+            first
+        } else {
+            second
+        }
+    }
+
+    /// Digs a room of random size in the area, a cell away from its sides, and returns its center
+    fn dig_room(rng: &mut StdRng, area: Area, cells: &mut [Vec<Cell>]) -> (usize, usize) {
+        // Only areas too small for it go without a margin
+        let (margin_x, margin_y) = (usize::from(area.width > 2), usize::from(area.height > 2));
+        let (inner_width, inner_height) = (area.width - 2 * margin_x, area.height - 2 * margin_y);
+        let width = rng.gen_range(MIN_ROOM.min(inner_width)..=inner_width);
+        let height = rng.gen_range(MIN_ROOM.min(inner_height)..=inner_height);
+        let x = area.x + margin_x + rng.gen_range(0..=inner_width - width);
+        let y = area.y + margin_y + rng.gen_range(0..=inner_height - height);
+        for row in &mut cells[y..y + height] {
+            row[x..x + width].fill(Cell::Floor);
+        }
+        (x + width / 2, y + height / 2)
+    }
+
+    /// Digs an L-shaped corridor between two cells
+    fn dig_corridor(rng: &mut StdRng, from: (usize, usize), to: (usize, usize), cells: &mut [Vec<Cell>]) {
+        let corner = if rng.gen() { (to.0, from.1) } else { (from.0, to.1) };
+        for (start, end) in [(from, corner), (corner, to)] {
+            for row in &mut cells[start.1.min(end.1)..=start.1.max(end.1)] {
+                for cell in &mut row[start.0.min(end.0)..=start.0.max(end.0)] {
+                    if *cell != Cell::Floor {
+                        *cell = Cell::Corridor;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl MapGenerator for DungeonGenerator {
+    fn generate_ground(&self, rng: &mut StdRng, size: (usize, usize), _assets: &MapAssets) -> Result<Vec<Vec<Option<String>>>, MapFileError> {
+        let (width, height) = size;
+        let mut cells = vec![vec![Cell::Void; width]; height];
+        if width > 2 && height > 2 {
+            Self::split(rng, Area { x: 1, y: 1, width: width - 2, height: height - 2 }, &mut cells);
+        }
+
+        // The empty cells next to the rooms and corridors are walls, the deeper ones stay empty
+        for row in 0..height {
+            for column in 0..width {
+                let open = |(column, row): (usize, usize)| matches!(cells[row][column], Cell::Floor | Cell::Corridor);
+                if cells[row][column] == Cell::Void && neighbours((column, row), size).any(open) {
+                    cells[row][column] = Cell::Wall;
+                }
+            }
+        }
+
+        Ok(cells
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| {
+                        let frames = match cell {
+                            Cell::Void => return None,
+                            Cell::Wall => &self.palette.wall,
+                            Cell::Floor => &self.palette.floor,
+                            Cell::Corridor => &self.palette.corridor,
+                        };
+                        Some(pick_frame(rng, frames).to_string())
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+}
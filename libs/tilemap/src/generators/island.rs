@@ -0,0 +1,50 @@
+use rand::{rngs::StdRng, Rng};
+
+use crate::{MapAssets, MapFileError, MapGenConfig};
+
+use super::MapGenerator;
+
+/// The elevation added at the center of the island
+const ISLAND_HEIGHT: f64 = 1.0;
+/// How fast the elevation sinks towards the sides, the sides being this much lower than the
+/// center
+const FALLOFF: f64 = 2.5;
+/// The share of the noise in the elevation, the rest being the shape of the island
+const NOISE_WEIGHT: f64 = 0.5;
+
+/// Generates an island: the noise landscape of the endless map, sinking into the ocean towards
+/// the sides of the map
+#[derive(Debug)]
+pub struct IslandGenerator {
+    config: MapGenConfig,
+}
+
+impl IslandGenerator {
+    pub fn new(config: MapGenConfig) -> IslandGenerator {
+        IslandGenerator { config }
+    }
+}
+
+impl MapGenerator for IslandGenerator {
+    fn generate_ground(&self, rng: &mut StdRng, (width, height): (usize, usize), assets: &MapAssets) -> Result<Vec<Vec<Option<String>>>, MapFileError> {
+        let noise = self.config.climate_noise(rng.gen());
+        let biomes = &assets.biomes;
+
+        Ok((0..height)
+            .map(|row| {
+                (0..width)
+                    .map(|column| {
+                        // The distance to the center, 1 at the middle of the sides
+                        let dx = (column as f64 + 0.5) / width as f64 * 2.0 - 1.0;
+                        let dy = (row as f64 + 0.5) / height as f64 * 2.0 - 1.0;
+                        let distance = dx * dx + dy * dy;
+
+                        let mut climate = noise.at(column as i32, row as i32);
+                        climate.elevation = climate.elevation * NOISE_WEIGHT + ISLAND_HEIGHT - FALLOFF * distance;
+                        Some(biomes.rule(&climate, self.config.sea_level).pick_tile(rng).to_string())
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,187 @@
+//! The generators of the bounded maps, each giving a map file that the map is built from
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use serde::Deserialize;
+use strum_macros::{Display, EnumString};
+
+use geometry::{Real, Scalar, TilePoint};
+
+use crate::{
+    chunk::{TILES_PER_TOWER, TOWER_SPACING},
+    AtlasContent, Faction, LayerFile, MapAssets, MapFile, MapFileError, MapGenConfig, MapObject, ObjectKind, TowerFile,
+};
+
+pub use self::caves::CaveGenerator;
+pub use self::dungeon::DungeonGenerator;
+pub use self::island::IslandGenerator;
+pub use self::wfc::WfcGenerator;
+
+mod caves;
+mod dungeon;
+mod island;
+mod wfc;
+
+/// The ways to generate the map, chosen in the map generation config or on the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Display, EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum GeneratorKind {
+    /// A noise landscape going on without limit, generated chunk by chunk around the player
+    #[default]
+    Endless,
+    /// A noise landscape sinking into the ocean towards the sides of the map
+    Island,
+    /// Rooms split by binary space partitioning and joined by corridors
+    Dungeon,
+    /// Caves grown by a cellular automaton
+    Caves,
+    /// Tiles placed by wave function collapse, following adjacency rules
+    Wfc,
+}
+
+impl GeneratorKind {
+    /// Returns the generator of the kind, or `None` for the endless map, which is not generated
+    /// at once, after checking that the frames of its palette or rules are landscape frames
+    pub fn generator(self, config: &MapGenConfig, assets: &MapAssets) -> Result<Option<Box<dyn MapGenerator>>, MapFileError> {
+        Ok(match self {
+            GeneratorKind::Endless => None,
+            GeneratorKind::Island => Some(Box::new(IslandGenerator::new(config.clone()))),
+            GeneratorKind::Dungeon => {
+                config.palette.check(assets)?;
+                Some(Box::new(DungeonGenerator::new(config.palette.clone())))
+            }
+            GeneratorKind::Caves => {
+                config.palette.check(assets)?;
+                Some(Box::new(CaveGenerator::new(config.palette.clone())))
+            }
+            GeneratorKind::Wfc => Some(Box::new(WfcGenerator::load(&config.wfc_rules, assets)?)),
+        })
+    }
+}
+
+/// The landscape frames of the generators drawing rooms and walls, with their weights
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Palette {
+    /// The frames of the rooms and the open caves
+    pub floor: Vec<(String, u32)>,
+    /// The frames of the walls around them
+    pub wall: Vec<(String, u32)>,
+    /// The frames of the corridors between rooms
+    pub corridor: Vec<(String, u32)>,
+}
+
+impl Default for Palette {
+    fn default() -> Palette {
+        let frames = |frames: &[(&str, u32)]| frames.iter().map(|&(frame, weight)| (frame.to_string(), weight)).collect();
+        Palette {
+            floor: frames(&[("28", 6), ("19", 2), ("16", 1)]),
+            wall: frames(&[("rocks_1", 1), ("rocks_2", 1), ("rocks_3", 1), ("rocks_4", 1)]),
+            corridor: frames(&[("02", 2), ("03", 1)]),
+        }
+    }
+}
+
+impl Palette {
+    /// Checks that every kind of tile of the palette has frames, all of them in the landscape
+    /// atlas
+    pub fn check(&self, assets: &MapAssets) -> Result<(), MapFileError> {
+        let landscape = assets.atlas(AtlasContent::Landscape);
+        for (kind, frames) in [("floor", &self.floor), ("wall", &self.wall), ("corridor", &self.corridor)] {
+            if frames.is_empty() {
+                return Err(MapFileError::new(format!("The palette has no {} frames", kind)));
+            }
+            if let Some((frame, _)) = frames.iter().find(|(frame, _)| !landscape.has_frame(frame)) {
+                return Err(MapFileError::new(format!("Unknown landscape frame {:?} in the {} frames of the palette", frame, kind)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A `MapGenerator` generates a map of a given size from a seed, the same seed always giving the
+/// same map
+///
+/// Generators only pick the ground frames: towers and the spawn point of the player are placed
+/// the same way for all of them, so that the game doesn't need to know which one ran.
+pub trait MapGenerator {
+    /// Returns the frame of each ground tile of the map, by row then column, `None` leaving the
+    /// tile empty, or why the generator failed
+    fn generate_ground(&self, rng: &mut StdRng, size: (usize, usize), assets: &MapAssets) -> Result<Vec<Vec<Option<String>>>, MapFileError>;
+
+    /// Generates the map file of the given number of columns and rows
+    fn generate(&self, seed: u32, size: (usize, usize), assets: &MapAssets) -> Result<MapFile, MapFileError> {
+        let rng = &mut StdRng::seed_from_u64(seed as u64);
+        let ground = self.generate_ground(rng, size, assets)?;
+        Ok(build_map_file(rng, seed, ground, assets))
+    }
+}
+
+/// Builds a map file from the frames of its ground tiles, placing the towers on dry ground away
+/// from obstacles and the player near the center
+fn build_map_file(rng: &mut StdRng, seed: u32, ground: Vec<Vec<Option<String>>>, assets: &MapAssets) -> MapFile {
+    let tile_table = &assets.tiles;
+    let (width, height) = (ground.first().map_or(0, Vec::len), ground.len());
+    let mut file = MapFile::new(Some(seed), (0, 0), (width, height));
+
+    let tiles = ground
+        .iter()
+        .map(|row| row.iter().map(|frame| frame.as_ref().map_or(0, |frame| file.frame_id(frame))).collect())
+        .collect();
+    file.layers.push(LayerFile { level: 0, tiles });
+
+    let info = |column: i32, row: i32| {
+        let frame = ground.get(usize::try_from(row).ok()?)?.get(usize::try_from(column).ok()?)?.as_ref()?;
+        Some(tile_table.get(frame))
+    };
+    let walkable = |column: i32, row: i32| info(column, row).is_some_and(|info| info.walkable);
+
+    // Towers stand on buildable tiles with walkable tiles all around, so that they never close a
+    // corridor, and spaced apart
+    let mut candidates: Vec<(i32, i32)> = (0..height as i32)
+        .flat_map(|row| (0..width as i32).map(move |column| (column, row)))
+        .filter(|&(column, row)| {
+            info(column, row).is_some_and(|info| info.is_buildable())
+                && (-1..=1).all(|dy| (-1..=1).all(|dx| walkable(column + dx, row + dy)))
+        })
+        .collect();
+    candidates.shuffle(rng);
+    for (column, row) in candidates {
+        if file.towers.len() >= width * height / TILES_PER_TOWER {
+            break;
+        }
+        let spaced = file.towers.iter().all(|tower| {
+            let (dx, dy) = ((tower.tile.0 - column) as f32, (tower.tile.1 - row) as f32);
+            (dx * dx + dy * dy).sqrt() >= TOWER_SPACING
+        });
+        if spaced {
+            // A random faction, with a random tower sprite of its atlas
+            let faction = Faction::random(rng);
+            let atlas = assets.atlas(AtlasContent::Towers(faction));
+            let frame = atlas.frame_name(rng.gen_range(0..atlas.frames.len())).unwrap();
+            file.towers.push(TowerFile { tile: (column, row), faction, frame: frame.to_string() });
+        }
+    }
+
+    // The player starts on the free walkable tile nearest the center
+    let center = (width as i32 / 2, height as i32 / 2);
+    let start = (0..height as i32)
+        .flat_map(|row| (0..width as i32).map(move |column| (column, row)))
+        .filter(|&(column, row)| walkable(column, row) && file.towers.iter().all(|tower| tower.tile != (column, row)))
+        .min_by_key(|&(column, row)| (column - center.0).pow(2) + (row - center.1).pow(2));
+    if let Some((column, row)) = start {
+        let position = TilePoint::new(Scalar::from_f32(column as f32 + 0.5), Scalar::from_f32(row as f32 + 0.5));
+        file.objects.push(MapObject::new(ObjectKind::PlayerSpawn, position));
+    }
+    file
+}
+
+/// Returns the indexes of the 8 tiles around the given one which are inside a map of the given
+/// size
+fn neighbours((column, row): (usize, usize), (width, height): (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+    (-1..=1)
+        .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+        .filter(|&offset| offset != (0, 0))
+        .filter_map(move |(dx, dy)| {
+            let (column, row) = (column.checked_add_signed(dx)?, row.checked_add_signed(dy)?);
+            (column < width && row < height).then_some((column, row))
+        })
+}
@@ -0,0 +1,155 @@
+use std::fs::File;
+
+use rand::{rngs::StdRng, Rng};
+use ron::de::from_reader;
+use serde::Deserialize;
+
+use crate::{AtlasContent, MapAssets, MapFileError};
+
+use super::MapGenerator;
+
+/// The number of times the collapse starts over after a contradiction before giving up
+const MAX_ATTEMPTS: usize = 10;
+
+/// A tile of the adjacency rules, as written in `resources/wfc.ron`
+#[derive(Debug, Deserialize)]
+struct TileRule {
+    frame: String,
+    weight: u32,
+    /// The frames which may be next to this one, on any side
+    neighbours: Vec<String>,
+}
+
+/// Generates a map by wave function collapse: every tile starts as any of the tiles of the rules,
+/// then the tile with the fewest choices left is picked one by one, which removes the tiles that
+/// can't be next to it from the choices of its neighbours
+#[derive(Debug)]
+pub struct WfcGenerator {
+    frames: Vec<String>,
+    weights: Vec<u32>,
+    /// The tiles which may be next to each tile, a bit for each tile
+    allowed: Vec<u64>,
+}
+
+impl WfcGenerator {
+    /// Loads the adjacency rules from the given RON file, a tile allowed next to another allowing
+    /// the other one next to it too, or returns why they can't be used: a file that can't be read,
+    /// more than 64 tiles, a weight of 0 or a frame which is not in the landscape atlas nor in the
+    /// rules
+    pub fn load(path: &str, assets: &MapAssets) -> Result<WfcGenerator, MapFileError> {
+        let invalid = |message: String| MapFileError::new(format!("{}: {}", path, message));
+        let file = File::open(path).map_err(|error| invalid(error.to_string()))?;
+        let rules: Vec<TileRule> = from_reader(file).map_err(|error| invalid(error.to_string()))?;
+        if rules.is_empty() || rules.len() > 64 {
+            return Err(invalid("The wave function collapse rules must hold 1 to 64 tiles".to_string()));
+        }
+        if let Some(rule) = rules.iter().find(|rule| rule.weight == 0) {
+            return Err(invalid(format!("The weight of {:?} must be positive", rule.frame)));
+        }
+        let landscape = assets.atlas(AtlasContent::Landscape);
+        if let Some(rule) = rules.iter().find(|rule| !landscape.has_frame(&rule.frame)) {
+            return Err(invalid(format!("Unknown landscape frame {:?}", rule.frame)));
+        }
+
+        let frames: Vec<String> = rules.iter().map(|rule| rule.frame.clone()).collect();
+        let mut allowed = vec![0u64; rules.len()];
+        for (tile, rule) in rules.iter().enumerate() {
+            for neighbour in &rule.neighbours {
+                let other = frames
+                    .iter()
+                    .position(|frame| frame == neighbour)
+                    .ok_or_else(|| invalid(format!("Unknown frame {:?} in the neighbours of {:?}", neighbour, rule.frame)))?;
+                allowed[tile] |= 1 << other;
+                allowed[other] |= 1 << tile;
+            }
+        }
+        Ok(WfcGenerator { frames, weights: rules.iter().map(|rule| rule.weight).collect(), allowed })
+    }
+
+    /// Collapses every cell of a map of the given size to a single tile, or returns `None` when
+    /// a cell is left without any choice
+    fn collapse(&self, rng: &mut StdRng, (width, height): (usize, usize)) -> Option<Vec<u64>> {
+        let all = u64::MAX >> (64 - self.frames.len());
+        let mut cells = vec![all; width * height];
+        loop {
+            // The undecided cell with the fewest choices, ties being broken at random
+            let mut lowest = None;
+            let (mut fewest, mut ties) = (u32::MAX, 0);
+            for (index, cell) in cells.iter().enumerate() {
+                let choices = cell.count_ones();
+                if choices < 2 || choices > fewest {
+                    continue;
+                }
+                ties = if choices < fewest { 1 } else { ties + 1 };
+                fewest = choices;
+                if rng.gen_range(0..ties) == 0 {
+                    lowest = Some(index);
+                }
+            }
+            let Some(index) = lowest else {
+                return Some(cells);
+            };
+
+            // Pick one of its tiles, the ones with a bigger weight being more likely
+            let cell = cells[index];
+            let choices = || (0..self.frames.len()).filter(move |tile| cell & 1 << tile != 0);
+            let total: u32 = choices().map(|tile| self.weights[tile]).sum();
+            let mut pick = rng.gen_range(0..total);
+            let tile = choices()
+                .find(|&tile| {
+                    let found = pick < self.weights[tile];
+                    pick = pick.saturating_sub(self.weights[tile]);
+                    found
+                })
+                .unwrap();
+            cells[index] = 1 << tile;
+
+            if !self.propagate(&mut cells, (width, height), index) {
+                return None;
+            }
+        }
+    }
+
+    /// Removes the tiles that can't be next to the choices of the changed cell from its
+    /// neighbours, then theirs, returns false when a cell is left without any choice
+    fn propagate(&self, cells: &mut [u64], (width, height): (usize, usize), changed: usize) -> bool {
+        let mut stack = vec![changed];
+        while let Some(index) = stack.pop() {
+            let allowed = (0..self.frames.len())
+                .filter(|tile| cells[index] & 1 << tile != 0)
+                .fold(0, |allowed, tile| allowed | self.allowed[tile]);
+            let (column, row) = (index % width, index / width);
+            let sides = [
+                (column > 0).then(|| index - 1),
+                (column + 1 < width).then(|| index + 1),
+                (row > 0).then(|| index - width),
+                (row + 1 < height).then(|| index + width),
+            ];
+            for side in sides.into_iter().flatten() {
+                let choices = cells[side] & allowed;
+                if choices != cells[side] {
+                    if choices == 0 {
+                        return false;
+                    }
+                    cells[side] = choices;
+                    stack.push(side);
+                }
+            }
+        }
+        true
+    }
+}
+
+impl MapGenerator for WfcGenerator {
+    /// Fails if every attempt ends in a contradiction, the rules then allowing too few tiles next
+    /// to each other
+    fn generate_ground(&self, rng: &mut StdRng, size: (usize, usize), _assets: &MapAssets) -> Result<Vec<Vec<Option<String>>>, MapFileError> {
+        let cells = (0..MAX_ATTEMPTS)
+            .find_map(|_| self.collapse(rng, size))
+            .ok_or_else(|| MapFileError::new(format!("The wave function collapse failed {} times, check the rules", MAX_ATTEMPTS)))?;
+        Ok(cells
+            .chunks(size.0.max(1))
+            .map(|row| row.iter().map(|cell| Some(self.frames[cell.trailing_zeros() as usize].clone())).collect())
+            .collect())
+    }
+}
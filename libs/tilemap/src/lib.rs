@@ -0,0 +1,32 @@
+//! The data of the maps of the game, without anything to draw them
+//!
+//! A `Map` is made of chunks of tile layers, generated from a seed (see `MapGenConfig` and the
+//! `MapGenerator`s) or built from a map file. Maps are saved as RON files (see `MapFile`) and can
+//! be imported from the isometric maps of the Tiled editor (see the `tiled` module).
+//! The `Pathfinder` finds the paths of the bodies walking on the loaded tiles.
+pub use self::assets::{Atlas, AtlasContent, MapAssets};
+pub use self::biome::{Biome, BiomeRule, BiomeTable, Climate};
+pub use self::chunk::{Chunk, CHUNK_SIZE};
+pub use self::config::{ClimateNoise, MapGenConfig};
+pub use self::faction::Faction;
+pub use self::frames::{FrameInfo, FrameTable};
+pub use self::file::{LayerFile, MapFile, MapFileError, MapHeader, MapObject, ObjectKind, TowerFile};
+pub use self::generators::{GeneratorKind, MapGenerator, Palette};
+pub use self::map::{Layer, Map, MapSource, Tile};
+pub use self::pathfinder::{Pathfinder, SEARCHES_PER_UPDATE};
+pub use self::tile_info::{TileInfo, TileTable};
+pub use self::tower::Tower;
+
+mod assets;
+mod biome;
+mod chunk;
+mod config;
+mod faction;
+mod file;
+mod frames;
+mod generators;
+mod map;
+mod pathfinder;
+pub mod tiled;
+mod tile_info;
+mod tower;
@@ -0,0 +1,542 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use rand::{Rng, seq::SliceRandom};
+
+use geometry::{Collide, GridTraversal, IsoProjection, Point, Position, Real, Scalar, Shape, TilePoint, WorldPoint, WorldPolygon, WorldRect};
+
+use crate::{
+    tiled, AtlasContent, Chunk, ClimateNoise, LayerFile, MapAssets, MapFile, MapFileError, MapGenConfig, MapObject, ObjectKind, TileInfo, Tower,
+    TowerFile, CHUNK_SIZE,
+};
+
+/// Chunks up to this many chunks away from the chunk at the streaming center are loaded
+const LOAD_RADIUS: i32 = 2;
+/// Chunks further than this many chunks away from it are dropped, a bit further than the loaded
+/// ones so that walking back and forth over a chunk border doesn't generate them again
+const UNLOAD_RADIUS: i32 = 3;
+
+/// Where the map of a new world comes from
+#[derive(Debug, Clone)]
+pub enum MapSource {
+    /// A map generated with the given parameters
+    Generated(MapGenConfig),
+    /// A map loaded from a file
+    File(MapFile),
+    /// A Tiled map, imported from the given `.tmj` or `.tmx` file
+    Tiled(String),
+}
+
+impl MapSource {
+    /// Imports the Tiled map of the source, if it is one, so that the map can be built again
+    /// without reading the file
+    pub fn import(self, assets: &MapAssets) -> Result<MapSource, MapFileError> {
+        match self {
+            MapSource::Tiled(path) => Ok(MapSource::File(import_tiled(&path, assets)?)),
+            source => Ok(source),
+        }
+    }
+}
+
+/// Imports the Tiled map of the given file as a map file
+fn import_tiled(path: &str, assets: &MapAssets) -> Result<MapFile, MapFileError> {
+    tiled::import(path, &assets.atlases).map_err(|error| MapFileError::new(format!("Failed to import the Tiled map: {}", error)))
+}
+
+/// Where the chunks of a map come from
+#[derive(Debug)]
+enum ChunkSource {
+    /// Generated from the seed with the given parameters, without limit
+    Generated { config: MapGenConfig, noise: Box<ClimateNoise> },
+    /// Built once from a map file, holding the tiles from the given top tile over the given
+    /// number of columns and rows, with the seed of the file if it was generated
+    File { origin: (i32, i32), size: (usize, usize), seed: Option<u32> },
+}
+
+/// The `Map` is made of chunks. A generated map goes on without limit: its chunks are generated
+/// from the seed around the streaming center (the player) and dropped when far away. A map loaded
+/// from a file has all its chunks loaded and ends with the tiles of the file.
+#[derive(Debug)]
+pub struct Map {
+    /// The seed the map was generated from, 0 for a map loaded from a file without one
+    pub seed: u32,
+    source: ChunkSource,
+    /// The frames and tables the chunks are generated from
+    assets: Arc<MapAssets>,
+    /// The loaded chunks, by index
+    chunks: HashMap<(i32, i32), Chunk>,
+    /// The index of the chunk at the streaming center
+    center: (i32, i32),
+    /// The projection between the tile, world and screen spaces of the map
+    pub projection: IsoProjection,
+    /// The outline of the tiles loaded around the streaming center, in world space (top, right,
+    /// bottom and left corners)
+    pub playable_area: WorldPolygon,
+    /// The objects placed on the map, like the spawn points of a map file
+    pub objects: Vec<MapObject>,
+}
+
+impl Map {
+    /// Creates a new map from the given source, made of the given assets and drawn with the given
+    /// projection
+    ///
+    /// Returns an error if the map file or the Tiled map can't be played.
+    pub fn from_source<R: Rng>(source: &MapSource, assets: Arc<MapAssets>, projection: IsoProjection, rng: &mut R) -> Result<Map, MapFileError> {
+        match source {
+            MapSource::Generated(config) => Map::new(config, assets, projection, rng),
+            MapSource::File(file) => Map::from_file(file, assets, projection),
+            MapSource::Tiled(path) => Map::from_file(&import_tiled(path, &assets)?, assets, projection),
+        }
+    }
+
+    /// Creates a new map from the seed of the config or a random one, with the generator of the
+    /// config: the endless map has the chunks around the world origin loaded, the other ones are
+    /// generated at once
+    ///
+    /// Fails if the palette or the rules of the generator name unknown frames, or if the generator
+    /// can't make a map out of them.
+    pub fn new<R: Rng>(config: &MapGenConfig, assets: Arc<MapAssets>, projection: IsoProjection, rng: &mut R) -> Result<Map, MapFileError> {
+        let seed = config.seed.unwrap_or_else(|| rng.gen());
+        if let Some(generator) = config.generator.generator(config, &assets)? {
+            let file = generator.generate(seed, config.size, &assets)?;
+            return Map::from_file(&file, assets, projection);
+        }
+
+        let (origin, size) = Self::window_of((0, 0));
+        let mut map = Map {
+            seed,
+            source: ChunkSource::Generated { config: config.clone(), noise: Box::new(config.climate_noise(seed)) },
+            assets,
+            chunks: HashMap::new(),
+            center: (0, 0),
+            projection,
+            playable_area: Self::calculate_playable_area((origin, (size, size)), &projection),
+            objects: vec![],
+        };
+        map.load_chunks();
+        Ok(map)
+    }
+
+    /// Builds the map of a file, without generating anything
+    ///
+    /// Returns an error if the file doesn't pass `MapFile::validate`, or if it refers to frames
+    /// missing from the atlases.
+    pub fn from_file(file: &MapFile, assets: Arc<MapAssets>, projection: IsoProjection) -> Result<Map, MapFileError> {
+        file.validate()?;
+        let (origin, size) = (file.header.origin, file.header.size);
+
+        // Check the frames first, the atlases don't tell which frame is missing
+        let landscape = assets.atlas(AtlasContent::Landscape);
+        if let Some(frame) = file.frames.iter().find(|frame| !landscape.has_frame(frame)) {
+            return Err(MapFileError::new(format!("Unknown landscape frame {:?} in the map file", frame)));
+        }
+        for tower in &file.towers {
+            let atlas = assets.atlas(AtlasContent::Towers(tower.faction));
+            if !atlas.has_frame(&tower.frame) {
+                return Err(MapFileError::new(format!("Unknown tower frame {:?} in the map file", tower.frame)));
+            }
+        }
+
+        let mut map = Map {
+            seed: file.header.seed.unwrap_or(0),
+            source: ChunkSource::File { origin, size, seed: file.header.seed },
+            assets,
+            chunks: HashMap::new(),
+            center: Chunk::locate(origin).0,
+            projection,
+            playable_area: Self::calculate_playable_area((origin, size), &projection),
+            objects: file.objects.clone(),
+        };
+
+        // Build every chunk holding tiles of the file
+        let frames = &map.assets.frames;
+        let (first, _) = Chunk::locate(origin);
+        let (last, _) = Chunk::locate((origin.0 + size.0 as i32 - 1, origin.1 + size.1 as i32 - 1));
+        for cx in first.0..=last.0 {
+            for cy in first.1..=last.1 {
+                let index = (cx, cy);
+                let (left, top) = Chunk::origin_of(index);
+                let layers = file
+                    .layers
+                    .iter()
+                    .map(|layer| {
+                        let id = |x: usize, y: usize| {
+                            let frame = file.frame(layer, (left + x as i32, top + y as i32))?;
+                            frames.id(AtlasContent::Landscape, frame)
+                        };
+                        Chunk::landscape_layer(layer.level, id)
+                    })
+                    .collect();
+                let towers = file
+                    .towers
+                    .iter()
+                    .filter(|tower| Chunk::locate(tower.tile).0 == index)
+                    .map(|tower| {
+                        let (column, row) = tower.tile;
+                        Tower::new(tower.tile, projection.tile_center(column, row), tower.faction, tower.frame.clone())
+                    })
+                    .collect();
+                map.chunks.insert(index, Chunk::build(index, layers, towers, frames));
+            }
+        }
+        Ok(map)
+    }
+
+    /// Returns the map file of the loaded chunks: their landscape layers, towers and objects
+    pub fn to_file(&self) -> MapFile {
+        let (origin, size) = self.loaded_bounds();
+        let seed = match self.source {
+            ChunkSource::Generated { .. } => Some(self.seed),
+            ChunkSource::File { seed, .. } => seed,
+        };
+        let mut file = MapFile::new(seed, origin, size);
+
+        // The chunks all have the same landscape layers, the n-th layer of a chunk being part of
+        // the n-th layer of the file
+        let layers = self.chunks.values().map(|chunk| chunk.landscape_layers().count()).max().unwrap_or(0);
+        for n in 0..layers {
+            let mut level = 0;
+            let tiles = (origin.1..origin.1 + size.1 as i32)
+                .map(|row| {
+                    (origin.0..origin.0 + size.0 as i32)
+                        .map(|column| {
+                            let (chunk, (x, y)) = Chunk::locate((column, row));
+                            let layer = self.chunks.get(&chunk).and_then(|chunk| chunk.landscape_layers().nth(n));
+                            match layer.and_then(|layer| self.assets.frames.get(layer.id(x, y)).map(|frame| (layer.level, frame))) {
+                                Some((layer_level, frame)) => {
+                                    level = layer_level;
+                                    file.frame_id(&frame.name)
+                                }
+                                None => 0,
+                            }
+                        })
+                        .collect()
+                })
+                .collect();
+            file.layers.push(LayerFile { level, tiles });
+        }
+
+        let mut towers: Vec<&Tower> = self.towers().collect();
+        towers.sort_by_key(|tower| tower.tile);
+        file.towers = towers
+            .into_iter()
+            .map(|tower| TowerFile { tile: tower.tile, faction: tower.faction, frame: tower.frame.clone() })
+            .collect();
+        file.objects = self.objects.clone();
+        file
+    }
+
+    /// Returns the top tile and the number of columns and rows of the loaded chunks
+    fn loaded_bounds(&self) -> ((i32, i32), (usize, usize)) {
+        if let ChunkSource::File { origin, size, .. } = self.source {
+            return (origin, size);
+        }
+        let (mut first, mut last) = (self.center, self.center);
+        for &(x, y) in self.chunks.keys() {
+            first = (first.0.min(x), first.1.min(y));
+            last = (last.0.max(x), last.1.max(y));
+        }
+        let chunks = ((last.0 - first.0 + 1) as usize, (last.1 - first.1 + 1) as usize);
+        (Chunk::origin_of(first), (chunks.0 * CHUNK_SIZE, chunks.1 * CHUNK_SIZE))
+    }
+
+    /// Returns the world positions of the objects of the given kind
+    pub fn spawn_points(&self, kind: ObjectKind) -> impl Iterator<Item = WorldPoint> + '_ {
+        self.objects
+            .iter()
+            .filter(move |object| object.kind == kind)
+            .map(|object| self.projection.tile_to_world(object.position))
+    }
+
+    /// Returns the trigger zones the given world position is in
+    pub fn triggers_at(&self, position: WorldPoint) -> impl Iterator<Item = &MapObject> {
+        let tile = self.projection.world_to_tile(position);
+        self.objects
+            .iter()
+            .filter(move |object| object.kind == ObjectKind::Trigger && object.area.as_ref().is_some_and(|area| area.contains(tile)))
+    }
+
+    /// Moves the streaming center to the given world position, generating the chunks around it
+    /// and dropping the far away ones, a map loaded from a file staying as it is
+    ///
+    /// Returns true if the loaded chunks changed
+    pub fn stream(&mut self, position: WorldPoint) -> bool {
+        let (center, _) = Chunk::locate(self.projection.tile_index(position));
+        if center == self.center || matches!(self.source, ChunkSource::File { .. }) {
+            return false;
+        }
+        self.center = center;
+        self.load_chunks();
+        true
+    }
+
+    /// Generates the missing chunks around the streaming center and drops the far away ones
+    fn load_chunks(&mut self) {
+        let ChunkSource::Generated { config, noise } = &self.source else {
+            return;
+        };
+        let (x, y) = self.center;
+        self.chunks.retain(|&(cx, cy), _| (cx - x).abs().max((cy - y).abs()) <= UNLOAD_RADIUS);
+        for cx in x - LOAD_RADIUS..=x + LOAD_RADIUS {
+            for cy in y - LOAD_RADIUS..=y + LOAD_RADIUS {
+                self.chunks
+                    .entry((cx, cy))
+                    .or_insert_with(|| Chunk::generate(config, noise, self.seed, (cx, cy), &self.assets, &self.projection));
+            }
+        }
+        let (origin, size) = self.window();
+        self.playable_area = Self::calculate_playable_area((origin, (size, size)), &self.projection);
+    }
+
+    /// Returns the index of the top tile of the square of tiles loaded around the streaming
+    /// center, and the number of tiles along its sides
+    ///
+    /// The window of a map loaded from a file is the smallest square holding its tiles.
+    pub fn window(&self) -> ((i32, i32), usize) {
+        match self.source {
+            ChunkSource::Generated { .. } => Self::window_of(self.center),
+            ChunkSource::File { origin, size, .. } => (origin, size.0.max(size.1)),
+        }
+    }
+
+    fn window_of((x, y): (i32, i32)) -> ((i32, i32), usize) {
+        let size = CHUNK_SIZE as i32;
+        (
+            ((x - LOAD_RADIUS) * size, (y - LOAD_RADIUS) * size),
+            (2 * LOAD_RADIUS as usize + 1) * CHUNK_SIZE,
+        )
+    }
+
+    /// Returns the projection of the map with the top tile of the window as the tile (0, 0), to
+    /// convert the tiles of the window to the world space
+    pub fn window_projection(&self) -> IsoProjection {
+        let ((column, row), _) = self.window();
+        IsoProjection {
+            origin: self.projection.tile_to_world(Self::tile_point((column, row))),
+            ..self.projection
+        }
+    }
+
+    /// Calculate the playable area of the map, the outline of the tiles from the given top tile
+    /// over the given number of columns and rows, in world space
+    fn calculate_playable_area(((column, row), (width, height)): ((i32, i32), (usize, usize)), projection: &IsoProjection) -> WorldPolygon {
+        let (width, height) = (width as i32, height as i32);
+        WorldPolygon::new(vec![
+            projection.tile_to_world(Self::tile_point((column, row))), // top,
+            projection.tile_to_world(Self::tile_point((column + width, row))), // right,
+            projection.tile_to_world(Self::tile_point((column + width, row + height))), // bottom,
+            projection.tile_to_world(Self::tile_point((column, row + height))), // left,
+        ])
+    }
+
+    fn tile_point((column, row): (i32, i32)) -> TilePoint {
+        TilePoint::new(Scalar::from_f32(column as f32), Scalar::from_f32(row as f32))
+    }
+
+    /// Returns the box around the playable area
+    pub fn world_bounds(&self) -> WorldRect {
+        self.playable_area.bounding_rect()
+    }
+
+    /// Returns the loaded chunks, from the back to the front of the screen
+    pub fn chunks(&self) -> Vec<&Chunk> {
+        let mut chunks: Vec<&Chunk> = self.chunks.values().collect();
+        chunks.sort_by_key(|chunk| (chunk.index.0 + chunk.index.1, chunk.index.0));
+        chunks
+    }
+
+    /// Returns the number of loaded chunks
+    pub fn chunks_len(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Returns the towers standing on the loaded chunks
+    pub fn towers(&self) -> impl Iterator<Item = &Tower> {
+        self.chunks.values().flat_map(|chunk| chunk.towers.iter())
+    }
+
+    /// Returns the index of the tile at the given world position, if it is loaded
+    pub fn tile_index(&self, world: WorldPoint) -> Option<(i32, i32)> {
+        let tile = self.projection.tile_index(world);
+        self.is_loaded(tile).then_some(tile)
+    }
+
+    /// Returns true if the chunk holding the tile at the given index is loaded
+    pub fn is_loaded(&self, tile: (i32, i32)) -> bool {
+        self.chunks.contains_key(&Chunk::locate(tile).0)
+    }
+
+    /// Returns the loaded tiles crossed by the segment between two world positions, in order
+    pub fn tiles_along(&self, from: WorldPoint, to: WorldPoint) -> Vec<(i32, i32)> {
+        let from = self.projection.world_to_tile(from);
+        let to = self.projection.world_to_tile(to);
+        GridTraversal::new(from, to)
+            .filter(|&tile| self.is_loaded(tile))
+            .collect()
+    }
+
+    /// Returns the tiles of every layer at the given index, from the ground up
+    pub fn tiles(&self, tile: (i32, i32)) -> impl Iterator<Item = Tile<'_>> {
+        let (chunk, tile) = Chunk::locate(tile);
+        self.chunks.get(&chunk).into_iter().flat_map(move |chunk| chunk.tiles(tile, &self.assets.frames))
+    }
+
+    /// Returns the topmost tile at the given index, if it is loaded
+    pub fn tile_at(&self, tile: (i32, i32)) -> Option<Tile<'_>> {
+        self.tiles(tile).last()
+    }
+
+    /// Returns true if the tile at the given index is loaded and every layer can be walked on
+    pub fn is_walkable(&self, tile: (i32, i32)) -> bool {
+        self.tile_at(tile).is_some() && self.tiles(tile).all(|tile| tile.info.walkable)
+    }
+
+    /// Returns the cost of walking on the tile at the given index, the highest of its layers, or
+    /// `None` if it can't be walked on
+    pub fn cost(&self, tile: (i32, i32)) -> Option<f32> {
+        if self.is_walkable(tile) {
+            self.tiles(tile).map(|tile| tile.info.cost).reduce(f32::max)
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if the given world position is on a walkable tile
+    pub fn is_walkable_at(&self, world: WorldPoint) -> bool {
+        self.tile_index(world).is_some_and(|tile| self.is_walkable(tile))
+    }
+
+    /// Returns the cost of walking at the given world position, 1 outside of the walkable tiles
+    pub fn cost_at(&self, world: WorldPoint) -> f32 {
+        self.tile_index(world).and_then(|tile| self.cost(tile)).unwrap_or(1.0)
+    }
+
+    /// Returns where a body moving from `from` to `to` ends up: at `to` if it is walkable,
+    /// otherwise sliding along the blocking tiles, or staying at `from`
+    pub fn walkable_position(&self, from: WorldPoint, to: WorldPoint) -> WorldPoint {
+        [to, Point::new(to.x, from.y), Point::new(from.x, to.y)]
+            .into_iter()
+            .find(|&position| self.is_walkable_at(position))
+            .unwrap_or(from)
+    }
+
+    /// Returns true if a tile of any layer at the given index blocks the sight
+    pub fn blocks_sight(&self, tile: (i32, i32)) -> bool {
+        self.tiles(tile).any(|tile| tile.blocks_sight())
+    }
+
+    /// Returns the first tile blocking the segment between two world positions, ignoring the
+    /// tile the segment starts from
+    pub fn raycast(&self, from: WorldPoint, to: WorldPoint) -> Option<(i32, i32)> {
+        let start = self.tile_index(from);
+        self.tiles_along(from, to)
+            .into_iter()
+            .filter(|&tile| Some(tile) != start)
+            .find(|&tile| self.blocks_sight(tile))
+    }
+
+    /// Returns true if nothing between the two world positions blocks the sight
+    pub fn line_of_sight(&self, from: WorldPoint, to: WorldPoint) -> bool {
+        match self.raycast(from, to) {
+            Some(tile) => Some(tile) == self.tile_index(to),
+            None => true,
+        }
+    }
+
+    /// Returns the world position of the center of a random walkable tile of the window, or of
+    /// the window if there is none
+    pub fn get_random_point<R: Rng>(&self, rng: &mut R) -> WorldPoint {
+        let ((left, top), size) = self.window();
+        let size = size as i32;
+        let walkable: Vec<(i32, i32)> = (left..left + size)
+            .flat_map(|column| (top..top + size).map(move |row| (column, row)))
+            .filter(|&tile| self.is_walkable(tile))
+            .collect();
+        match walkable.choose(rng) {
+            Some(&(column, row)) => self.projection.tile_center(column, row),
+            None => self.world_bounds().center(),
+        }
+    }
+
+    /// Returns the given position moved out of the footprints of the towers, for a body of the
+    /// given shape
+    pub fn push_out_of_towers(&self, mut position: WorldPoint, shape: &Shape) -> WorldPoint {
+        // Towers are much smaller than a chunk, so only the chunk of the position and the ones
+        // around it may hold a tower in contact
+        let ((cx, cy), _) = Chunk::locate(self.projection.tile_index(position));
+        let around = (cx - 1..=cx + 1).flat_map(|x| (cy - 1..=cy + 1).map(move |y| (x, y)));
+        let towers = around.filter_map(|index| self.chunks.get(&index)).flat_map(|chunk| chunk.towers.iter());
+        for tower in towers {
+            if let Some(contact) = tower.shape().contact(tower.position(), shape, position) {
+                position = position + contact.normal * contact.depth;
+            }
+        }
+        position
+    }
+}
+
+
+/// A tile of a layer, computed on demand from the frame ID the layer stores and the frame table
+#[derive(Debug, Clone, Copy)]
+pub struct Tile<'a> {
+    /// The index of the tile on the map grid
+    pub position: TilePoint,
+    /// The ID of the frame of the tile in the frame table
+    pub id: u16,
+    /// The name of the atlas frame of the tile
+    pub frame: &'a str,
+    /// What the tile means to the game
+    pub info: TileInfo,
+}
+
+impl Tile<'_> {
+    /// Returns true if the tile can't be seen through
+    pub fn blocks_sight(&self) -> bool {
+        self.info.blocks_sight
+    }
+
+    /// Returns true if a tower can be built on the tile: dry ground, easy to walk on
+    pub fn is_buildable(&self) -> bool {
+        self.info.is_buildable()
+    }
+}
+
+#[derive(Debug)]
+pub struct Layer {
+    pub level: i32,
+    /// The atlas of the frames of the layer
+    pub atlas: AtlasContent,
+    /// The frame ID of each tile of the chunk, by column then row, 0 where the layer is empty
+    pub tiles: Vec<u16>,
+}
+
+impl Layer {
+    /// Returns a layer of a chunk without any tile
+    pub fn empty(level: i32, atlas: AtlasContent) -> Layer {
+        Layer { level, atlas, tiles: vec![0; CHUNK_SIZE * CHUNK_SIZE] }
+    }
+
+    /// Returns the frame ID of the tile at the given index in the chunk, 0 if there is none
+    pub fn id(&self, column: usize, row: usize) -> u16 {
+        if column < CHUNK_SIZE && row < CHUNK_SIZE {
+            self.tiles[column * CHUNK_SIZE + row]
+        } else {
+            0
+        }
+    }
+
+    /// Sets the frame ID of the tile at the given index in the chunk
+    pub fn set(&mut self, column: usize, row: usize, id: u16) {
+        self.tiles[column * CHUNK_SIZE + row] = id;
+    }
+
+    /// Returns the index in the chunk and the frame ID of every tile of the layer, by column then
+    /// row, skipping the empty ones
+    pub fn ids(&self) -> impl Iterator<Item = ((usize, usize), u16)> + '_ {
+        self.tiles
+            .iter()
+            .enumerate()
+            .filter(|&(_, &id)| id != 0)
+            .map(|(index, &id)| ((index / CHUNK_SIZE, index % CHUNK_SIZE), id))
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use geometry::{AStar, FlowField, Neighbourhood, Real, Scalar, TilePath};
+
+use crate::{Map, TileTable};
+
+/// The number of path searches allowed in an update, the other requests wait for the next ones
+pub const SEARCHES_PER_UPDATE: usize = 8;
+/// The number of tiles a search explores before giving up
+const MAX_EXPLORED: usize = 4096;
+/// The number of paths kept, the cache is emptied when it grows bigger
+const CACHE_CAPACITY: usize = 512;
+/// The number of tiles the flow field explores in an update when its goal changes
+const FLOW_FIELD_TILES_PER_UPDATE: usize = 4096;
+
+/// The start and goal tiles of a path
+type PathKey = ((usize, usize), (usize, usize));
+
+/// A `Pathfinder` finds paths between the tiles of a map, remembers them, and limits the number
+/// of searches in each update
+///
+/// It also keeps a flow field leading every tile to a single goal, for the many bodies going to
+/// the same place. Searches run on the window of the map, the square of tiles loaded around the
+/// streaming center, indexed from its top tile: the paths and the field are dropped when the
+/// window moves.
+pub struct Pathfinder {
+    astar: AStar,
+    flow_field: FlowField,
+    /// The top tile and the size of the window of the map the searches run on
+    window: ((i32, i32), usize),
+    /// The paths found, by start and goal tiles of the window, `None` when there is no path
+    cache: HashMap<PathKey, Option<TilePath>>,
+    /// The number of searches left in the current update
+    budget: usize,
+}
+
+impl Pathfinder {
+    /// Returns a new `Pathfinder` for the given map, with the tile costs of the given table
+    pub fn new(map: &Map, tiles: &TileTable, neighbourhood: Neighbourhood) -> Pathfinder {
+        let mut astar = AStar::new(neighbourhood);
+        astar.max_explored = MAX_EXPLORED;
+        // The cheapest tile keeps the search from overestimating the remaining cost
+        astar.min_cost = Scalar::from_f32(tiles.min_cost());
+        let window = map.window();
+        Pathfinder {
+            astar,
+            flow_field: FlowField::new(window.1, window.1, neighbourhood),
+            window,
+            cache: HashMap::new(),
+            budget: SEARCHES_PER_UPDATE,
+        }
+    }
+
+    /// Gives back the searches of an update and leads the flow field to the goal, called once
+    /// at the start of each update
+    pub fn update(&mut self, map: &Map, goal: Option<(i32, i32)>) {
+        self.budget = SEARCHES_PER_UPDATE;
+        if map.window() != self.window {
+            self.window = map.window();
+            self.clear();
+        }
+        if let Some(goal) = goal.and_then(|goal| self.window_tile(goal)) {
+            let window = self.window;
+            self.flow_field.update(goal, FLOW_FIELD_TILES_PER_UPDATE, |tile| Self::cost(map, window, tile));
+        }
+    }
+
+    /// Returns the tile to step to from `from` to go to `to`: down the flow field, or along a
+    /// path when the field doesn't lead anywhere from there
+    pub fn next_tile(&mut self, map: &Map, from: (i32, i32), to: (i32, i32)) -> Option<(i32, i32)> {
+        let start = self.window_tile(from)?;
+        let next = match self.flow_field.next_tile(start) {
+            Some(next) => next,
+            // The first tile of the path is the start
+            None => *self.request(map, from, to)?.tiles.get(1)?,
+        };
+        Some(self.map_tile(next))
+    }
+
+    /// Returns the path between two tiles of the map, from the cache or from a new search
+    ///
+    /// The tiles of the path are indexed from the top of the window, `Map::window_projection`
+    /// converts them to the world space. Returns `None` if there is no path, if the tiles are
+    /// out of the window, or if there are no searches left in this update.
+    pub fn request(&mut self, map: &Map, from: (i32, i32), to: (i32, i32)) -> Option<&TilePath> {
+        let key = (self.window_tile(from)?, self.window_tile(to)?);
+        if !self.cache.contains_key(&key) {
+            if self.budget == 0 {
+                return None;
+            }
+            self.budget -= 1;
+            if self.cache.len() >= CACHE_CAPACITY {
+                self.cache.clear();
+            }
+            let window = self.window;
+            let path = self.astar.find_path(key.0, key.1, |tile| Self::cost(map, window, tile));
+            self.cache.insert(key, path);
+        }
+        self.cache[&key].as_ref()
+    }
+
+    /// Forgets the paths found and the flow field, to call when the map changes
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.flow_field = FlowField::new(self.window.1, self.window.1, self.astar.neighbourhood);
+    }
+
+    /// Returns the cost of a tile of the window, `None` out of it
+    fn cost(map: &Map, ((left, top), size): ((i32, i32), usize), (column, row): (usize, usize)) -> Option<Scalar> {
+        if column >= size || row >= size {
+            return None;
+        }
+        map.cost((left + column as i32, top + row as i32)).map(Scalar::from_f32)
+    }
+
+    /// Returns the index of a tile of the map in the window, if it is in it
+    fn window_tile(&self, (column, row): (i32, i32)) -> Option<(usize, usize)> {
+        let ((left, top), size) = self.window;
+        let (column, row) = (usize::try_from(column - left).ok()?, usize::try_from(row - top).ok()?);
+        (column < size && row < size).then_some((column, row))
+    }
+
+    /// Returns the index in the map of a tile of the window
+    fn map_tile(&self, (column, row): (usize, usize)) -> (i32, i32) {
+        let ((left, top), _) = self.window;
+        (left + column as i32, top + row as i32)
+    }
+}
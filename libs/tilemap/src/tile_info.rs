@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use std::fs::File;
+
+use ron::de::from_reader;
+use serde::Deserialize;
+
+/// What a tile means to the game
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct TileInfo {
+    /// True if entities can walk on the tile
+    pub walkable: bool,
+    /// How much slower walking on the tile is, 1 being plain ground, always positive
+    pub cost: f32,
+    /// True if the tile can't be seen through
+    pub blocks_sight: bool,
+    /// True if the tile is water
+    pub liquid: bool,
+}
+
+impl TileInfo {
+    /// A tower, standing in the way and in the line of sight
+    pub const TOWER: TileInfo = TileInfo {
+        walkable: false,
+        cost: 1.0,
+        blocks_sight: true,
+        liquid: false,
+    };
+
+    /// Returns true if a tower can be built on the tile: dry ground, easy to walk on
+    pub fn is_buildable(&self) -> bool {
+        self.walkable && !self.liquid && self.cost <= 1.0
+    }
+}
+
+impl Default for TileInfo {
+    /// Plain walkable ground
+    fn default() -> TileInfo {
+        TileInfo {
+            walkable: true,
+            cost: 1.0,
+            blocks_sight: false,
+            liquid: false,
+        }
+    }
+}
+
+/// The meaning of the landscape frames, loaded from `resources/tiles.ron`
+///
+/// Frames are looked up by name, a name ending with `*` applies to every frame starting with it,
+/// and frames missing from the table are plain ground
+#[derive(Debug, Clone, Deserialize)]
+#[serde(transparent)]
+pub struct TileTable {
+    frames: HashMap<String, TileInfo>,
+}
+
+impl TileTable {
+    /// Loads the tile table from the given RON file
+    pub fn load(path: &str) -> TileTable {
+        let file = File::open(path).expect("Tile table not found");
+        let table: TileTable = from_reader(file).expect("Failed to parse the tile table");
+        // Speeds are divided by the costs, and paths estimated with the lowest one
+        assert!(table.frames.values().all(|info| info.cost > 0.0), "every tile cost must be positive");
+        table
+    }
+
+    /// Returns the lowest cost of walking on a tile, plain ground included
+    pub fn min_cost(&self) -> f32 {
+        self.frames
+            .values()
+            .filter(|info| info.walkable)
+            .map(|info| info.cost)
+            .fold(TileInfo::default().cost, f32::min)
+    }
+
+    /// Returns the meaning of the given frame, the longest matching `*` pattern applying when
+    /// the name isn't in the table
+    pub fn get(&self, frame: &str) -> TileInfo {
+        if let Some(info) = self.frames.get(frame) {
+            return *info;
+        }
+        self.frames
+            .iter()
+            .filter_map(|(name, info)| name.strip_suffix('*').map(|prefix| (prefix, info)))
+            .filter(|(prefix, _)| frame.starts_with(prefix))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or_else(TileInfo::default, |(_, info)| *info)
+    }
+}
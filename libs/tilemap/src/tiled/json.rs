@@ -0,0 +1,237 @@
+//! The JSON format of Tiled, `.tmj` maps and `.tsj` tilesets
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{decode_gids, read_file, read_tileset as read_external_tileset, Chunk, Document, ImportError, Layer, Object, TileDef, Tileset};
+
+#[derive(Deserialize)]
+struct MapJson {
+    #[serde(default)]
+    orientation: String,
+    tileheight: u32,
+    #[serde(default)]
+    tilesets: Vec<TilesetJson>,
+    #[serde(default)]
+    layers: Vec<LayerJson>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct TilesetJson {
+    firstgid: u32,
+    /// The path of an external tileset, relative to the map
+    source: Option<String>,
+    name: String,
+    image: Option<String>,
+    tilewidth: u32,
+    tileheight: u32,
+    columns: u32,
+    margin: u32,
+    spacing: u32,
+    properties: Vec<PropertyJson>,
+    tiles: Vec<TileJson>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct TileJson {
+    id: u32,
+    image: Option<String>,
+    x: Option<i32>,
+    y: Option<i32>,
+    properties: Vec<PropertyJson>,
+}
+
+#[derive(Deserialize)]
+struct PropertyJson {
+    name: String,
+    value: Value,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct LayerJson {
+    #[serde(rename = "type")]
+    kind: String,
+    x: i32,
+    y: i32,
+    width: usize,
+    height: usize,
+    data: Option<DataJson>,
+    encoding: Option<String>,
+    compression: String,
+    /// The chunks of the layers of infinite maps
+    chunks: Vec<ChunkJson>,
+    objects: Vec<ObjectJson>,
+    /// The layers of a group
+    layers: Vec<LayerJson>,
+    properties: Vec<PropertyJson>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DataJson {
+    Gids(Vec<u32>),
+    Encoded(String),
+}
+
+#[derive(Deserialize)]
+struct ChunkJson {
+    x: i32,
+    y: i32,
+    width: usize,
+    height: usize,
+    data: DataJson,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ObjectJson {
+    name: String,
+    /// The type of the object, called class by some versions of Tiled
+    #[serde(rename = "type")]
+    kind: String,
+    class: String,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    polygon: Option<Vec<PointJson>>,
+}
+
+#[derive(Deserialize)]
+struct PointJson {
+    x: f64,
+    y: f64,
+}
+
+/// Reads a `.tmj` map and its external tilesets
+pub(super) fn read_map(path: &Path) -> Result<Document, ImportError> {
+    let map: MapJson = parse(path)?;
+    let directory = path.parent().unwrap_or(Path::new(""));
+    let tilesets = map
+        .tilesets
+        .into_iter()
+        .map(|tileset| {
+            let first_gid = tileset.firstgid;
+            match &tileset.source {
+                Some(source) => read_external_tileset(&directory.join(source), first_gid),
+                None => Ok(tileset.into_tileset(first_gid)),
+            }
+        })
+        .collect::<Result<_, _>>()?;
+    let mut layers = vec![];
+    for layer in map.layers {
+        layer.flatten_into(&mut layers)?;
+    }
+    Ok(Document { orientation: map.orientation, tile_height: map.tileheight, tilesets, layers })
+}
+
+/// Reads a `.tsj` tileset
+pub(super) fn read_tileset(path: &Path, first_gid: u32) -> Result<Tileset, ImportError> {
+    let tileset: TilesetJson = parse(path)?;
+    Ok(tileset.into_tileset(first_gid))
+}
+
+fn parse<T: DeserializeOwned>(path: &Path) -> Result<T, ImportError> {
+    serde_json::from_str(&read_file(path)?).map_err(|error| ImportError::new(format!("{}: {}", path.display(), error)))
+}
+
+fn properties(properties: Vec<PropertyJson>) -> HashMap<String, String> {
+    properties
+        .into_iter()
+        .map(|property| {
+            let value = match property.value {
+                Value::String(value) => value,
+                value => value.to_string(),
+            };
+            (property.name, value)
+        })
+        .collect()
+}
+
+impl TilesetJson {
+    fn into_tileset(self, first_gid: u32) -> Tileset {
+        let tiles = self
+            .tiles
+            .into_iter()
+            .map(|tile| {
+                let corner = tile.x.zip(tile.y);
+                (tile.id, TileDef { image: tile.image, corner, properties: properties(tile.properties) })
+            })
+            .collect();
+        Tileset {
+            name: self.name,
+            first_gid,
+            image: self.image,
+            tile_width: self.tilewidth,
+            tile_height: self.tileheight,
+            columns: self.columns,
+            margin: self.margin,
+            spacing: self.spacing,
+            properties: properties(self.properties),
+            tiles,
+        }
+    }
+}
+
+impl DataJson {
+    fn gids(self, encoding: &str, compression: &str) -> Result<Vec<u32>, ImportError> {
+        match self {
+            DataJson::Gids(gids) => Ok(gids),
+            DataJson::Encoded(text) => decode_gids(&text, encoding, compression),
+        }
+    }
+}
+
+impl LayerJson {
+    /// Adds the layer to the given ones, or the layers it holds if it is a group
+    fn flatten_into(self, layers: &mut Vec<Layer>) -> Result<(), ImportError> {
+        match self.kind.as_str() {
+            "tilelayer" => {
+                let encoding = self.encoding.as_deref().unwrap_or("csv");
+                let mut chunks = self
+                    .chunks
+                    .into_iter()
+                    .map(|chunk| {
+                        let gids = chunk.data.gids(encoding, &self.compression)?;
+                        Chunk::new(chunk.x, chunk.y, chunk.width, chunk.height, gids)
+                    })
+                    .collect::<Result<Vec<_>, ImportError>>()?;
+                if let Some(data) = self.data {
+                    let gids = data.gids(encoding, &self.compression)?;
+                    chunks.push(Chunk::new(self.x, self.y, self.width, self.height, gids)?);
+                }
+                layers.push(Layer::Tiles { properties: properties(self.properties), chunks });
+            }
+            "objectgroup" => {
+                let objects = self
+                    .objects
+                    .into_iter()
+                    .map(|object| Object {
+                        kind: if object.kind.is_empty() { object.class } else { object.kind },
+                        name: object.name,
+                        x: object.x,
+                        y: object.y,
+                        width: object.width,
+                        height: object.height,
+                        polygon: object.polygon.map(|points| points.iter().map(|point| (point.x, point.y)).collect()),
+                    })
+                    .collect();
+                layers.push(Layer::Objects(objects));
+            }
+            "group" => {
+                for layer in self.layers {
+                    layer.flatten_into(layers)?;
+                }
+            }
+            // Image layers don't hold anything the game uses
+            _ => {}
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,373 @@
+//! Import of the isometric maps of the Tiled editor
+//!
+//! Maps are read from the JSON (`.tmj`) and XML (`.tmx`) formats, finite or infinite, with
+//! embedded or external (`.tsj`, `.tsx`) tilesets. The tiles of the tilesets are matched with the
+//! frames of the atlases of the game:
+//!
+//! - a tile cut from an atlas image is the frame at the same place in the image (the top left
+//!   corner of the tile, or of its sub-rectangle)
+//! - a tile image named `<atlas>/<frame>.png`, `<atlas>` being the name of the atlas image
+//!   without extension, is the frame of that name
+//! - an `atlas` property on the tileset or the tile and a `frame` property on the tile name them
+//!   directly
+//!
+//! Landscape frames become the tiles of the layers of the map, tower frames become towers, and
+//! the `level` property of a tile layer sets its level (0 by default). Objects of the
+//! `PlayerSpawn` and `EnemySpawn` types become spawn points at their center, rectangles and
+//! polygons of the `Trigger` type become trigger zones, the other objects are ignored.
+use std::collections::hash_map::{Entry, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use base64::Engine;
+use flate2::read::{GzDecoder, ZlibDecoder};
+
+use geometry::{Real, Scalar, TilePoint, TilePolygon};
+
+use crate::{Atlas, AtlasContent, Faction, LayerFile, MapFile, MapObject, ObjectKind, TowerFile};
+
+mod json;
+mod tmx;
+
+/// The bits of a global tile ID holding the tile, the others flip or rotate it
+const GID_MASK: u32 = 0x0FFF_FFFF;
+
+/// Why a Tiled map couldn't be imported: a file that can't be read, or a map the game can't use
+#[derive(Debug)]
+pub struct ImportError(String);
+
+impl ImportError {
+    fn new(message: impl Into<String>) -> ImportError {
+        ImportError(message.into())
+    }
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for ImportError {}
+
+/// Imports the Tiled map at the given path, `.tmj` or `.tmx`, matching its tiles with the frames
+/// of the given atlases
+pub fn import(path: &str, atlases: &[Atlas]) -> Result<MapFile, ImportError> {
+    let path = Path::new(path);
+    let document = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("tmj") | Some("json") => json::read_map(path)?,
+        Some("tmx") => tmx::read_map(path)?,
+        _ => return Err(ImportError::new(format!("{} is not a Tiled map", path.display()))),
+    };
+    document.convert(atlases)
+}
+
+/// A Tiled map, read from either format
+#[derive(Debug, Default)]
+struct Document {
+    orientation: String,
+    /// The height of the tiles, the unit of the positions of the objects along both axes
+    tile_height: u32,
+    tilesets: Vec<Tileset>,
+    layers: Vec<Layer>,
+}
+
+#[derive(Debug, Default)]
+struct Tileset {
+    name: String,
+    /// The global ID of the first tile of the tileset
+    first_gid: u32,
+    /// The image the tiles are cut from, `None` for a collection of images
+    image: Option<String>,
+    tile_width: u32,
+    tile_height: u32,
+    columns: u32,
+    margin: u32,
+    spacing: u32,
+    properties: HashMap<String, String>,
+    /// The tiles with an image, a sub-rectangle or properties of their own, by ID
+    tiles: HashMap<u32, TileDef>,
+}
+
+#[derive(Debug, Default)]
+struct TileDef {
+    image: Option<String>,
+    /// The top left corner of the sub-rectangle of the image
+    corner: Option<(i32, i32)>,
+    properties: HashMap<String, String>,
+}
+
+#[derive(Debug)]
+enum Layer {
+    Tiles { properties: HashMap<String, String>, chunks: Vec<Chunk> },
+    Objects(Vec<Object>),
+}
+
+/// A rectangle of tiles of a layer, a whole finite layer or a chunk of an infinite one
+#[derive(Debug)]
+struct Chunk {
+    x: i32,
+    y: i32,
+    width: usize,
+    height: usize,
+    /// The global ID of each tile, row by row, 0 for the empty tiles
+    gids: Vec<u32>,
+}
+
+impl Chunk {
+    /// Returns a chunk of the given place and size, or an error if it doesn't hold a tile for
+    /// each of its cells
+    fn new(x: i32, y: i32, width: usize, height: usize, gids: Vec<u32>) -> Result<Chunk, ImportError> {
+        if gids.len() != width * height {
+            let message = format!("The chunk at ({}, {}) has {} tiles instead of {}x{}", x, y, gids.len(), width, height);
+            return Err(ImportError::new(message));
+        }
+        Ok(Chunk { x, y, width, height, gids })
+    }
+}
+
+#[derive(Debug, Default)]
+struct Object {
+    /// The type, or class, of the object
+    kind: String,
+    name: String,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    /// The vertices of a polygon object, relative to its position
+    polygon: Option<Vec<(f64, f64)>>,
+}
+
+/// What a tile of a tileset is in the game
+#[derive(Debug, Clone)]
+enum TileFrame {
+    Landscape(String),
+    Tower(Faction, String),
+}
+
+impl Document {
+    /// Converts the map to a map file
+    fn convert(&self, atlases: &[Atlas]) -> Result<MapFile, ImportError> {
+        if self.orientation != "isometric" {
+            return Err(ImportError::new(format!("The map is {}, not isometric", self.orientation)));
+        }
+        let chunks = self.layers.iter().flat_map(|layer| match layer {
+            Layer::Tiles { chunks, .. } => chunks.as_slice(),
+            Layer::Objects(_) => &[],
+        });
+        let (mut first, mut last) = ((i32::MAX, i32::MAX), (i32::MIN, i32::MIN));
+        for chunk in chunks.filter(|chunk| chunk.width > 0 && chunk.height > 0) {
+            first = (first.0.min(chunk.x), first.1.min(chunk.y));
+            last = (last.0.max(chunk.x + chunk.width as i32), last.1.max(chunk.y + chunk.height as i32));
+        }
+        if first.0 > last.0 {
+            return Err(ImportError::new("The map has no tiles"));
+        }
+        let size = ((last.0 - first.0) as usize, (last.1 - first.1) as usize);
+        let mut file = MapFile::new(None, first, size);
+
+        let mut frames: HashMap<u32, TileFrame> = HashMap::new();
+        for layer in &self.layers {
+            match layer {
+                Layer::Tiles { properties, chunks } => {
+                    let level = match properties.get("level") {
+                        Some(level) => level.parse().map_err(|_| ImportError::new(format!("Invalid layer level {:?}", level)))?,
+                        None => 0,
+                    };
+                    let mut tiles = vec![vec![0; size.0]; size.1];
+                    let mut landscape = false;
+                    for chunk in chunks {
+                        for (index, &gid) in chunk.gids.iter().enumerate() {
+                            let gid = gid & GID_MASK;
+                            if gid == 0 {
+                                continue;
+                            }
+                            let column = chunk.x + (index % chunk.width) as i32;
+                            let row = chunk.y + (index / chunk.width) as i32;
+                            let frame = match frames.entry(gid) {
+                                Entry::Occupied(entry) => entry.into_mut(),
+                                Entry::Vacant(entry) => entry.insert(self.tile_frame(gid, atlases)?),
+                            };
+                            match frame {
+                                TileFrame::Landscape(frame) => {
+                                    tiles[(row - first.1) as usize][(column - first.0) as usize] = file.frame_id(frame);
+                                    landscape = true;
+                                }
+                                TileFrame::Tower(faction, frame) => file.towers.push(TowerFile {
+                                    tile: (column, row),
+                                    faction: *faction,
+                                    frame: frame.clone(),
+                                }),
+                            }
+                        }
+                    }
+                    if landscape {
+                        file.layers.push(LayerFile { level, tiles });
+                    }
+                }
+                Layer::Objects(objects) => {
+                    for object in objects {
+                        if let Some(object) = self.map_object(object)? {
+                            file.objects.push(object);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(file)
+    }
+
+    /// Returns what the tile of the given global ID is in the game
+    fn tile_frame(&self, gid: u32, atlases: &[Atlas]) -> Result<TileFrame, ImportError> {
+        let tileset = self
+            .tilesets
+            .iter()
+            .filter(|tileset| tileset.first_gid <= gid)
+            .max_by_key(|tileset| tileset.first_gid)
+            .ok_or_else(|| ImportError::new(format!("No tileset holds the tile {}", gid)))?;
+        let (atlas, frame) = tileset.match_frame(gid - tileset.first_gid, atlases)?;
+        Ok(match atlas.content {
+            AtlasContent::Landscape => TileFrame::Landscape(frame),
+            AtlasContent::Towers(faction) => TileFrame::Tower(faction, frame),
+        })
+    }
+
+    /// Converts an object to a map object, `None` for the objects the game doesn't use
+    fn map_object(&self, object: &Object) -> Result<Option<MapObject>, ImportError> {
+        let kind = match object.kind.as_str() {
+            "PlayerSpawn" => ObjectKind::PlayerSpawn,
+            "EnemySpawn" => ObjectKind::EnemySpawn,
+            "Trigger" => ObjectKind::Trigger,
+            _ => return Ok(None),
+        };
+        // Objects are placed in pixels along the axes of the tiles, a tile being as long as the
+        // tiles are high
+        let unit = self.tile_height.max(1) as f64;
+        let point = |x: f64, y: f64| TilePoint::new(Scalar::from_f32((x / unit) as f32), Scalar::from_f32((y / unit) as f32));
+
+        let vertices: Vec<(f64, f64)> = match &object.polygon {
+            Some(polygon) => polygon.iter().map(|&(x, y)| (object.x + x, object.y + y)).collect(),
+            None if object.width > 0.0 && object.height > 0.0 => {
+                let (right, bottom) = (object.x + object.width, object.y + object.height);
+                vec![(object.x, object.y), (right, object.y), (right, bottom), (object.x, bottom)]
+            }
+            None => vec![(object.x, object.y)],
+        };
+        let count = vertices.len() as f64;
+        let (x, y) = vertices.iter().fold((0.0, 0.0), |(x, y), vertex| (x + vertex.0 / count, y + vertex.1 / count));
+
+        let mut map_object = MapObject::new(kind, point(x, y));
+        map_object.name = object.name.clone();
+        if kind == ObjectKind::Trigger {
+            if vertices.len() < 3 {
+                return Err(ImportError::new(format!("The trigger {:?} has no area", object.name)));
+            }
+            map_object.area = Some(TilePolygon::new(vertices.iter().map(|&(x, y)| point(x, y)).collect()));
+        }
+        Ok(Some(map_object))
+    }
+}
+
+impl Tileset {
+    /// Returns the atlas and the name of the frame the tile of the given ID matches
+    fn match_frame<'a>(&self, id: u32, atlases: &'a [Atlas]) -> Result<(&'a Atlas, String), ImportError> {
+        let tile = self.tiles.get(&id);
+        let image = tile.and_then(|tile| tile.image.as_deref()).or(self.image.as_deref());
+        let frame = tile.and_then(|tile| tile.properties.get("frame")).cloned();
+        let unmatched = || ImportError::new(format!("The tile {} of the tileset {:?} matches no atlas frame", id, self.name));
+
+        let (atlas, frame) = if let Some(name) = tile.and_then(|tile| tile.properties.get("atlas")).or(self.properties.get("atlas")) {
+            let atlas = atlases
+                .iter()
+                .find(|atlas| file_stem(&atlas.image) == Some(name))
+                .ok_or_else(|| ImportError::new(format!("Unknown atlas {:?} in the tileset {:?}", name, self.name)))?;
+            (atlas, frame)
+        } else if let Some(atlas) = image.and_then(|image| atlases.iter().find(|atlas| file_name(&atlas.image) == file_name(image))) {
+            // The tile is cut from the atlas image
+            let corner = tile.and_then(|tile| tile.corner).unwrap_or_else(|| self.grid_corner(id));
+            let frame = frame.or_else(|| atlas.frames.iter().find(|(_, at)| *at == corner).map(|(name, _)| name.clone()));
+            (atlas, frame)
+        } else {
+            // The tile image is named after the frame, in a directory named after the atlas
+            let image = Path::new(image.ok_or_else(unmatched)?);
+            let directory = image.parent().and_then(|parent| parent.file_name()).and_then(|name| name.to_str());
+            let atlas = atlases
+                .iter()
+                .find(|atlas| directory.is_some() && file_stem(&atlas.image) == directory)
+                .ok_or_else(unmatched)?;
+            (atlas, frame.or_else(|| image.file_stem().and_then(|stem| stem.to_str()).map(String::from)))
+        };
+
+        let frame = frame.ok_or_else(unmatched)?;
+        if !atlas.frames.iter().any(|(name, _)| *name == frame) {
+            return Err(ImportError::new(format!("Unknown frame {:?} of the atlas {} in the tileset {:?}", frame, atlas.image, self.name)));
+        }
+        Ok((atlas, frame))
+    }
+
+    /// Returns the top left corner of the tile of the given ID in the image of the tileset
+    fn grid_corner(&self, id: u32) -> (i32, i32) {
+        let (column, row) = (id % self.columns.max(1), id / self.columns.max(1));
+        (
+            (self.margin + column * (self.tile_width + self.spacing)) as i32,
+            (self.margin + row * (self.tile_height + self.spacing)) as i32,
+        )
+    }
+}
+
+fn file_name(path: &str) -> Option<&str> {
+    Path::new(path).file_name().and_then(|name| name.to_str())
+}
+
+fn file_stem(path: &str) -> Option<&str> {
+    Path::new(path).file_stem().and_then(|stem| stem.to_str())
+}
+
+/// Reads a whole file, the error telling which one failed
+fn read_file(path: &Path) -> Result<String, ImportError> {
+    fs::read_to_string(path).map_err(|error| ImportError::new(format!("{}: {}", path.display(), error)))
+}
+
+/// Reads the external tileset at the given path, `.tsj` or `.tsx`
+fn read_tileset(path: &Path, first_gid: u32) -> Result<Tileset, ImportError> {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("tsj") | Some("json") => json::read_tileset(path, first_gid),
+        Some("tsx") => tmx::read_tileset(path, first_gid),
+        _ => Err(ImportError::new(format!("{} is not a Tiled tileset", path.display()))),
+    }
+}
+
+/// Decodes the global IDs of the tiles of a layer, written as CSV or as compressed or plain
+/// base64
+fn decode_gids(text: &str, encoding: &str, compression: &str) -> Result<Vec<u32>, ImportError> {
+    match encoding {
+        "csv" => text
+            .split(',')
+            .filter(|gid| !gid.trim().is_empty())
+            .map(|gid| gid.trim().parse().map_err(|_| ImportError::new(format!("Invalid tile {:?}", gid.trim()))))
+            .collect(),
+        "base64" => {
+            let text: String = text.chars().filter(|char| !char.is_whitespace()).collect();
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(text)
+                .map_err(|error| ImportError::new(format!("Invalid base64 tiles: {}", error)))?;
+            let mut data = vec![];
+            let inflated = match compression {
+                "" => {
+                    data = bytes;
+                    Ok(0)
+                }
+                "zlib" => ZlibDecoder::new(bytes.as_slice()).read_to_end(&mut data),
+                "gzip" => GzDecoder::new(bytes.as_slice()).read_to_end(&mut data),
+                _ => return Err(ImportError::new(format!("Unsupported tile compression {:?}", compression))),
+            };
+            inflated.map_err(|error| ImportError::new(format!("Invalid {} tiles: {}", compression, error)))?;
+            Ok(data.chunks_exact(4).map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])).collect())
+        }
+        _ => Err(ImportError::new(format!("Unsupported tile encoding {:?}", encoding))),
+    }
+}
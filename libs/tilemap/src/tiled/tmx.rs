@@ -0,0 +1,191 @@
+//! The XML format of Tiled, `.tmx` maps and `.tsx` tilesets
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use roxmltree::Node;
+
+use super::{decode_gids, read_file, read_tileset as read_external_tileset, Chunk, Document, ImportError, Layer, Object, TileDef, Tileset};
+
+/// Reads a `.tmx` map and its external tilesets
+pub(super) fn read_map(path: &Path) -> Result<Document, ImportError> {
+    let text = read_file(path)?;
+    let xml = parse(path, &text)?;
+    let map = root(path, &xml, "map")?;
+    let directory = path.parent().unwrap_or(Path::new(""));
+
+    let mut document = Document {
+        orientation: map.attribute("orientation").unwrap_or_default().to_string(),
+        tile_height: number(map, "tileheight")?,
+        ..Document::default()
+    };
+    for node in elements(map) {
+        match node.tag_name().name() {
+            "tileset" => {
+                let first_gid = number(node, "firstgid")?;
+                let tileset = match node.attribute("source") {
+                    Some(source) => read_external_tileset(&directory.join(source), first_gid)?,
+                    None => read_tileset_node(node, first_gid)?,
+                };
+                document.tilesets.push(tileset);
+            }
+            _ => read_layers(node, &mut document.layers)?,
+        }
+    }
+    Ok(document)
+}
+
+/// Reads a `.tsx` tileset
+pub(super) fn read_tileset(path: &Path, first_gid: u32) -> Result<Tileset, ImportError> {
+    let text = read_file(path)?;
+    let xml = parse(path, &text)?;
+    read_tileset_node(root(path, &xml, "tileset")?, first_gid)
+}
+
+fn parse<'a>(path: &Path, text: &'a str) -> Result<roxmltree::Document<'a>, ImportError> {
+    roxmltree::Document::parse(text).map_err(|error| ImportError::new(format!("{}: {}", path.display(), error)))
+}
+
+/// Returns the root element of the document, checking its name
+fn root<'a, 'input>(path: &Path, xml: &'a roxmltree::Document<'input>, name: &str) -> Result<Node<'a, 'input>, ImportError> {
+    let root = xml.root_element();
+    if root.tag_name().name() != name {
+        return Err(ImportError::new(format!("{}: no {} element", path.display(), name)));
+    }
+    Ok(root)
+}
+
+/// Returns the child elements of a node
+fn elements<'a, 'input>(node: Node<'a, 'input>) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.children().filter(Node::is_element)
+}
+
+/// Returns the child element of the given name
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    elements(node).find(|child| child.tag_name().name() == name)
+}
+
+/// Parses a numeric attribute, 0 when it is missing
+fn number<T: FromStr + Default>(node: Node, name: &str) -> Result<T, ImportError> {
+    match node.attribute(name) {
+        Some(value) => value
+            .parse()
+            .map_err(|_| ImportError::new(format!("Invalid {} {:?} of a {} element", name, value, node.tag_name().name()))),
+        None => Ok(T::default()),
+    }
+}
+
+/// Reads the `properties` child of a node
+fn properties(node: Node) -> HashMap<String, String> {
+    child(node, "properties")
+        .into_iter()
+        .flat_map(elements)
+        .filter_map(|property| {
+            // Multiline string values are the text of the property
+            let value = property.attribute("value").or_else(|| property.text())?;
+            Some((property.attribute("name")?.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+fn read_tileset_node(node: Node, first_gid: u32) -> Result<Tileset, ImportError> {
+    let image = |node: Node| child(node, "image").and_then(|image| image.attribute("source")).map(String::from);
+    let mut tiles = HashMap::new();
+    for tile in elements(node).filter(|tile| tile.tag_name().name() == "tile") {
+        let corner = match (tile.attribute("x"), tile.attribute("y")) {
+            (None, None) => None,
+            _ => Some((number(tile, "x")?, number(tile, "y")?)),
+        };
+        tiles.insert(number(tile, "id")?, TileDef { image: image(tile), corner, properties: properties(tile) });
+    }
+    Ok(Tileset {
+        name: node.attribute("name").unwrap_or_default().to_string(),
+        first_gid,
+        image: image(node),
+        tile_width: number(node, "tilewidth")?,
+        tile_height: number(node, "tileheight")?,
+        columns: number(node, "columns")?,
+        margin: number(node, "margin")?,
+        spacing: number(node, "spacing")?,
+        properties: properties(node),
+        tiles,
+    })
+}
+
+/// Adds the layer of the given node to the given ones, or the layers it holds if it is a group
+fn read_layers(node: Node, layers: &mut Vec<Layer>) -> Result<(), ImportError> {
+    match node.tag_name().name() {
+        "layer" => {
+            let mut chunks = vec![];
+            if let Some(data) = child(node, "data") {
+                let (encoding, compression) = (data.attribute("encoding").unwrap_or_default(), data.attribute("compression").unwrap_or_default());
+                let mut chunk_nodes = elements(data).filter(|chunk| chunk.tag_name().name() == "chunk").peekable();
+                if chunk_nodes.peek().is_none() {
+                    let (width, height) = (number(node, "width")?, number(node, "height")?);
+                    chunks.push(Chunk::new(number(node, "x")?, number(node, "y")?, width, height, read_gids(data, encoding, compression)?)?);
+                }
+                for chunk in chunk_nodes {
+                    chunks.push(Chunk::new(
+                        number(chunk, "x")?,
+                        number(chunk, "y")?,
+                        number(chunk, "width")?,
+                        number(chunk, "height")?,
+                        read_gids(chunk, encoding, compression)?,
+                    )?);
+                }
+            }
+            layers.push(Layer::Tiles { properties: properties(node), chunks });
+        }
+        "objectgroup" => {
+            let objects = elements(node)
+                .filter(|object| object.tag_name().name() == "object")
+                .map(read_object)
+                .collect::<Result<_, _>>()?;
+            layers.push(Layer::Objects(objects));
+        }
+        "group" => {
+            for node in elements(node) {
+                read_layers(node, layers)?;
+            }
+        }
+        // Image layers and the properties of the map don't hold anything the game uses
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Reads the tiles of a `data` or `chunk` node: encoded text, or a `tile` element for each tile
+fn read_gids(node: Node, encoding: &str, compression: &str) -> Result<Vec<u32>, ImportError> {
+    if encoding.is_empty() {
+        elements(node).filter(|tile| tile.tag_name().name() == "tile").map(|tile| number(tile, "gid")).collect()
+    } else {
+        decode_gids(node.text().unwrap_or_default(), encoding, compression)
+    }
+}
+
+fn read_object(node: Node) -> Result<Object, ImportError> {
+    // The type of the object is called class by some versions of Tiled
+    let kind = node.attribute("type").or_else(|| node.attribute("class")).unwrap_or_default();
+    let polygon = match child(node, "polygon").and_then(|polygon| polygon.attribute("points")) {
+        Some(points) => Some(
+            points
+                .split_whitespace()
+                .map(|point| {
+                    let invalid = || ImportError::new(format!("Invalid polygon point {:?}", point));
+                    let (x, y) = point.split_once(',').ok_or_else(invalid)?;
+                    Ok((x.parse().map_err(|_| invalid())?, y.parse().map_err(|_| invalid())?))
+                })
+                .collect::<Result<_, ImportError>>()?,
+        ),
+        None => None,
+    };
+    Ok(Object {
+        kind: kind.to_string(),
+        name: node.attribute("name").unwrap_or_default().to_string(),
+        x: number(node, "x")?,
+        y: number(node, "y")?,
+        width: number(node, "width")?,
+        height: number(node, "height")?,
+        polygon,
+    })
+}
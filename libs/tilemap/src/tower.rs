@@ -0,0 +1,36 @@
+use geometry::{scalar, Collide, Scalar, Shape, WorldPoint, WorldSize};
+use geometry_derive::Position;
+
+use crate::Faction;
+
+/// The size of the diamond a tower stands on, smaller than a tile so entities can walk around it
+const TOWER_FOOTPRINT: WorldSize = WorldSize::new(scalar!(64.0), scalar!(32.0));
+
+/// A `Tower` stands on a map tile and blocks the way of the entities
+#[derive(Debug, Position)]
+#[position(field = "position")]
+pub struct Tower {
+    /// The index of the tile the tower stands on
+    pub tile: (i32, i32),
+    /// The center of the tile, in world space
+    pub position: WorldPoint,
+    pub faction: Faction,
+    /// The name of the frame of the tower in the atlas of its faction
+    pub frame: String,
+}
+
+impl Tower {
+    pub fn new(tile: (i32, i32), position: WorldPoint, faction: Faction, frame: String) -> Tower {
+        Tower { tile, position, faction, frame }
+    }
+}
+
+impl Collide for Tower {
+    fn radius(&self) -> Scalar {
+        TOWER_FOOTPRINT.width / scalar!(2.0)
+    }
+
+    fn shape(&self) -> Shape {
+        Shape::diamond(TOWER_FOOTPRINT)
+    }
+}
@@ -0,0 +1,79 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use tilemap::{Biome, BiomeRule, BiomeTable, Climate};
+
+/// A small table: an ocean, a beach band at the coast, mountains up high and forests where it
+/// is wet, grasslands everywhere else
+const TABLE: &str = r#"#![enable(implicit_some)]
+[
+    (biome: Ocean, tiles: [("00", 1)]),
+    (biome: Beach, elevation: (0.0, 0.2), tiles: [("06", 1)]),
+    (biome: Mountain, elevation: (1.0, 9.0), tiles: [("rocks_1", 1)]),
+    (biome: Forest, moisture: (0.1, 9.0), tiles: [("trees_1", 1)]),
+    (biome: Grassland, tiles: [("28", 1)]),
+]"#;
+
+fn climate(elevation: f64, moisture: f64) -> Climate {
+    Climate { elevation, moisture, temperature: 0.0 }
+}
+
+#[test]
+fn the_first_matching_rule_gives_the_biome() {
+    let table: BiomeTable = ron::from_str(TABLE).unwrap();
+    let cases = [
+        // (elevation, moisture, sea level, biome)
+        (-0.8, 0.0, -0.5, Biome::Ocean),
+        (-0.4, 0.5, -0.5, Biome::Beach),
+        (-0.3, 0.5, -0.5, Biome::Forest),
+        (0.0, 0.0, -0.5, Biome::Grassland),
+        (0.6, 0.5, -0.5, Biome::Mountain),
+        // The coast follows the sea level
+        (-0.4, 0.0, 0.0, Biome::Ocean),
+        (0.1, 0.0, 0.0, Biome::Beach),
+        (0.1, 0.0, -0.5, Biome::Grassland),
+        (0.6, 0.0, 0.0, Biome::Grassland),
+    ];
+    for (elevation, moisture, sea_level, biome) in cases {
+        let rule = table.rule(&climate(elevation, moisture), sea_level);
+        assert_eq!(rule.biome, biome, "elevation {} moisture {} sea level {}", elevation, moisture, sea_level);
+    }
+}
+
+#[test]
+fn rules_match_half_open_ranges_and_anything_when_a_range_is_missing() {
+    let rule = BiomeRule {
+        biome: Biome::CrystalFields,
+        elevation: Some((0.5, 1.0)),
+        moisture: None,
+        temperature: Some((-9.0, -0.4)),
+        tiles: vec![("crystals_1".to_string(), 1)],
+    };
+    let cold = |elevation, moisture| Climate { elevation, moisture, temperature: -1.0 };
+    assert!(rule.matches(&cold(0.5, 0.0)));
+    assert!(rule.matches(&cold(0.9, -5.0)));
+    assert!(rule.matches(&cold(0.9, 5.0)));
+    assert!(!rule.matches(&cold(1.0, 0.0)));
+    assert!(!rule.matches(&cold(0.4, 0.0)));
+    assert!(!rule.matches(&Climate { temperature: -0.4, ..cold(0.7, 0.0) }));
+}
+
+#[test]
+fn tiles_are_picked_by_weight() {
+    let rule = BiomeRule {
+        biome: Biome::Grassland,
+        elevation: None,
+        moisture: None,
+        temperature: None,
+        tiles: vec![("28".to_string(), 3), ("19".to_string(), 1), ("16".to_string(), 0)],
+    };
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut counts = [0; 3];
+    for _ in 0..10_000 {
+        let frame = rule.pick_tile(&mut rng);
+        counts[rule.tiles.iter().position(|(name, _)| name == frame).unwrap()] += 1;
+    }
+    assert!((7_200..7_800).contains(&counts[0]), "{:?}", counts);
+    assert_eq!(counts[0] + counts[1], 10_000);
+    assert_eq!(counts[2], 0);
+}
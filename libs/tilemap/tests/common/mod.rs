@@ -0,0 +1,51 @@
+//! The fixtures shared by the tests of the maps
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use geometry::{scalar, IsoProjection, Scalar, WorldSize};
+use tilemap::{LayerFile, Map, MapAssets, MapFile};
+
+pub const TILE_SIZE: WorldSize = WorldSize::new(scalar!(128.0), scalar!(64.0));
+pub const SCALE: Scalar = scalar!(0.6);
+
+/// The frames of the test maps, by the letter drawing them, a space leaving the tile empty
+const FRAMES: [(char, &str); 6] = [('.', "28"), ('C', "crystals_1"), ('R', "rocks_1"), ('W', "00"), ('T', "trees_1"), ('D', "02")];
+
+/// Returns the path of a file of the resources of the game
+pub fn resource(path: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../resources").join(path);
+    path.to_str().unwrap().to_string()
+}
+
+pub fn assets() -> Arc<MapAssets> {
+    Arc::new(MapAssets::load(resource("")))
+}
+
+pub fn projection() -> IsoProjection {
+    IsoProjection::new(TILE_SIZE, SCALE)
+}
+
+/// Builds a map file from layers of rows of letters: `.` ground, `C` crystals blocking the
+/// sight, `R` rocks, `W` water, `T` trees and `D` dirt
+pub fn layered_file(layers: &[&[&str]]) -> MapFile {
+    let mut file = MapFile::new(None, (0, 0), (layers[0][0].len(), layers[0].len()));
+    file.frames = FRAMES.iter().map(|&(_, frame)| frame.to_string()).collect();
+    for (level, rows) in layers.iter().enumerate() {
+        let id = |letter| FRAMES.iter().position(|&(l, _)| l == letter).map_or(0, |index| index as u16 + 1);
+        let tiles = rows.iter().map(|row| row.chars().map(id).collect()).collect();
+        file.layers.push(LayerFile { level: level as i32, tiles });
+    }
+    file
+}
+
+/// Builds a map from layers of rows of letters, see `layered_file`
+pub fn layered_map(layers: &[&[&str]]) -> Map {
+    Map::from_file(&layered_file(layers), assets(), projection()).unwrap()
+}
+
+/// Builds a map from rows of letters, see `layered_file`
+pub fn map(rows: &[&str]) -> Map {
+    layered_map(&[rows])
+}
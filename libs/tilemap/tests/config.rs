@@ -0,0 +1,68 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use tilemap::{GeneratorKind, Map, MapGenConfig};
+
+use common::{assets, projection, resource};
+
+mod common;
+
+#[test]
+fn missing_fields_keep_their_default_value() {
+    let path = std::env::temp_dir().join("tilemap_partial_mapgen.ron");
+    std::fs::write(&path, "(seed: Some(7), scale: 10.0, generator: Island)").unwrap();
+    let config = MapGenConfig::load(path.to_str().unwrap());
+    std::fs::remove_file(&path).unwrap();
+
+    let default = MapGenConfig::default();
+    assert_eq!(config.seed, Some(7));
+    assert_eq!(config.scale, 10.0);
+    assert_eq!(config.generator, GeneratorKind::Island);
+    assert_eq!((config.octaves, config.sea_level, config.size), (default.octaves, default.sea_level, default.size));
+
+    // The config of the game leaves the seed out, to get a new map each game
+    assert_eq!(MapGenConfig::load(&resource("mapgen.ron")).seed, None);
+}
+
+#[test]
+fn the_climate_only_depends_on_the_seed() {
+    let config = MapGenConfig::default();
+    let climates = |seed| {
+        let noise = config.climate_noise(seed);
+        (-20..20)
+            .flat_map(|column| (-20..20).map(move |row| (column, row)))
+            .map(|(column, row)| {
+                let climate = noise.at(column, row);
+                (climate.elevation, climate.moisture, climate.temperature)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let climate = climates(42);
+    assert_eq!(climate, climates(42));
+    assert_ne!(climate, climates(43));
+    assert!(climate.iter().all(|&(elevation, _, _)| (-1.5..=1.5).contains(&elevation)));
+    // The three noises are different maps
+    assert!(climate.iter().any(|&(elevation, moisture, temperature)| elevation != moisture && moisture != temperature));
+}
+
+#[test]
+fn the_same_seed_always_gives_the_same_map() {
+    let assets = assets();
+    let config = MapGenConfig { seed: Some(42), ..MapGenConfig::default() };
+    let generate = |rng_seed| {
+        Map::new(&config, assets.clone(), projection(), &mut StdRng::seed_from_u64(rng_seed)).unwrap().to_file()
+    };
+
+    // The seed of the config wins over the random number generator of the game
+    assert_eq!(generate(1), generate(2));
+
+    // Without a seed, one is drawn for each map
+    let unseeded = MapGenConfig::default();
+    let mut rng = StdRng::seed_from_u64(1);
+    let projection = projection();
+    let first = Map::new(&unseeded, assets.clone(), projection, &mut rng).unwrap();
+    let second = Map::new(&unseeded, assets.clone(), projection, &mut rng).unwrap();
+    assert_ne!(first.seed, second.seed);
+    assert_ne!(first.to_file().layers, second.to_file().layers);
+}
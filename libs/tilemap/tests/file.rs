@@ -0,0 +1,80 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use tilemap::{Faction, Map, MapFile, MapSource, TowerFile};
+
+use common::{assets, layered_file, projection, resource};
+
+mod common;
+
+/// Returns the error of building the map of the file, which must fail
+fn error(file: &MapFile) -> String {
+    Map::from_file(file, assets(), projection()).unwrap_err().to_string()
+}
+
+/// A change breaking a map file, with what the error says about it
+type Breakage = (&'static str, fn(&mut MapFile));
+
+fn tower(tile: (i32, i32), frame: &str) -> TowerFile {
+    TowerFile { tile, faction: Faction::Red, frame: frame.to_string() }
+}
+
+#[test]
+fn files_with_tiles_that_do_not_fit_are_reported() {
+    let file = layered_file(&[&["...", "..."], &["T  ", "  T"]]);
+    assert!(file.validate().is_ok());
+    assert!(Map::from_file(&file, assets(), projection()).is_ok());
+
+    let cases: [Breakage; 7] = [
+        ("version 2 is not supported", |file| file.header.version = 2),
+        ("has no tiles", |file| file.header.size = (0, 2)),
+        ("The layer 1 has 1 rows instead of 2", |file| {
+            file.layers[1].tiles.pop();
+        }),
+        ("The row 1 of the layer 0 has 2 tiles instead of 3", |file| {
+            file.layers[0].tiles[1].pop();
+        }),
+        ("The tile 9 at (2, 0) of the layer 1 is out of the frame table of 6 frames", |file| file.layers[1].tiles[0][2] = 9),
+        ("Unknown landscape frame \"no_such_frame\"", |file| file.frames[3] = "no_such_frame".to_string()),
+        ("The tower at (3, 0) is out of the map", |file| file.towers.push(tower((3, 0), "00"))),
+    ];
+    for (message, change) in cases {
+        let mut broken = file.clone();
+        change(&mut broken);
+        let error = error(&broken);
+        assert!(error.contains(message), "{:?} doesn't say {:?}", error, message);
+    }
+
+    let mut unknown_tower = file.clone();
+    unknown_tower.towers.push(tower((1, 1), "no_such_tower"));
+    assert!(unknown_tower.validate().is_ok());
+    assert!(error(&unknown_tower).contains("Unknown tower frame \"no_such_tower\""));
+}
+
+#[test]
+fn loading_reports_unreadable_and_invalid_files() {
+    let path = |name: &str| std::env::temp_dir().join(format!("tilemap-{}-{}", std::process::id(), name));
+
+    let missing = path("missing.ron");
+    let error = MapFile::load(missing.to_str().unwrap()).unwrap_err().to_string();
+    assert!(error.contains("missing.ron"), "{}", error);
+
+    let malformed = path("malformed.ron");
+    std::fs::write(&malformed, "(header: (version: 1, size: (2, 2)), frames: [\"28\"], layers: [(level: 0, tiles: [[1, 1], [1, ").unwrap();
+    let error = MapFile::load(malformed.to_str().unwrap()).unwrap_err().to_string();
+    std::fs::remove_file(&malformed).unwrap();
+    assert!(error.contains("malformed.ron"), "{}", error);
+
+    let short_row = path("short_row.ron");
+    std::fs::write(&short_row, "(header: (version: 1, size: (2, 2)), frames: [\"28\"], layers: [(level: 0, tiles: [[1, 1], [1]])])").unwrap();
+    let error = MapFile::load(short_row.to_str().unwrap()).unwrap_err().to_string();
+    std::fs::remove_file(&short_row).unwrap();
+    assert!(error.contains("The row 1 of the layer 0 has 1 tiles instead of 2"), "{}", error);
+}
+
+#[test]
+fn tiled_maps_that_cannot_be_imported_are_reported() {
+    let source = MapSource::Tiled(resource("maps/tiled/no_such_map.tmx"));
+    let error = Map::from_source(&source, assets(), projection(), &mut StdRng::seed_from_u64(0)).unwrap_err();
+    assert!(error.to_string().starts_with("Failed to import the Tiled map"), "{}", error);
+}
@@ -0,0 +1,228 @@
+use std::collections::{HashSet, VecDeque};
+use std::fs::File;
+use std::sync::Arc;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::Deserialize;
+
+use geometry::{scalar, Collide, Position, Shape, WorldPoint};
+use tilemap::{AtlasContent, GeneratorKind, Map, MapAssets, MapFile, MapFileError, MapGenConfig, ObjectKind, CHUNK_SIZE};
+
+use common::{assets, projection, resource};
+
+mod common;
+
+fn config(generator: GeneratorKind, seed: u32) -> MapGenConfig {
+    MapGenConfig { seed: Some(seed), generator, size: (48, 32), wfc_rules: resource("wfc.ron"), ..MapGenConfig::default() }
+}
+
+fn generate(assets: &Arc<MapAssets>, config: &MapGenConfig) -> Map {
+    generate_map(assets, config).unwrap()
+}
+
+fn generate_map(assets: &Arc<MapAssets>, config: &MapGenConfig) -> Result<Map, MapFileError> {
+    Map::new(config, assets.clone(), projection(), &mut StdRng::seed_from_u64(0))
+}
+
+/// A tile of the adjacency rules of the wave function collapse generator
+#[derive(Deserialize)]
+struct TileRule {
+    frame: String,
+    neighbours: Vec<String>,
+}
+
+/// The pairs of frames which may be next to each other in the given rules, both ways
+fn allowed_pairs(path: &str) -> HashSet<(String, String)> {
+    let rules: Vec<TileRule> = ron::de::from_reader(File::open(path).unwrap()).unwrap();
+    rules
+        .iter()
+        .flat_map(|rule| rule.neighbours.iter().map(move |neighbour| (rule.frame.clone(), neighbour.clone())))
+        .flat_map(|(frame, neighbour)| [(frame.clone(), neighbour.clone()), (neighbour, frame)])
+        .collect()
+}
+
+/// The walkable tiles the player can walk to from the given tile, without crossing the towers
+fn reachable(map: &Map, start: (i32, i32)) -> HashSet<(i32, i32)> {
+    let towers: HashSet<(i32, i32)> = map.towers().map(|tower| tower.tile).collect();
+    let mut reached = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    while let Some((column, row)) = queue.pop_front() {
+        for next in [(column - 1, row), (column + 1, row), (column, row - 1), (column, row + 1)] {
+            if map.is_walkable(next) && !towers.contains(&next) && reached.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    reached
+}
+
+/// The frames of the tiles of every layer at the given index
+fn frames(map: &Map, tile: (i32, i32)) -> Vec<String> {
+    map.tiles(tile).map(|tile| tile.frame.to_string()).collect()
+}
+
+#[test]
+fn endless_maps_are_the_same_for_the_same_seed() {
+    let assets = assets();
+    let map = generate(&assets, &config(GeneratorKind::Endless, 42));
+    assert_eq!(map.seed, 42);
+    assert_eq!(map.chunks_len(), 25);
+    assert_eq!(map.to_file(), generate(&assets, &config(GeneratorKind::Endless, 42)).to_file());
+    assert_ne!(map.to_file().layers, generate(&assets, &config(GeneratorKind::Endless, 43)).to_file().layers);
+}
+
+#[test]
+fn endless_maps_generate_the_same_chunks_again_when_streaming_back() {
+    let assets = assets();
+    let mut map = generate(&assets, &config(GeneratorKind::Endless, 7));
+    let before: Vec<Vec<String>> = (0..CHUNK_SIZE as i32).map(|column| frames(&map, (column, 3))).collect();
+    let home = map.projection.tile_center(0, 0);
+
+    // Far enough for the chunks around the origin to be dropped
+    let far = CHUNK_SIZE as i32 * 10;
+    assert!(map.stream(map.projection.tile_center(far, far)));
+    assert!(!map.is_loaded((0, 3)));
+    assert!(map.is_loaded((far, far)));
+
+    assert!(map.stream(home));
+    let after: Vec<Vec<String>> = (0..CHUNK_SIZE as i32).map(|column| frames(&map, (column, 3))).collect();
+    assert_eq!(after, before);
+}
+
+#[test]
+fn every_generator_gives_a_playable_map() {
+    let assets = assets();
+    let landscape = assets.atlas(AtlasContent::Landscape);
+    for kind in [GeneratorKind::Island, GeneratorKind::Dungeon, GeneratorKind::Caves, GeneratorKind::Wfc] {
+        let config = config(kind, 3);
+        let file = kind.generator(&config, &assets).unwrap().unwrap().generate(3, config.size, &assets).unwrap();
+        assert_eq!(file.header.size, (48, 32), "{}", kind);
+        assert_eq!(file.layers[0].tiles.len(), 32, "{}", kind);
+        assert!(file.layers[0].tiles.iter().all(|row| row.len() == 48), "{}", kind);
+        assert!(file.frames.iter().all(|frame| landscape.has_frame(frame)), "{}", kind);
+
+        // The player starts on a walkable tile
+        let map = generate(&assets, &config);
+        let start = map.spawn_points(ObjectKind::PlayerSpawn).next().unwrap();
+        assert!(map.is_walkable_at(start), "{}", kind);
+        assert!(map.towers().all(|tower| !map.is_walkable_at(tower.position())), "{}", kind);
+
+        // The player can walk next to every tower, the rooms and caves being joined together
+        if matches!(kind, GeneratorKind::Dungeon | GeneratorKind::Caves) {
+            assert!(map.towers().count() > 0, "{}", kind);
+            let reached = reachable(&map, map.tile_index(start).unwrap());
+            for tower in map.towers() {
+                let (column, row) = tower.tile;
+                let mut around = (-1..=1).flat_map(|dy| (-1..=1).map(move |dx| (column + dx, row + dy)));
+                assert!(around.any(|tile| reached.contains(&tile)), "{}: tower at {:?}", kind, tower.tile);
+            }
+        }
+
+        // Wave function collapse only places tiles next to the ones its rules allow
+        if kind == GeneratorKind::Wfc {
+            let allowed = allowed_pairs(&config.wfc_rules);
+            let ground = &file.layers[0];
+            for (row, tiles) in ground.tiles.iter().enumerate() {
+                for column in 0..tiles.len() {
+                    let (column, row) = (column as i32, row as i32);
+                    let frame = file.frame(ground, (column, row)).unwrap();
+                    for side in [(column + 1, row), (column, row + 1)] {
+                        if let Some(next) = file.frame(ground, side) {
+                            let pair = (frame.to_string(), next.to_string());
+                            assert!(allowed.contains(&pair), "{:?} next to {:?} at {:?}", frame, next, (column, row));
+                        }
+                    }
+                }
+            }
+        }
+
+        // The same seed gives the same map
+        assert_eq!(map.to_file(), generate(&assets, &config).to_file(), "{}", kind);
+    }
+}
+
+#[test]
+fn maps_too_small_for_a_cave_are_left_as_rock() {
+    let assets = assets();
+    let config = MapGenConfig { size: (2, 2), ..config(GeneratorKind::Caves, 1) };
+    let file = GeneratorKind::Caves.generator(&config, &assets).unwrap().unwrap().generate(1, config.size, &assets).unwrap();
+    assert_eq!(file.layers[0].tiles, vec![vec![0; 2]; 2]);
+    assert!(file.objects.is_empty());
+}
+
+#[test]
+fn generator_configs_that_cannot_make_a_map_are_reported() {
+    let assets = assets();
+    let error = |config: &MapGenConfig| generate_map(&assets, config).unwrap_err().to_string();
+
+    let mut dungeon = config(GeneratorKind::Dungeon, 1);
+    dungeon.palette.wall[0].0 = "rokcs_1".to_string();
+    assert!(error(&dungeon).contains(r#"Unknown landscape frame "rokcs_1" in the wall frames of the palette"#));
+    let mut caves = config(GeneratorKind::Caves, 1);
+    caves.palette.floor.clear();
+    assert!(error(&caves).contains("The palette has no floor frames"));
+
+    // Wave function collapse rules with unknown frames, or which can't fill a map
+    let cases = [
+        ("unknown_frame", r#"[(frame: "no_such_frame", weight: 1, neighbours: [])]"#, r#"Unknown landscape frame "no_such_frame""#),
+        ("unknown_neighbour", r#"[(frame: "28", weight: 1, neighbours: ["29"])]"#, r#"Unknown frame "29" in the neighbours of "28""#),
+        (
+            "contradiction",
+            r#"[(frame: "28", weight: 1, neighbours: []), (frame: "29", weight: 1, neighbours: [])]"#,
+            "The wave function collapse failed",
+        ),
+    ];
+    for (name, rules, message) in cases {
+        let path = std::env::temp_dir().join(format!("tilemap-{}-wfc-{}.ron", std::process::id(), name));
+        std::fs::write(&path, rules).unwrap();
+        let wfc = MapGenConfig { wfc_rules: path.to_str().unwrap().to_string(), ..config(GeneratorKind::Wfc, 1) };
+        let error = error(&wfc);
+        std::fs::remove_file(&path).unwrap();
+        assert!(error.contains(message), "{:?} doesn't say {:?}", error, message);
+    }
+    let missing = MapGenConfig { wfc_rules: "no_such_rules.ron".to_string(), ..config(GeneratorKind::Wfc, 1) };
+    assert!(error(&missing).starts_with("no_such_rules.ron"));
+}
+
+#[test]
+fn generated_maps_round_trip_through_map_files() {
+    let assets = assets();
+    let config = config(GeneratorKind::Dungeon, 11);
+    let mut file: MapFile = GeneratorKind::Dungeon.generator(&config, &assets).unwrap().unwrap().generate(11, config.size, &assets).unwrap();
+    let map = Map::from_file(&file, assets.clone(), projection()).unwrap();
+
+    // Saved maps list their towers by tile
+    file.towers.sort_by_key(|tower| tower.tile);
+    assert_eq!(map.to_file(), file);
+}
+
+#[test]
+fn bodies_are_pushed_out_of_the_towers_they_overlap() {
+    let assets = assets();
+    let map = generate(&assets, &config(GeneratorKind::Endless, 42));
+    let body = Shape::Circle(scalar!(10));
+    assert!(map.towers().count() > 0);
+
+    // Pushed by the nearby towers only, the body lands where checking every tower would put it
+    let push_out_of_every_tower = |position: WorldPoint| {
+        map.towers().fold(position, |position, tower| match tower.shape().contact(tower.position(), &body, position) {
+            Some(contact) => position + contact.normal * contact.depth,
+            None => position,
+        })
+    };
+    for tower in map.towers() {
+        let position = tower.position() + WorldPoint::new(scalar!(5), scalar!(2));
+        let pushed = map.push_out_of_towers(position, &body);
+        assert!((pushed - tower.position()).length() > (position - tower.position()).length());
+        assert_eq!(pushed, push_out_of_every_tower(position));
+    }
+
+    // Away from the towers, nothing moves
+    let tile = (0..CHUNK_SIZE as i32)
+        .flat_map(|column| (0..CHUNK_SIZE as i32).map(move |row| (column, row)))
+        .map(|(column, row)| map.projection.tile_center(column, row))
+        .find(|&center| map.towers().all(|tower| (tower.position() - center).length() > scalar!(200)))
+        .unwrap();
+    assert_eq!(map.push_out_of_towers(tile, &body), tile);
+}
@@ -0,0 +1,97 @@
+use geometry::{scalar, WorldPoint};
+use tilemap::Map;
+
+use common::{layered_map, map, TILE_SIZE};
+
+mod common;
+
+#[test]
+fn sight_is_blocked_by_the_tiles_in_between() {
+    let map = map(&[
+        "........",
+        "........",
+        "....C...",
+        "....R...",
+    ]);
+    let (from, to) = (map.projection.tile_center(1, 2), map.projection.tile_center(7, 2));
+    assert_eq!(map.raycast(from, to), Some((4, 2)));
+    assert!(!map.line_of_sight(from, to));
+    assert!(!map.line_of_sight(to, from));
+
+    // Rocks are in the way of walking, not of the sight
+    let below = (map.projection.tile_center(1, 3), map.projection.tile_center(7, 3));
+    assert_eq!(map.raycast(below.0, below.1), None);
+    assert!(map.line_of_sight(below.0, below.1));
+}
+
+#[test]
+fn a_blocking_target_tile_is_still_in_sight() {
+    let map = map(&[
+        "........",
+        "....C...",
+        "........",
+    ]);
+    let (from, to) = (map.projection.tile_center(0, 1), map.projection.tile_center(4, 1));
+    assert_eq!(map.raycast(from, to), Some((4, 1)));
+    assert!(map.line_of_sight(from, to));
+
+    // The tile the ray starts from doesn't block it either
+    assert!(map.line_of_sight(to, from));
+}
+
+#[test]
+fn walking_depends_on_every_layer_of_a_tile() {
+    let map = layered_map(&[
+        &["..WD", "...."],
+        &[" T  ", "  R "],
+    ]);
+    let cases = [
+        // (tile, cost, None when it can't be walked on)
+        ((0, 0), Some(1.0)),
+        ((3, 0), Some(0.8)),
+        ((2, 0), None),
+        // The costliest layer wins, and any layer can block the way
+        ((1, 0), Some(2.0)),
+        ((2, 1), None),
+        // Out of the map
+        ((4, 0), None),
+        ((0, -1), None),
+    ];
+    for (tile, cost) in cases {
+        assert_eq!(map.cost(tile), cost, "{:?}", tile);
+        assert_eq!(map.is_walkable(tile), cost.is_some(), "{:?}", tile);
+    }
+
+    assert_eq!(map.cost_at(map.projection.tile_center(1, 0)), 2.0);
+    assert_eq!(map.cost_at(map.projection.tile_center(2, 0)), 1.0);
+}
+
+#[test]
+fn blocked_bodies_slide_along_the_tiles_in_their_way() {
+    // Moving from the middle of the tile (2, 2) to the tile (3, 2), the body may slide along
+    // the world x axis into the tile (3, 1), or along the y axis and stay in the tile (2, 2)
+    let half = WorldPoint::new(TILE_SIZE.width / scalar!(2), TILE_SIZE.height / scalar!(2));
+    let moves = |map: &Map| {
+        let from = map.projection.tile_center(2, 2);
+        let to = from + WorldPoint::new(half.x * scalar!(1.2), half.y * scalar!(0.4));
+        (from, to, map.walkable_position(from, to))
+    };
+
+    let (_, to, position) = moves(&map(&["......", "......", "......", "......"]));
+    assert_eq!(position, to);
+
+    let rocks = map(&["......", "......", "...R..", "......"]);
+    let (from, to, position) = moves(&rocks);
+    assert_eq!(rocks.projection.tile_index(to), (3, 2));
+    assert_eq!(position, WorldPoint::new(to.x, from.y));
+    assert_eq!(rocks.projection.tile_index(position), (3, 1));
+
+    let rocks = map(&["......", "...R..", "...R..", "......"]);
+    let (from, to, position) = moves(&rocks);
+    assert_eq!(position, WorldPoint::new(from.x, to.y));
+    assert_eq!(rocks.projection.tile_index(position), (2, 2));
+
+    // A body stuck in the rocks stays where it is
+    let (from, _, position) = moves(&map(&["......", "...R..", "..RR..", "......"]));
+    assert_eq!(position, from);
+}
@@ -0,0 +1,103 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use geometry::Neighbourhood;
+use tilemap::{Map, MapGenConfig, Pathfinder, CHUNK_SIZE, SEARCHES_PER_UPDATE};
+
+use common::{assets, map, projection};
+
+mod common;
+
+fn pathfinder(map: &Map) -> Pathfinder {
+    Pathfinder::new(map, &assets().tiles, Neighbourhood::Four)
+}
+
+/// Spends the searches left in the update on paths from `from` to new goals, on its right
+fn spend_budget(pathfinder: &mut Pathfinder, map: &Map, from: (i32, i32)) {
+    for column in 0..SEARCHES_PER_UPDATE as i32 {
+        pathfinder.request(map, from, (from.0 + column + 2, from.1));
+    }
+}
+
+#[test]
+fn searches_are_limited_to_the_budget_of_an_update() {
+    let map = map(&["............"; 12]);
+    let mut pathfinder = pathfinder(&map);
+    pathfinder.update(&map, None);
+
+    for column in 0..SEARCHES_PER_UPDATE as i32 {
+        assert!(pathfinder.request(&map, (0, 0), (column + 1, 3)).is_some());
+    }
+    // The next requests wait for the next update
+    assert!(pathfinder.request(&map, (0, 0), (11, 11)).is_none());
+    pathfinder.update(&map, None);
+    assert_eq!(pathfinder.request(&map, (0, 0), (11, 11)).map(|path| path.tiles.len()), Some(23));
+}
+
+#[test]
+fn cached_paths_are_found_without_spending_the_budget() {
+    let map = map(&[
+        "............",
+        "....RRR.....",
+        "....R.R.....",
+        "....RRR.....",
+    ]);
+    let mut pathfinder = pathfinder(&map);
+    pathfinder.update(&map, None);
+
+    let path = pathfinder.request(&map, (0, 0), (7, 3)).cloned().unwrap();
+    // The tile walled in by the rocks can't be reached, which is remembered too
+    assert!(pathfinder.request(&map, (0, 0), (5, 2)).is_none());
+
+    pathfinder.update(&map, None);
+    spend_budget(&mut pathfinder, &map, (0, 3));
+    assert!(pathfinder.request(&map, (0, 3), (0, 0)).is_none());
+    assert_eq!(pathfinder.request(&map, (0, 0), (7, 3)), Some(&path));
+    assert!(pathfinder.request(&map, (0, 0), (5, 2)).is_none());
+}
+
+#[test]
+fn paths_are_forgotten_when_the_window_moves() {
+    let config = MapGenConfig { seed: Some(42), ..MapGenConfig::default() };
+    let mut map = Map::new(&config, assets(), projection(), &mut StdRng::seed_from_u64(0)).unwrap();
+    // Two walkable tiles side by side in the chunk at the origin
+    let size = CHUNK_SIZE as i32;
+    let (from, to) = (0..size - 1)
+        .flat_map(|column| (0..size).map(move |row| ((column, row), (column + 1, row))))
+        .find(|&(from, to)| map.is_walkable(from) && map.is_walkable(to))
+        .unwrap();
+
+    let mut pathfinder = pathfinder(&map);
+    pathfinder.update(&map, None);
+    assert!(pathfinder.request(&map, from, to).is_some());
+
+    // Within the same window, the path is still known once the budget is spent
+    pathfinder.update(&map, None);
+    spend_budget(&mut pathfinder, &map, from);
+    assert!(pathfinder.request(&map, from, to).is_some());
+
+    // One chunk away, both tiles are still in the window, but indexed from another top tile
+    assert!(map.stream(map.projection.tile_center(size + 1, 1)));
+    pathfinder.update(&map, None);
+    spend_budget(&mut pathfinder, &map, from);
+    assert!(pathfinder.request(&map, from, to).is_none());
+    pathfinder.update(&map, None);
+    assert!(pathfinder.request(&map, from, to).is_some());
+}
+
+#[test]
+fn bodies_step_down_the_flow_field_or_along_a_path() {
+    let map = map(&["............"]);
+    let mut pathfinder = pathfinder(&map);
+
+    // Without a flow field, the step comes from a search
+    pathfinder.update(&map, None);
+    assert_eq!(pathfinder.next_tile(&map, (0, 0), (5, 0)), Some((1, 0)));
+
+    // With one leading to the goal, no search is needed
+    pathfinder.update(&map, Some((5, 0)));
+    spend_budget(&mut pathfinder, &map, (0, 0));
+    assert_eq!(pathfinder.next_tile(&map, (2, 0), (5, 0)), Some((3, 0)));
+    // Out of the map, there is nowhere to go
+    assert_eq!(pathfinder.next_tile(&map, (20, 0), (5, 0)), None);
+}
@@ -0,0 +1,76 @@
+use std::collections::HashSet;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use tilemap::{Map, MapGenConfig, CHUNK_SIZE};
+
+use common::{assets, map, projection};
+
+mod common;
+
+fn endless_map() -> Map {
+    let config = MapGenConfig { seed: Some(42), ..MapGenConfig::default() };
+    Map::new(&config, assets(), projection(), &mut StdRng::seed_from_u64(0)).unwrap()
+}
+
+/// The indices of the loaded chunks
+fn loaded(map: &Map) -> HashSet<(i32, i32)> {
+    map.chunks().iter().map(|chunk| chunk.index).collect()
+}
+
+/// The indices of the chunks within `radius` chunks of `(x, y)`
+fn around((x, y): (i32, i32), radius: i32) -> HashSet<(i32, i32)> {
+    (x - radius..=x + radius).flat_map(|cx| (y - radius..=y + radius).map(move |cy| (cx, cy))).collect()
+}
+
+/// Streams the map around the middle of the given chunk
+fn stream_to(map: &mut Map, (x, y): (i32, i32)) -> bool {
+    let size = CHUNK_SIZE as i32;
+    let center = map.projection.tile_center(x * size + size / 2, y * size + size / 2);
+    map.stream(center)
+}
+
+#[test]
+fn chunks_are_loaded_around_the_streaming_center() {
+    let mut map = endless_map();
+    assert_eq!(loaded(&map), around((0, 0), 2));
+    assert_eq!(map.window(), ((-32, -32), 80));
+
+    // Moving within the chunk at the center changes nothing
+    assert!(!stream_to(&mut map, (0, 0)));
+
+    // One chunk over, the next column of chunks is generated and the first one is kept
+    assert!(stream_to(&mut map, (1, 0)));
+    assert_eq!(loaded(&map), &around((0, 0), 2) | &around((1, 0), 2));
+    assert_eq!(map.chunks_len(), 30);
+    assert_eq!(map.window(), ((-16, -32), 80));
+    assert!(map.is_loaded((3 * 16 + 15, 0)));
+}
+
+#[test]
+fn far_chunks_are_dropped() {
+    let mut map = endless_map();
+    assert!(stream_to(&mut map, (1, 0)));
+    assert!(stream_to(&mut map, (2, 1)));
+
+    // The chunks further than 3 chunks away from the center are gone
+    let chunks = loaded(&map);
+    assert!(chunks.is_superset(&around((2, 1), 2)));
+    assert!(chunks.is_subset(&around((2, 1), 3)));
+    assert!(!chunks.contains(&(-2, 0)));
+    assert!(!map.is_loaded((-2 * 16, 0)));
+
+    // Far away, only the chunks around the new center are left
+    assert!(stream_to(&mut map, (40, -40)));
+    assert_eq!(loaded(&map), around((40, -40), 2));
+}
+
+#[test]
+fn maps_loaded_from_a_file_do_not_stream() {
+    let mut map = map(&["....", "...."]);
+    let chunks = loaded(&map);
+    assert!(!stream_to(&mut map, (5, 5)));
+    assert_eq!(loaded(&map), chunks);
+    assert_eq!(map.window(), ((0, 0), 4));
+}
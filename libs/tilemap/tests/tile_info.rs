@@ -0,0 +1,46 @@
+use tilemap::{TileInfo, TileTable};
+
+const TABLE: &str = r#"{
+    "00": (walkable: false, liquid: true),
+    "trees_*": (cost: 2.0),
+    "trees_big_*": (cost: 3.0),
+    "trees_big_1": (walkable: false),
+}"#;
+
+#[test]
+fn frames_are_looked_up_by_name_then_by_the_longest_pattern() {
+    let table: TileTable = ron::from_str(TABLE).unwrap();
+    let ground = TileInfo::default();
+    let cases = [
+        // (frame, walkable, cost, liquid)
+        ("00", false, 1.0, true),
+        ("trees_1", true, 2.0, false),
+        ("trees_big_2", true, 3.0, false),
+        // An exact name wins over the patterns, its missing fields being plain ground
+        ("trees_big_1", false, 1.0, false),
+        // Frames matching nothing are plain ground
+        ("trees", ground.walkable, ground.cost, ground.liquid),
+        ("28", ground.walkable, ground.cost, ground.liquid),
+    ];
+    for (frame, walkable, cost, liquid) in cases {
+        let info = table.get(frame);
+        assert_eq!((info.walkable, info.cost, info.liquid), (walkable, cost, liquid), "{}", frame);
+    }
+}
+
+#[test]
+fn the_lowest_cost_counts_plain_ground_and_walkable_tiles_only() {
+    let table: TileTable = ron::from_str(TABLE).unwrap();
+    assert_eq!(table.min_cost(), 1.0);
+
+    let table: TileTable = ron::from_str(r#"{"02": (cost: 0.8), "rocks_*": (walkable: false, cost: 0.1)}"#).unwrap();
+    assert_eq!(table.min_cost(), 0.8);
+}
+
+#[test]
+#[should_panic(expected = "every tile cost must be positive")]
+fn tiles_that_cost_nothing_are_rejected() {
+    let path = std::env::temp_dir().join(format!("tilemap-{}-free-tiles.ron", std::process::id()));
+    std::fs::write(&path, r#"{"trees_*": (cost: 2.0), "02": (cost: 0.0)}"#).unwrap();
+    TileTable::load(path.to_str().unwrap());
+}
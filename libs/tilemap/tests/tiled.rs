@@ -0,0 +1,152 @@
+use std::fs;
+
+use geometry::{scalar, TilePoint, TilePolygon};
+use tilemap::tiled;
+use tilemap::{Atlas, Faction, LayerFile, MapAssets, MapFile, ObjectKind, TowerFile};
+
+use common::resource;
+
+mod common;
+
+/// The atlases of the game, read from `resources/atlas.ron`
+fn atlases() -> Vec<Atlas> {
+    MapAssets::load(resource("")).atlases
+}
+
+/// Writes a map to a temporary file and imports it
+fn import_text(name: &str, text: &str) -> Result<MapFile, tiled::ImportError> {
+    let path = std::env::temp_dir().join(format!("tilemap-{}-{}", std::process::id(), name));
+    fs::write(&path, text).unwrap();
+    let map = tiled::import(path.to_str().unwrap(), &atlases());
+    fs::remove_file(&path).unwrap();
+    map
+}
+
+#[test]
+fn fixture_layers_towers_and_objects_are_imported() {
+    let map = tiled::import(&resource("maps/tiled/fixture.tmj"), &atlases()).unwrap();
+
+    assert_eq!(map.header.origin, (0, 0));
+    assert_eq!(map.header.size, (6, 4));
+    // Frames are numbered in the order they are met, the rock being named by its properties
+    assert_eq!(map.frames, ["00", "28", "rocks_1", "trees_3"]);
+    assert_eq!(
+        map.layers,
+        [
+            LayerFile {
+                level: 0,
+                tiles: vec![vec![1, 2, 2, 2, 2, 1], vec![1, 2, 2, 2, 2, 1], vec![1, 2, 2, 3, 2, 1], vec![1; 6]],
+            },
+            LayerFile { level: 1, tiles: vec![vec![0, 0, 4, 0, 0, 0], vec![0; 6], vec![0; 6], vec![0; 6]] },
+        ]
+    );
+    // Towers are matched by their place in the atlas image, flipped tiles too
+    assert_eq!(
+        map.towers,
+        [
+            TowerFile { tile: (1, 1), faction: Faction::Red, frame: "00".to_string() },
+            TowerFile { tile: (4, 1), faction: Faction::Red, frame: "01".to_string() },
+        ]
+    );
+
+    // The decoration is dropped, positions are in tiles
+    let kinds: Vec<ObjectKind> = map.objects.iter().map(|object| object.kind).collect();
+    assert_eq!(kinds, [ObjectKind::PlayerSpawn, ObjectKind::EnemySpawn, ObjectKind::Trigger]);
+    assert_eq!(map.objects[0].position, TilePoint::new(scalar!(1.5), scalar!(1.5)));
+    assert_eq!(map.objects[0].name, "start");
+    assert_eq!(map.objects[1].position, TilePoint::new(scalar!(4.5), scalar!(0.5)));
+    let gate = &map.objects[2];
+    assert_eq!(gate.name, "gate");
+    assert_eq!(gate.position, TilePoint::new(scalar!(1), scalar!(2.5)));
+    let area = TilePolygon::new(vec![
+        TilePoint::new(scalar!(0), scalar!(2)),
+        TilePoint::new(scalar!(2), scalar!(2)),
+        TilePoint::new(scalar!(2), scalar!(3)),
+        TilePoint::new(scalar!(0), scalar!(3)),
+    ]);
+    assert_eq!(gate.area, Some(area));
+}
+
+#[test]
+fn tmx_and_tmj_fixtures_give_the_same_map() {
+    let json = tiled::import(&resource("maps/tiled/fixture.tmj"), &atlases()).unwrap();
+    let xml = tiled::import(&resource("maps/tiled/fixture.tmx"), &atlases()).unwrap();
+    assert_eq!(xml, json);
+}
+
+#[test]
+fn imported_maps_round_trip_through_map_files() {
+    let map = tiled::import(&resource("maps/tiled/fixture.tmx"), &atlases()).unwrap();
+    let path = std::env::temp_dir().join(format!("tilemap-{}-round-trip.ron", std::process::id()));
+    let path = path.to_str().unwrap();
+    map.save(path).unwrap();
+    let loaded = MapFile::load(path).unwrap();
+    fs::remove_file(path).unwrap();
+    assert_eq!(loaded, map);
+}
+
+#[test]
+fn infinite_maps_keep_the_place_of_their_chunks() {
+    // Two 2x1 chunks, one of them left of and above the origin, in uncompressed base64
+    let map = import_text(
+        "infinite.tmj",
+        r#"{
+            "orientation": "isometric", "tileheight": 64, "infinite": true,
+            "tilesets": [{ "firstgid": 1, "name": "landscape", "tiles": [{ "id": 0, "image": "landscape/28.png" }] }],
+            "layers": [{
+                "type": "tilelayer", "encoding": "base64",
+                "chunks": [
+                    { "x": -2, "y": -1, "width": 2, "height": 1, "data": "AQAAAAAAAAA=" },
+                    { "x": 0, "y": 0, "width": 2, "height": 1, "data": "AQAAAAEAAAA=" }
+                ]
+            }]
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(map.header.origin, (-2, -1));
+    assert_eq!(map.header.size, (4, 2));
+    assert_eq!(map.layers[0].tiles, [[1, 0, 0, 0], [0, 0, 1, 1]]);
+    assert_eq!(map.frame(&map.layers[0], (-2, -1)), Some("28"));
+    assert_eq!(map.frame(&map.layers[0], (-1, -1)), None);
+}
+
+#[test]
+fn maps_the_game_cannot_use_are_errors() {
+    let map = |orientation: &str, image: &str| {
+        format!(
+            r#"{{
+                "orientation": "{}", "tileheight": 64,
+                "tilesets": [{{ "firstgid": 1, "name": "landscape", "tiles": [{{ "id": 0, "image": "{}" }}] }}],
+                "layers": [{{ "type": "tilelayer", "width": 1, "height": 1, "data": [1] }}]
+            }}"#,
+            orientation, image
+        )
+    };
+
+    let error = import_text("orthogonal.tmj", &map("orthogonal", "landscape/28.png")).unwrap_err();
+    assert!(error.to_string().contains("not isometric"), "{}", error);
+
+    let error = import_text("unknown-frame.tmj", &map("isometric", "landscape/99.png")).unwrap_err();
+    assert!(error.to_string().contains("Unknown frame \"99\""), "{}", error);
+
+    let error = import_text("unknown-atlas.tmj", &map("isometric", "elsewhere/28.png")).unwrap_err();
+    assert!(error.to_string().contains("matches no atlas frame"), "{}", error);
+
+    let error = import_text("missing-tileset.tmj", &map("isometric", "landscape/28.png").replace("\"firstgid\": 1", "\"firstgid\": 2")).unwrap_err();
+    assert!(error.to_string().contains("No tileset"), "{}", error);
+
+    // Chunks holding more or fewer tiles than their size
+    let layer = |width: usize, gids: &str| {
+        map("isometric", "landscape/28.png").replace(r#""width": 1, "height": 1, "data": [1]"#, &format!(r#""width": {}, "height": 1, "data": [{}]"#, width, gids))
+    };
+    let error = import_text("extra-tiles.tmj", &layer(1, "1, 1")).unwrap_err();
+    assert!(error.to_string().contains("has 2 tiles instead of 1x1"), "{}", error);
+    let error = import_text("no-width.tmj", &layer(0, "1")).unwrap_err();
+    assert!(error.to_string().contains("has 1 tiles instead of 0x1"), "{}", error);
+    let tmx = r#"<map orientation="isometric" tileheight="64" infinite="1">
+        <tileset firstgid="1" name="landscape"><tile id="0"><image source="landscape/28.png"/></tile></tileset>
+        <layer><data encoding="csv"><chunk x="0" y="0" width="2" height="1">1</chunk></data></layer>
+    </map>"#;
+    let error = import_text("short-chunk.tmx", tmx).unwrap_err();
+    assert!(error.to_string().contains("The chunk at (0, 0) has 1 tiles instead of 2x1"), "{}", error);
+}
@@ -0,0 +1,10 @@
+<?xml version="1.0" encoding="UTF-8"?>
+<tileset version="1.10" tiledversion="1.10.2" name="towers_red" tilewidth="93" tileheight="75" tilecount="2" columns="0">
+ <grid orientation="orthogonal" width="1" height="1"/>
+ <tile id="0" x="530" y="143" width="79" height="70">
+  <image source="../../atlas/towers_red.png" width="1024" height="512"/>
+ </tile>
+ <tile id="1" x="95" y="231" width="93" height="75">
+  <image source="../../atlas/towers_red.png" width="1024" height="512"/>
+ </tile>
+</tileset>
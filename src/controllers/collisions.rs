@@ -1,5 +1,5 @@
 use std::time::Duration;
-use geometry::{Position, Collide};
+use geometry::{scalar, Position, Collide, Scalar, SpatialGrid, WorldPoint};
 use crate::{
     controllers::{
         Event,
@@ -12,6 +12,8 @@ use crate::{
 
 const SCORE_PER_ENEMY: u32 = 10;
 const POWERUP_DURATION: u64 = 10;
+/// Enemies within this distance of the player are killed when the shield breaks
+const SHIELD_BLAST_RADIUS: Scalar = scalar!(150.0);
 
 pub struct CollisionsController;
 
@@ -77,21 +79,21 @@ impl CollisionsController {
         if !player.is_dead
             && state
                 .world
-                .enemies
-                .iter()
-                .any(|enemy| player.collides_with(enemy))
+                .enemy_grid
+                .query_radius(player.position(), player.shape().bounding_radius())
+                .into_iter()
+                .any(|index| player.collides_with(&state.world.enemies[index]))
         {
             // Remove shield powerup from player, also killing any enemies within close range
             if let Some(PowerupKind::Shield) = player.powerup {
                 player.powerup = None;
 
-                let enemies = &mut state.world.enemies;
-                // let particles = &mut state.world.particles;
-                // CollisionsController::remove_surrounding_enemies(
-                //     enemies,
-                //     particles,
-                //     player.position(),
-                // );
+                CollisionsController::remove_surrounding_enemies(
+                    &mut state.world.enemies,
+                    &state.world.enemy_grid,
+                    player.position(),
+                );
+                state.world.index_enemies();
                 events.push(Event::EnemyKilled);
             } else {
                 // Make an explosion where the player was killed
@@ -105,4 +107,12 @@ impl CollisionsController {
 
         return player_died;
     }
+
+    /// Removes the enemies within `SHIELD_BLAST_RADIUS` of the given position
+    fn remove_surrounding_enemies(enemies: &mut Vec<Enemy>, enemy_grid: &SpatialGrid<usize>, position: WorldPoint) {
+        // Indices are sorted, so removing from the back keeps the remaining ones valid
+        for index in enemy_grid.query_radius(position, SHIELD_BLAST_RADIUS).into_iter().rev() {
+            enemies.swap_remove(index);
+        }
+    }
 }
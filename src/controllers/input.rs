@@ -1,8 +1,12 @@
 use ggez::event::{KeyCode, KeyMods};
 
+use geometry::{Real, Scalar, ScreenPoint};
+
 #[derive(Default)]
 pub struct InputController {
     actions: Actions,
+    /// The last known position of the mouse cursor, in screen space
+    cursor: ScreenPoint,
 }
 
 /// Active actions (toggled by user input)
@@ -27,6 +31,16 @@ impl InputController {
         &self.actions
     }
 
+    /// Returns the last known position of the mouse cursor
+    pub fn cursor(&self) -> ScreenPoint {
+        self.cursor
+    }
+
+    /// Processes a mouse motion
+    pub fn mouse_motion(&mut self, x: f32, y: f32) {
+        self.cursor = ScreenPoint::new(Scalar::from_f32(x), Scalar::from_f32(y));
+    }
+
     /// Processes a key press
     pub fn key_press(&mut self, keycode: KeyCode, _keymod: KeyMods) {
         self.handle_key(keycode, true);
@@ -6,7 +6,7 @@ pub use self::input::{
     Actions,
     InputController,
 };
-pub use self::time::{PLAYER_GRACE_AREA, TimeController};
+pub use self::time::{PLAYER_GRACE_AREA, TimeController, tween};
 pub use self::collisions::CollisionsController;
 
 mod collisions;
@@ -1,11 +1,12 @@
 mod timeout;
 mod timeout_queue;
 mod timer;
+pub mod tween;
 
 use std::{mem, f32};
 use std::time::Duration;
 use rand::Rng;
-use geometry::{Point, Vector};
+use geometry::{scalar, Collide, Point, Position, Real, Scalar, Vector, WorldPoint};
 
 use crate::{
     controllers::{
@@ -19,6 +20,7 @@ use crate::models::{Enemy, Powerup};
 use self::timer::Timer;
 pub use self::timeout::Timeout;
 use self::timeout_queue::TimeoutQueue;
+use self::tween::Clock;
 
 // Constants related to time
 const ATTACKS_PER_SECOND: f32 = 30.0;
@@ -33,13 +35,16 @@ const POWERUP_SPAWN_RATE: f32 = 1.0 / POWERUP_SPAWNS_PER_SECOND;
 // Constants related to movement
 // Speed is measured in pixels per second
 // Rotation speed is measured in radians per second
-const ADVANCE_SPEED: f32 = 200.0;
-const BULLET_SPEED: f32 = 500.0;
-const ENEMY_SPEED: f32 = 100.0;
+const ADVANCE_SPEED: Scalar = scalar!(200.0);
+const BULLET_SPEED: Scalar = scalar!(500.0);
+const ENEMY_SPEED: Scalar = scalar!(100.0);
+const ENEMY_NEIGHBOUR_RADIUS: Scalar = scalar!(24.0);
 const ROTATE_SPEED: f32 = 2.0 * f32::consts::PI;
-const STAR_BASE_SPEED: f32 = 50.0;
+const STAR_BASE_SPEED: Scalar = scalar!(50.0);
+/// How much slower enemies are while time is slowed down
+const TIME_SLOW_SPEED_PENALTY: Scalar = scalar!(75.0);
 
-pub const PLAYER_GRACE_AREA: f32 = 200.0;
+pub const PLAYER_GRACE_AREA: Scalar = scalar!(200.0);
 
 pub struct TimeController {
     /// The duration of the current game, since the last restart
@@ -54,6 +59,8 @@ pub struct TimeController {
     powerup_timer: Timer,
     /// Scheduled events that should happen in the future
     scheduled_timeouts: TimeoutQueue,
+    /// The clock driving the animations, which can be paused or slowed down
+    animation_clock: Clock,
 }
 
 impl TimeController {
@@ -65,6 +72,7 @@ impl TimeController {
             powerup_timer: Timer::from_seconds(POWERUP_SPAWN_RATE),
             enemy_timer: Timer::from_seconds(ENEMY_SPAWN_RATE),
             scheduled_timeouts: TimeoutQueue::new(),
+            animation_clock: Clock::new(),
         }
     }
 
@@ -78,6 +86,15 @@ impl TimeController {
         self.scheduled_timeouts.push(self.current_time + offset, timeout);
     }
 
+    /// Freezes every animation, the rest of the game keeps running
+    pub fn pause_animations(&mut self) {
+        self.animation_clock.paused = true;
+    }
+
+    pub fn resume_animations(&mut self) {
+        self.animation_clock.paused = false;
+    }
+
     /// Updates the game
     ///
     /// `dt` is the amount of seconds that have passed since the last update
@@ -90,9 +107,10 @@ impl TimeController {
         rng: &mut R
     ) {
         self.current_time += dt;
+        let animation_dt = self.animation_clock.tick(dt);
 
-        let dt = dt.as_secs_f32();
-        state.difficulty += dt / 100.0;
+        let dt = Scalar::from_f32(dt.as_secs_f32());
+        state.difficulty += dt / scalar!(100.0);
 
         // Check if we have the "TimeSlow" powerup
         let time_slow = false; //state.world.player.powerup == Some(PowerupKind::TimeSlow);
@@ -109,15 +127,18 @@ impl TimeController {
         // Only modify player/powerups if player is alive
         if !state.world.player.is_dead {
             self.update_player(dt, actions, state);
-            self.update_powerups(dt, state, rng);
+            self.update_powerups(dt, animation_dt, state, rng);
         }
 
+        // Generate the map around the player as they explore it
+        state.world.stream_map();
+
         self.update_enemies(dt, state, events, time_slow, rng);
     }
 
-    fn update_powerups<R: Rng>(&mut self, dt: f32, state: &mut GameState, rng: &mut R) {
+    fn update_powerups<R: Rng>(&mut self, dt: Scalar, animation_dt: Duration, state: &mut GameState, rng: &mut R) {
         for powerup in &mut state.world.powerups {
-            powerup.update(dt);
+            powerup.update(dt, animation_dt);
         }
 
         // Remove any expired powerups
@@ -128,18 +149,26 @@ impl TimeController {
             state
                 .world
                 .powerups
-                .push(Powerup::random(rng, state.world.size));
+                .push(Powerup::random(rng, &state.world));
         });
     }
 
     // Updates the position and rotation of the player
-    fn update_player(&mut self, dt: f32, actions: &Actions, state: &mut GameState) {
+    fn update_player(&mut self, dt: Scalar, actions: &Actions, state: &mut GameState) {
         if !state.world.player.is_dead {
-            // Set speed and advance the player with wrap around
-            // state
-            //     .world
-            //     .player
-            //     .advance_wrapping(dt * ADVANCE_SPEED, state.world.size);
+            // Set speed from the pressed arrows and advance the player within the map bounds
+            let axis = |negative: bool, positive: bool| Scalar::from_f32(positive as i32 as f32 - negative as i32 as f32);
+            let direction = Point::new(axis(actions.left, actions.right), axis(actions.up, actions.down));
+
+            // The player is slowed down by rough tiles and can't step on the unwalkable ones
+            let map = &state.world.map;
+            let shape = state.world.player.shape();
+            let vector = &mut state.world.player.vector;
+            let from = vector.position;
+            vector.velocity = direction.normalize() * (ADVANCE_SPEED / Scalar::from_f32(map.cost_at(from)));
+            vector.advance(dt);
+            let position = map.walkable_position(from, vector.position);
+            vector.position = map.playable_area.confine(map.push_out_of_towers(position, &shape));
 
             // Cool down the player's gun
             // state.world.player.gun.cool_down(dt);
@@ -149,7 +178,7 @@ impl TimeController {
     // Updates positions of enemies, and spawns new ones when necessary
     fn update_enemies<R: Rng>(
         &mut self,
-        dt: f32,
+        dt: Scalar,
         state: &mut GameState,
         events: &mut Vec<Event>,
         time_slow: bool,
@@ -162,7 +191,10 @@ impl TimeController {
             // We loop here, just in case the new enemy random position is exactly equal
             // to the players current position, this would break our calculations below
             loop {
-                enemy_pos = Vector::random(rng, state.world.size);
+                enemy_pos = Vector::new(
+                    state.world.enemy_spawn_point(rng),
+                    Scalar::from_f32(rng.gen::<f32>()) * scalar!(2.0) * Scalar::PI,
+                );
                 if enemy_pos.position != player_pos.position {
                     break;
                 }
@@ -175,15 +207,17 @@ impl TimeController {
                 .intersect_circle(&player_pos.position, PLAYER_GRACE_AREA)
             {
                 // Treat the player as the centre of a circle with radius PLAYER_GRACE_AREA
-                let Point { x: cx, y: cy } = player_pos.position;
-                let dp: Point = enemy_pos.position - player_pos.position;
+                let Point { x: cx, y: cy, .. } = player_pos.position;
+                let dp = enemy_pos.position - player_pos.position;
                 // Calculate the angle between the player's position and the enemy's
                 let angle = (dp.y).atan2(dp.x);
                 // Use that to place the enemy on the edge of the circle surrounding the player
-                enemy_pos.position = Point {
-                    x: cx + PLAYER_GRACE_AREA * angle.cos(),
-                    y: cy + PLAYER_GRACE_AREA * angle.sin(),
-                };
+                enemy_pos.position = Point::new(
+                    cx + PLAYER_GRACE_AREA * angle.cos(),
+                    cy + PLAYER_GRACE_AREA * angle.sin(),
+                );
+                // The edge of the grace area may be off the map
+                enemy_pos.position = state.world.map.playable_area.confine(enemy_pos.position);
             }
 
             let new_enemy = Enemy::new(enemy_pos.position);
@@ -191,23 +225,195 @@ impl TimeController {
             events.push(Event::EnemySpawned);
         });
 
-        // Move enemies in the player's direction if player is alive, otherwise let them drift in
-        // the direction they're facing
-        for enemy in &mut state.world.enemies {
-            if !state.world.player.is_dead {
+        // Move enemies in the player's direction if player is alive and they can see it, follow the
+        // flow field or a path to the player if they can't, otherwise let them wander around or
+        // drift in the direction they're facing
+        let world = &mut state.world;
+        let player_tile = world.map.tile_index(world.player.position());
+        world.pathfinder.update(&world.map, player_tile);
+        for index in 0..world.enemies.len() {
+            if !world.player.is_dead {
                 let base_speed = if time_slow {
-                    ENEMY_SPEED - 75.0
+                    ENEMY_SPEED - TIME_SLOW_SPEED_PENALTY
                 } else {
                     ENEMY_SPEED
                 };
-                // enemy.update(
-                //     dt * base_speed + state.difficulty,
-                //     state.world.player.vector.position,
-                //     state.world.size,
-                // );
+                let neighbours: Vec<WorldPoint> = world
+                    .enemy_grid
+                    .neighbours(index, ENEMY_NEIGHBOUR_RADIUS)
+                    .into_iter()
+                    .map(|neighbour| world.enemies[neighbour].position())
+                    .collect();
+                let enemy = &mut world.enemies[index];
+                let from = enemy.position();
+                let cost = Scalar::from_f32(world.map.cost_at(from));
+                if world.map.line_of_sight(from, world.player.position()) {
+                    enemy.update(dt, (base_speed + state.difficulty) / cost, &world.player.vector, &neighbours);
+                } else {
+                    // The next tile down the flow field, or of a path when the field doesn't lead
+                    // anywhere yet
+                    let waypoint = world
+                        .map
+                        .tile_index(from)
+                        .zip(player_tile)
+                        .and_then(|(start, goal)| world.pathfinder.next_tile(&world.map, start, goal))
+                        .map(|(column, row)| world.map.projection.tile_center(column, row));
+                    match waypoint {
+                        Some(waypoint) => enemy.follow(dt, (base_speed + state.difficulty) / cost, waypoint, &neighbours),
+                        None => enemy.wander(dt, base_speed / cost, rng, &neighbours),
+                    }
+                }
+                enemy.vector.position = world.map.walkable_position(from, enemy.position());
             } else {
-                // enemy.advance(dt * ENEMY_SPEED);
+                world.enemies[index].advance(dt, ENEMY_SPEED);
             }
         }
+
+        // Enemies can't walk through towers
+        for enemy in &mut world.enemies {
+            enemy.vector.position = world.map.push_out_of_towers(enemy.position(), &enemy.shape());
+        }
+
+        // Keep the spatial index in sync with the new positions
+        state.world.index_enemies();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use geometry::{scalar, Position, Real, Scalar, ScreenSize, TilePoint, WorldPoint};
+
+    use crate::controllers::Actions;
+    use crate::game_state::GameState;
+    use tilemap::LayerFile;
+
+    use crate::models::{Enemy, MapAssets, MapFile, MapGenConfig, MapObject, MapSource, ObjectKind};
+    use crate::UPDATE_DURATION;
+
+    use super::TimeController;
+
+    /// Returns a started game on the endless map, the player being alive
+    fn started_game(rng: &mut StdRng) -> GameState {
+        started_game_on(rng, MapSource::Generated(MapGenConfig { seed: Some(7), ..MapGenConfig::default() }))
+    }
+
+    /// Returns a started game on a map from the given source, the player being alive
+    fn started_game_on(rng: &mut StdRng, map_source: MapSource) -> GameState {
+        let assets = Arc::new(MapAssets::load("resources"));
+        let mut state = GameState::new(rng, ScreenSize::new(scalar!(1400), scalar!(1000)), map_source, assets).unwrap();
+        state.start();
+        state
+    }
+
+    /// Returns a map file with a layer of grass (`.`) and crystals (`C`), given by row, and the
+    /// player starting at the top corner of the given tile
+    fn map_file(rows: &[&str], spawn: (i32, i32)) -> MapFile {
+        let mut file = MapFile::new(None, (0, 0), (rows[0].len(), rows.len()));
+        file.frames = vec!["28".to_string(), "crystals_1".to_string()];
+        let tiles = rows.iter().map(|row| row.chars().map(|tile| if tile == 'C' { 2 } else { 1 }).collect()).collect();
+        file.layers.push(LayerFile { level: 0, tiles });
+        let position = TilePoint::new(Scalar::from_f32(spawn.0 as f32), Scalar::from_f32(spawn.1 as f32));
+        file.objects.push(MapObject::new(ObjectKind::PlayerSpawn, position));
+        file
+    }
+
+    /// Plays the given number of fixed time steps with the given actions
+    fn play(state: &mut GameState, rng: &mut StdRng, actions: &Actions, steps: usize) {
+        let (mut time_controller, mut events) = (TimeController::new(), vec![]);
+        for _ in 0..steps {
+            time_controller.update_seconds(UPDATE_DURATION, actions, state, &mut events, rng);
+        }
+    }
+
+    #[test]
+    fn enemies_close_in_on_a_live_player() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut state = started_game(&mut rng);
+        let player = state.world.player.position();
+
+        // An enemy on walkable ground, in sight of the player
+        let map = &state.world.map;
+        let start = (0..16)
+            .map(|step| Scalar::from_f32(step as f32 * std::f32::consts::PI / 8.0))
+            .map(|angle| player + WorldPoint::new(angle.cos(), angle.sin()) * scalar!(120))
+            .find(|&position| map.is_walkable_at(position) && map.line_of_sight(position, player))
+            .unwrap();
+        state.world.enemies.push(Enemy::new(start));
+        state.world.index_enemies();
+
+        play(&mut state, &mut rng, &Actions::default(), 120);
+        let distance = |position: WorldPoint| (position - player).length();
+        assert_eq!(state.world.player.position(), player);
+        assert!(distance(state.world.enemies[0].position()) < distance(start) - scalar!(20));
+    }
+
+    #[test]
+    fn the_player_stays_on_the_map() {
+        // A small map of grass, the player starting in the middle
+        let file = map_file(&["......"; 6], (3, 3));
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut state = started_game_on(&mut rng, MapSource::File(file));
+        let start = state.world.player.position();
+
+        // Walking from the middle towards each side of the screen, it stops at the corners of the
+        // map without ever leaving it
+        let bounds = state.world.map.world_bounds();
+        let (min, max) = (bounds.min(), bounds.max());
+        let sides = [
+            (Actions { right: true, ..Actions::default() }, WorldPoint::new(max.x, start.y)),
+            (Actions { down: true, ..Actions::default() }, WorldPoint::new(start.x, max.y)),
+            (Actions { left: true, ..Actions::default() }, WorldPoint::new(min.x, start.y)),
+            (Actions { up: true, ..Actions::default() }, WorldPoint::new(start.x, min.y)),
+        ];
+        for (actions, corner) in &sides {
+            state.world.player.vector.position = start;
+            for _ in 0..5 {
+                play(&mut state, &mut rng, actions, 60);
+                let position = state.world.player.position();
+                assert!(state.world.map.playable_area.contains(position), "{:?}", position);
+            }
+            assert!((state.world.player.position() - *corner).length() < scalar!(4), "{:?}", corner);
+        }
+    }
+
+    #[test]
+    fn enemies_out_of_sight_walk_around_the_obstacles() {
+        // The player is hidden behind a wall of crystals, with a way around at the bottom
+        let rows = [
+            ".....C....",
+            ".....C....",
+            ".....C....",
+            ".....C....",
+            ".....C....",
+            ".....C....",
+            ".....C....",
+            "..........",
+            "..........",
+            "..........",
+        ];
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut state = started_game_on(&mut rng, MapSource::File(map_file(&rows, (8, 2))));
+        let player = state.world.player.position();
+        let map = &state.world.map;
+        let start = map.projection.tile_center(2, 2);
+        assert!(!map.line_of_sight(start, player));
+        state.world.enemies.push(Enemy::new(start));
+        state.world.index_enemies();
+
+        // It goes down along the wall, around it, then up to the player on the other side
+        let (mut steps, mut lowest_row) = (0, 0);
+        while (state.world.enemies[0].position() - player).length() > scalar!(20) {
+            play(&mut state, &mut rng, &Actions::default(), 30);
+            let enemy = state.world.enemies[0].position();
+            assert!(state.world.map.is_walkable_at(enemy), "{:?}", enemy);
+            lowest_row = lowest_row.max(state.world.map.tile_index(enemy).unwrap().1);
+            steps += 1;
+            assert!(steps < 60, "the enemy never reached the player");
+        }
+        assert!(lowest_row >= 7);
     }
 }
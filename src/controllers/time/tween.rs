@@ -0,0 +1,493 @@
+//! Tweens move a value smoothly from one state to another over time
+//!
+//! - `Easing` curves shape how a tween goes from its start to its end
+//! - `Tween` animates any `Tweenable` value: numbers, points and colors
+//! - `Sequence` plays animations one after the other, `Parallel` plays two at the same time
+//!
+//! Animations are advanced with the time of a `Clock`, which can be paused or scaled
+use std::f32::consts::PI;
+use std::time::Duration;
+
+use ggez::graphics::Color;
+
+use geometry::{Point, Real, Scalar};
+
+/// The shape of the progression of a tween
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Easing {
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    SineIn,
+    SineOut,
+    SineInOut,
+    /// Overshoots the end a little before settling
+    BackOut,
+    /// Springs around the end before settling
+    ElasticOut,
+    /// Bounces on the end like a dropped ball
+    BounceOut,
+}
+
+impl Easing {
+    /// Maps the linear progress `t`, from 0 to 1, to the eased progress
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1.0 - (1.0 - t).powi(2),
+            Easing::QuadInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (2.0 - 2.0 * t).powi(2) / 2.0
+                }
+            }
+            Easing::CubicIn => t.powi(3),
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut => {
+                if t < 0.5 {
+                    4.0 * t.powi(3)
+                } else {
+                    1.0 - (2.0 - 2.0 * t).powi(3) / 2.0
+                }
+            }
+            Easing::SineIn => 1.0 - (t * PI / 2.0).cos(),
+            Easing::SineOut => (t * PI / 2.0).sin(),
+            Easing::SineInOut => (1.0 - (t * PI).cos()) / 2.0,
+            Easing::BackOut => {
+                const OVERSHOOT: f32 = 1.70158;
+                1.0 + (OVERSHOOT + 1.0) * (t - 1.0).powi(3) + OVERSHOOT * (t - 1.0).powi(2)
+            }
+            Easing::ElasticOut => {
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else {
+                    2f32.powf(-10.0 * t) * ((10.0 * t - 0.75) * 2.0 * PI / 3.0).sin() + 1.0
+                }
+            }
+            Easing::BounceOut => {
+                const N: f32 = 7.5625;
+                const D: f32 = 2.75;
+                if t < 1.0 / D {
+                    N * t * t
+                } else if t < 2.0 / D {
+                    let t = t - 1.5 / D;
+                    N * t * t + 0.75
+                } else if t < 2.5 / D {
+                    let t = t - 2.25 / D;
+                    N * t * t + 0.9375
+                } else {
+                    let t = t - 2.625 / D;
+                    N * t * t + 0.984375
+                }
+            }
+        }
+    }
+}
+
+/// A value that can be interpolated by a tween
+pub trait Tweenable: Copy {
+    /// Returns the value at `t` between `self` (0) and `to` (1), `t` may go a bit out of this
+    /// range with overshooting easings
+    fn lerp(self, to: Self, t: f32) -> Self;
+}
+
+impl Tweenable for f32 {
+    fn lerp(self, to: f32, t: f32) -> f32 {
+        self + (to - self) * t
+    }
+}
+
+impl<S> Tweenable for Point<S> {
+    fn lerp(self, to: Point<S>, t: f32) -> Point<S> {
+        self + (to - self) * Scalar::from_f32(t)
+    }
+}
+
+impl Tweenable for Color {
+    fn lerp(self, to: Color, t: f32) -> Color {
+        Color::new(
+            self.r.lerp(to.r, t),
+            self.g.lerp(to.g, t),
+            self.b.lerp(to.b, t),
+            self.a.lerp(to.a, t),
+        )
+    }
+}
+
+/// Something that changes over time
+pub trait Animation {
+    /// The type of the animated value
+    type Value;
+
+    /// Moves the animation forward by `dt`, and returns the part of `dt` left once it finished
+    fn advance(&mut self, dt: Duration) -> Duration;
+
+    /// Returns the current value
+    fn value(&self) -> Self::Value;
+
+    /// Returns true if the animation reached its end (looping animations never do)
+    fn is_finished(&self) -> bool;
+
+    /// Goes back to the start of the animation
+    fn reset(&mut self);
+}
+
+/// What a tween does when it reaches its end
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Repeat {
+    /// Stops at the end
+    Once,
+    /// Starts over from the beginning
+    Loop,
+    /// Goes back and forth between the start and the end
+    PingPong,
+}
+
+/// A `Tween` moves a value from `from` to `to` in the given duration
+#[derive(Clone, Debug)]
+pub struct Tween<T> {
+    from: T,
+    to: T,
+    duration: Duration,
+    elapsed: Duration,
+    easing: Easing,
+    repeat: Repeat,
+    paused: bool,
+}
+
+impl<T: Tweenable> Tween<T> {
+    /// Returns a new `Tween`, played once
+    pub fn new(from: T, to: T, duration: Duration, easing: Easing) -> Tween<T> {
+        Tween {
+            from,
+            to,
+            duration,
+            elapsed: Duration::ZERO,
+            easing,
+            repeat: Repeat::Once,
+            paused: false,
+        }
+    }
+
+    /// Returns a `Tween` keeping the same value for the given duration, to wait in a `Sequence`
+    pub fn hold(value: T, duration: Duration) -> Tween<T> {
+        Tween::new(value, value, duration, Easing::Linear)
+    }
+
+    /// Consumes the tween and returns it with the given repeat mode
+    pub fn repeat(mut self, repeat: Repeat) -> Tween<T> {
+        self.repeat = repeat;
+        self
+    }
+
+    /// Stops the tween where it is, until it is resumed
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resumes a paused tween
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Returns the linear progress of the current run, from 0 to 1
+    pub fn progress(&self) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        let t = self.elapsed.as_secs_f32() / self.duration.as_secs_f32();
+        match self.repeat {
+            // The second half of the period plays backward
+            Repeat::PingPong if t > 1.0 => 2.0 - t,
+            _ => t.min(1.0),
+        }
+    }
+}
+
+impl<T: Tweenable> Animation for Tween<T> {
+    type Value = T;
+
+    fn advance(&mut self, dt: Duration) -> Duration {
+        if self.paused {
+            return Duration::ZERO;
+        }
+        self.elapsed += dt;
+
+        let period = match self.repeat {
+            Repeat::Once => {
+                let left = self.elapsed.saturating_sub(self.duration);
+                self.elapsed = self.elapsed.min(self.duration);
+                return left;
+            }
+            Repeat::Loop => self.duration,
+            Repeat::PingPong => self.duration * 2,
+        };
+        if !period.is_zero() {
+            self.elapsed = Duration::from_nanos((self.elapsed.as_nanos() % period.as_nanos()) as u64);
+        }
+        Duration::ZERO
+    }
+
+    fn value(&self) -> T {
+        self.from.lerp(self.to, self.easing.apply(self.progress()))
+    }
+
+    fn is_finished(&self) -> bool {
+        self.repeat == Repeat::Once && self.elapsed >= self.duration
+    }
+
+    fn reset(&mut self) {
+        self.elapsed = Duration::ZERO;
+    }
+}
+
+/// A `Sequence` plays animations one after the other, its value is the one of the current step
+pub struct Sequence<A> {
+    steps: Vec<A>,
+    current: usize,
+}
+
+impl<A: Animation> Sequence<A> {
+    /// Returns a new `Sequence` of the given steps, there must be at least one
+    pub fn new(steps: Vec<A>) -> Sequence<A> {
+        assert!(!steps.is_empty(), "a sequence needs at least one step");
+        Sequence { steps, current: 0 }
+    }
+
+    /// Returns the index of the step being played
+    pub fn current_step(&self) -> usize {
+        self.current.min(self.steps.len() - 1)
+    }
+}
+
+impl<A: Animation> Animation for Sequence<A> {
+    type Value = A::Value;
+
+    fn advance(&mut self, mut dt: Duration) -> Duration {
+        // The time left by a finished step goes to the next one
+        while let Some(step) = self.steps.get_mut(self.current) {
+            dt = step.advance(dt);
+            if !step.is_finished() {
+                return Duration::ZERO;
+            }
+            self.current += 1;
+        }
+        dt
+    }
+
+    fn value(&self) -> A::Value {
+        self.steps[self.current_step()].value()
+    }
+
+    fn is_finished(&self) -> bool {
+        self.current >= self.steps.len()
+    }
+
+    fn reset(&mut self) {
+        self.steps.iter_mut().for_each(Animation::reset);
+        self.current = 0;
+    }
+}
+
+/// A `Parallel` group plays two animations at the same time, its value is the pair of their
+/// values
+///
+/// Groups can be nested to play more animations together
+pub struct Parallel<A, B>(pub A, pub B);
+
+impl<A: Animation, B: Animation> Animation for Parallel<A, B> {
+    type Value = (A::Value, B::Value);
+
+    fn advance(&mut self, dt: Duration) -> Duration {
+        let left = self.0.advance(dt).min(self.1.advance(dt));
+        if self.is_finished() {
+            left
+        } else {
+            Duration::ZERO
+        }
+    }
+
+    fn value(&self) -> (A::Value, B::Value) {
+        (self.0.value(), self.1.value())
+    }
+
+    fn is_finished(&self) -> bool {
+        self.0.is_finished() && self.1.is_finished()
+    }
+
+    fn reset(&mut self) {
+        self.0.reset();
+        self.1.reset();
+    }
+}
+
+/// A `Clock` turns the game time into animation time, so animations can be paused or slowed
+/// down without touching the rest of the simulation
+#[derive(Clone, Copy, Debug)]
+pub struct Clock {
+    /// How fast animations play, 1 being the normal speed
+    pub time_scale: f32,
+    pub paused: bool,
+}
+
+impl Clock {
+    pub fn new() -> Clock {
+        Clock {
+            time_scale: 1.0,
+            paused: false,
+        }
+    }
+
+    /// Returns the animation time elapsed during `dt` of game time
+    pub fn tick(&self, dt: Duration) -> Duration {
+        if self.paused {
+            Duration::ZERO
+        } else {
+            dt.mul_f32(self.time_scale.max(0.0))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EASINGS: [Easing; 13] = [
+        Easing::Linear,
+        Easing::QuadIn,
+        Easing::QuadOut,
+        Easing::QuadInOut,
+        Easing::CubicIn,
+        Easing::CubicOut,
+        Easing::CubicInOut,
+        Easing::SineIn,
+        Easing::SineOut,
+        Easing::SineInOut,
+        Easing::BackOut,
+        Easing::ElasticOut,
+        Easing::BounceOut,
+    ];
+
+    fn seconds(seconds: f32) -> Duration {
+        Duration::from_secs_f32(seconds)
+    }
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-4, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn easings_start_at_0_and_end_at_1() {
+        for easing in EASINGS {
+            assert_close(easing.apply(0.0), 0.0);
+            assert_close(easing.apply(1.0), 1.0);
+            // The progress is clamped
+            assert_close(easing.apply(-1.0), 0.0);
+            assert_close(easing.apply(2.0), 1.0);
+        }
+    }
+
+    #[test]
+    fn a_tween_played_once_stops_at_its_end() {
+        let mut tween = Tween::new(0.0, 10.0, seconds(1.0), Easing::Linear);
+        assert_eq!(tween.advance(seconds(0.25)), Duration::ZERO);
+        assert_close(tween.value(), 2.5);
+        assert!(!tween.is_finished());
+
+        // The time past the end is given back
+        assert_close(tween.advance(seconds(1.25)).as_secs_f32(), 0.5);
+        assert_close(tween.value(), 10.0);
+        assert!(tween.is_finished());
+    }
+
+    #[test]
+    fn a_looping_tween_wraps_around_to_its_start() {
+        let mut tween = Tween::new(0.0, 10.0, seconds(1.0), Easing::Linear).repeat(Repeat::Loop);
+        assert_eq!(tween.advance(seconds(1.25)), Duration::ZERO);
+        assert_close(tween.value(), 2.5);
+        tween.advance(seconds(2.5));
+        assert_close(tween.value(), 7.5);
+        assert!(!tween.is_finished());
+    }
+
+    #[test]
+    fn a_ping_pong_tween_plays_back_to_its_start() {
+        let mut tween = Tween::new(0.0, 10.0, seconds(1.0), Easing::Linear).repeat(Repeat::PingPong);
+        tween.advance(seconds(0.75));
+        assert_close(tween.value(), 7.5);
+        // Half way back
+        tween.advance(seconds(0.75));
+        assert_close(tween.value(), 5.0);
+        // Around the start, and forward again
+        tween.advance(seconds(0.75));
+        assert_close(tween.value(), 2.5);
+        assert!(!tween.is_finished());
+    }
+
+    #[test]
+    fn a_paused_tween_keeps_its_value() {
+        let mut tween = Tween::new(0.0, 10.0, seconds(1.0), Easing::Linear);
+        tween.advance(seconds(0.5));
+        tween.pause();
+        tween.advance(seconds(0.25));
+        assert_close(tween.value(), 5.0);
+        tween.resume();
+        tween.advance(seconds(0.25));
+        assert_close(tween.value(), 7.5);
+    }
+
+    #[test]
+    fn a_sequence_hands_the_time_left_to_the_next_step() {
+        let mut sequence = Sequence::new(vec![
+            Tween::new(0.0, 1.0, seconds(1.0), Easing::Linear),
+            Tween::new(10.0, 20.0, seconds(1.0), Easing::Linear),
+        ]);
+        assert_eq!(sequence.advance(seconds(1.5)), Duration::ZERO);
+        assert_eq!(sequence.current_step(), 1);
+        assert_close(sequence.value(), 15.0);
+
+        assert_close(sequence.advance(seconds(1.0)).as_secs_f32(), 0.5);
+        assert!(sequence.is_finished());
+        assert_close(sequence.value(), 20.0);
+
+        sequence.reset();
+        assert_eq!(sequence.current_step(), 0);
+        assert_close(sequence.value(), 0.0);
+    }
+
+    #[test]
+    fn a_parallel_group_finishes_with_its_longest_animation() {
+        let mut group = Parallel(
+            Tween::new(0.0, 1.0, seconds(1.0), Easing::Linear),
+            Tween::new(0.0, 4.0, seconds(2.0), Easing::Linear),
+        );
+        assert_eq!(group.advance(seconds(1.5)), Duration::ZERO);
+        assert!(group.0.is_finished() && !group.is_finished());
+        let (a, b) = group.value();
+        assert_close(a, 1.0);
+        assert_close(b, 3.0);
+
+        assert_close(group.advance(seconds(1.0)).as_secs_f32(), 0.5);
+        assert!(group.is_finished());
+    }
+
+    #[test]
+    fn the_clock_pauses_and_scales_the_animation_time() {
+        let mut clock = Clock::new();
+        clock.time_scale = 2.0;
+        assert_close(clock.tick(seconds(0.5)).as_secs_f32(), 1.0);
+        clock.paused = true;
+        assert_eq!(clock.tick(seconds(0.5)), Duration::ZERO);
+    }
+}
@@ -1,12 +1,15 @@
+use std::hash::Hasher;
+use std::sync::Arc;
+
 use rand::Rng;
-use rand::rngs::ThreadRng;
 
-use geometry::Size;
+use geometry::{Position, Real, Scalar, ScreenSize, Vector};
 
-use crate::models::World;
+use crate::models::{MapAssets, MapFileError, MapSource, World};
 
 /// This is a message that will be drawn to the screen. When it's shown on the screen the game
 /// will be waiting for user input
+#[derive(PartialEq)]
 pub struct Message {
     pub title: &'static str,
     pub subtitle: &'static str,
@@ -29,22 +32,31 @@ pub struct GameState {
     /// The world contains everything that needs to be drawn
     pub world: World,
     /// The current difficulty - the enemies will speed up over time
-    pub difficulty: f32,
+    pub difficulty: Scalar,
     /// Information about the Message to draw on the screen
     pub message: Option<Message>,
     /// Score
     pub score: u32,
+    /// Where the map of each new world comes from
+    pub map_source: MapSource,
+    /// What the maps are made of
+    pub map_assets: Arc<MapAssets>,
 }
 
 impl GameState {
-    /// Returns a new `GameState` containing a `World` of the given `Size`
-    pub fn new<R: Rng>(ref mut rng: &mut R, size: Size) -> GameState {
-        GameState {
-            world: World::new(rng, size),
-            difficulty: 0.0,
+    /// Returns a new `GameState` containing a `World` of the given `Size`, with a map from the
+    /// given source made of the given assets, or the reason why the map can't be played
+    pub fn new<R: Rng>(ref mut rng: &mut R, size: ScreenSize, map_source: MapSource, map_assets: Arc<MapAssets>) -> Result<GameState, MapFileError> {
+        // A Tiled map is imported once, resetting the game doesn't read the file again
+        let map_source = map_source.import(&map_assets)?;
+        Ok(GameState {
+            world: World::new(rng, size, &map_source, &map_assets)?,
+            difficulty: Scalar::ZERO,
             message: Some(WELCOME_MESSAGE),
             score: 0,
-        }
+            map_source,
+            map_assets,
+        })
     }
 
     /// Called when the game is over - displays a message onscreen
@@ -52,16 +64,28 @@ impl GameState {
         self.message = Some(GAMEOVER_MESSAGE);
     }
 
-    /// Reset our game-state
-    pub fn reset(&mut self, rng: &mut impl Rng) {
-        // Create a new world
-        self.world = World::new(rng, self.world.size);
+    /// Returns true if the game over message is shown, the game then waiting to be restarted
+    pub fn is_over(&self) -> bool {
+        self.message == Some(GAMEOVER_MESSAGE)
+    }
+
+    /// Starts the game once the user pressed a key, bringing the player to life
+    pub fn start(&mut self) {
+        self.message = None;
+        self.world.player.is_dead = false;
+    }
+
+    /// Reset our game-state, or return why the map of the new world can't be built, the game
+    /// state being left as it was
+    pub fn reset(&mut self, rng: &mut impl Rng) -> Result<(), MapFileError> {
+        // Create a new world, from the map source that already made the first one
+        self.world = World::new(rng, self.world.size, &self.map_source, &self.map_assets)?;
 
         // Reset score
         self.score = 0;
 
         // Reset difficulty
-        self.difficulty = 0.0;
+        self.difficulty = Scalar::ZERO;
 
         // Reset message
         self.message = None;
@@ -69,5 +93,132 @@ impl GameState {
         // Remove all enemies and powerups
         self.world.enemies.clear();
         self.world.powerups.clear();
+        self.world.index_enemies();
+        Ok(())
+    }
+
+    /// Returns a hash of the simulated state, to check that two runs didn't diverge
+    ///
+    /// With the `fixed` feature, a run with the same seed and inputs always gives the same hash
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = StateHasher::default();
+        let write_vector = |hasher: &mut StateHasher, vector: &Vector| {
+            for value in [vector.position.x, vector.position.y, vector.velocity.x, vector.velocity.y, vector.direction] {
+                hasher.write_u64(value.bits());
+            }
+        };
+
+        write_vector(&mut hasher, &self.world.player.vector);
+        hasher.write_u64(self.world.player.is_dead as u64);
+        hasher.write_u64(self.world.enemies.len() as u64);
+        for enemy in &self.world.enemies {
+            write_vector(&mut hasher, &enemy.vector);
+        }
+        hasher.write_u64(self.world.powerups.len() as u64);
+        for powerup in &self.world.powerups {
+            hasher.write_u64(powerup.x().bits());
+            hasher.write_u64(powerup.y().bits());
+            hasher.write_u64(powerup.ttl.bits());
+        }
+        hasher.write_u64(self.score as u64);
+        hasher.write_u64(self.difficulty.bits());
+        hasher.finish()
+    }
+}
+
+/// A FNV-1a hasher, unlike the `std` one its output never changes between platforms and releases
+struct StateHasher(u64);
+
+impl Default for StateHasher {
+    fn default() -> Self {
+        StateHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for StateHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        // The default implementation uses the native byte order
+        self.write(&value.to_le_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use geometry::{scalar, Position, ScreenSize, WorldPoint};
+
+    use crate::controllers::{Actions, CollisionsController, TimeController};
+    use crate::models::{MapAssets, MapGenConfig, MapSource};
+    use crate::UPDATE_DURATION;
+
+    use super::GameState;
+
+    /// Plays the given number of fixed time steps from the given seed, the player walking then
+    /// standing still for the enemies to catch up, and returns the state reached with where the
+    /// player started
+    fn play(seed: u64, steps: usize) -> (GameState, WorldPoint) {
+        let assets = Arc::new(MapAssets::load("resources"));
+        let map_source = MapSource::Generated(MapGenConfig { seed: Some(7), ..MapGenConfig::default() });
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut state = GameState::new(&mut rng, ScreenSize::new(scalar!(1400), scalar!(1000)), map_source, assets).unwrap();
+        let (mut time_controller, mut events) = (TimeController::new(), vec![]);
+        let walking = Actions { right: true, down: true, attack: true, ..Actions::default() };
+        let standing = Actions { attack: true, ..Actions::default() };
+
+        // As if a key was pressed on the welcome message
+        state.start();
+        let start = state.world.player.position();
+        for step in 0..steps {
+            let actions = if step < steps / 2 { &walking } else { &standing };
+            time_controller.update_seconds(UPDATE_DURATION, actions, &mut state, &mut events, &mut rng);
+            CollisionsController::handle_collisions(&mut state, &mut time_controller, &mut events);
+        }
+        (state, start)
+    }
+
+    #[test]
+    fn a_seeded_run_always_reaches_the_same_state() {
+        let (state, start) = play(42, 600);
+        let hash = state.state_hash();
+        assert_eq!(play(42, 600).0.state_hash(), hash);
+        assert_ne!(play(43, 600).0.state_hash(), hash);
+
+        // The player walked away, then the enemies, spawned out of its grace area, chased it until
+        // one of them caught it
+        assert!((state.world.player.position() - start).length() > scalar!(100));
+        assert!(state.world.player.is_dead);
+    }
+
+    #[test]
+    fn a_tiled_map_is_imported_once() {
+        // A copy of the Tiled fixture, deleted once the game started
+        let dir = std::env::temp_dir().join(format!("rust-rpg-{}-tiled", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["fixture.tmj", "towers.tsj"] {
+            std::fs::copy(format!("resources/maps/tiled/{}", name), dir.join(name)).unwrap();
+        }
+        let map_source = MapSource::Tiled(dir.join("fixture.tmj").to_str().unwrap().to_string());
+        let mut rng = StdRng::seed_from_u64(1);
+        let assets = Arc::new(MapAssets::load("resources"));
+        let mut state = GameState::new(&mut rng, ScreenSize::new(scalar!(1400), scalar!(1000)), map_source, assets).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(state.map_source, MapSource::File(_)));
+        let file = state.world.map.to_file();
+        state.reset(&mut rng).unwrap();
+        assert_eq!(state.world.map.to_file(), file);
     }
 }
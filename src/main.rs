@@ -9,12 +9,14 @@
 // YAGNI - You Ain't Gonna Need It
 //----------------------------------------------
 
+use std::time::Duration;
+
 use ggez::{Context, GameError, GameResult};
 use ggez::event::{self, KeyCode, KeyMods};
-use rand::prelude::ThreadRng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use structopt::StructOpt;
 
-use geometry::Size;
+use geometry::{Real, Scalar, ScreenSize};
 
 use crate::{
     controllers::{
@@ -23,8 +25,10 @@ use crate::{
         CollisionsController
     },
     game_state::GameState,
+    models::{GeneratorKind, MapFile, MapGenConfig, MapSource},
     view::{
         init_rendering_ctx,
+        MapRenderCache,
         Resources,
     },
 };
@@ -35,6 +39,13 @@ mod controllers;
 mod game_state;
 mod models;
 
+/// The number of simulation steps per second
+const UPDATES_PER_SECOND: u32 = 60;
+/// The simulated time of a step
+const UPDATE_DURATION: Duration = Duration::from_nanos(1_000_000_000 / UPDATES_PER_SECOND as u64);
+/// The file the map is saved to when pressing F5
+const SAVED_MAP_PATH: &str = "saved_map.ron";
+
 #[derive(StructOpt, Debug)]
 #[structopt(name = "Rust RPG", about = "A simple RPG game written in Rust")]
 struct Opt {
@@ -44,6 +55,20 @@ struct Opt {
     /// The height of the game window
     #[structopt(short, long, default_value = "1000")]
     height: f32,
+    /// The map generation config file
+    #[structopt(long, default_value = "resources/mapgen.ron")]
+    map_config: String,
+    /// The seed of the map, overriding the one of the config
+    #[structopt(long)]
+    seed: Option<u32>,
+    /// The map generator (endless, island, dungeon, caves or wfc), overriding the one of the
+    /// config
+    #[structopt(long)]
+    generator: Option<GeneratorKind>,
+    /// A map file to play on instead of generating the map, or a Tiled map (.tmj or .tmx) to
+    /// import
+    #[structopt(long)]
+    map: Option<String>,
 }
 
 /// This struct contains the application's state
@@ -62,36 +87,46 @@ pub struct ApplicationState {
     // The event buffer keeps track of events that trigger sounds, so we can separate
     // sound playing from the game logic
     event_buffer: Vec<Event>,
-    // A source of randomness
-    rng: ThreadRng,
+    // The source of randomness of the simulation, seeded so that a run can be played again
+    rng: StdRng,
+    // The sprite batches of the loaded chunks of the map
+    map_cache: MapRenderCache,
 }
 
 impl ApplicationState {
-    /// Simply creates a new application state
-    fn new(ctx: &mut Context, game_size: Size) -> GameResult<ApplicationState> {
-        let mut rng = rand::thread_rng();
+    /// Simply creates a new application state, the simulation drawing from the given seed
+    fn new(ctx: &mut Context, game_size: ScreenSize, map_source: MapSource, seed: u32) -> GameResult<ApplicationState> {
+        let mut rng = StdRng::seed_from_u64(seed as u64);
 
         // Initialize the game resources
         Resources::init(ctx);
+        let resources = Resources::instance();
+        let map_cache = MapRenderCache::new(&resources.atlas_data, &resources.map_assets.frames);
+        let game_state = GameState::new(&mut rng, game_size, map_source, resources.map_assets.clone())
+            .map_err(|error| GameError::ResourceLoadError(error.to_string()))?;
 
         // Return the application state in a game result
         Ok(ApplicationState {
             has_focus: true,
-            resources: Resources::instance(),
-            game_state: GameState::new(&mut rng, game_size),
+            resources,
+            game_state,
             time_controller: TimeController::new(),
             input_controller: InputController::new(),
             event_buffer: vec![],
             rng,
+            map_cache,
         })
     }
 
     /// This will be called when the game needs to be reset
-    fn reset(&mut self) {
+    fn reset(&mut self) -> GameResult<()> {
         // reset the game state
-        self.game_state.reset(&mut self.rng);
+        self.game_state.reset(&mut self.rng).map_err(|error| GameError::ResourceLoadError(error.to_string()))?;
+        // the new world has a new map, drawn with new sprite batches
+        self.map_cache.clear();
         // add a game start event to the event buffer
         self.event_buffer.push(Event::GameStart);
+        Ok(())
     }
 }
 
@@ -105,21 +140,71 @@ impl event::EventHandler for ApplicationState {
             return Ok(());
         }
 
-        // Update game state, and check for collisions
-        let duration = ggez::timer::delta(ctx);
-        self.time_controller.update_seconds(
-            duration,
-            self.input_controller.actions(),
-            &mut self.game_state,
-            &mut self.event_buffer,
-            &mut self.rng,
-        );
+        // Update game state with a fixed time step, so the simulation doesn't depend on the
+        // frame rate, and check for collisions
+        while ggez::timer::check_update_time(ctx, UPDATES_PER_SECOND) {
+            self.time_controller.update_seconds(
+                UPDATE_DURATION,
+                self.input_controller.actions(),
+                &mut self.game_state,
+                &mut self.event_buffer,
+                &mut self.rng,
+            );
 
-        CollisionsController::handle_collisions(&mut self.game_state, &mut self.time_controller, &mut self.event_buffer);
+            CollisionsController::handle_collisions(&mut self.game_state, &mut self.time_controller, &mut self.event_buffer);
+        }
 
         Ok(())
     }
 
+    /// This will be called when the window gains or loses the focus
+    fn focus_event(&mut self, _ctx: &mut Context, gained: bool) {
+        self.has_focus = gained;
+        // Animations freeze with the game, instead of jumping ahead when it resumes
+        if gained {
+            self.time_controller.resume_animations();
+        } else {
+            self.time_controller.pause_animations();
+        }
+    }
+
+    /// This will be called every time a key is pressed
+    fn key_down_event(&mut self, ctx: &mut Context, keycode: KeyCode, keymod: KeyMods, _repeat: bool) {
+        // Keep the default behaviour of quitting on escape
+        if keycode == KeyCode::Escape {
+            event::quit(ctx);
+        }
+        // Any key starts the game while a message is shown, on a new world once it is over
+        if self.game_state.message.is_some() {
+            if self.game_state.is_over() {
+                if let Err(error) = self.reset() {
+                    eprintln!("failed to restart the game: {}", error);
+                    event::quit(ctx);
+                    return;
+                }
+            }
+            self.game_state.start();
+        }
+        // Save the map, to play it again with `--map`
+        if keycode == KeyCode::F5 {
+            match self.game_state.world.save_map(SAVED_MAP_PATH) {
+                Ok(()) => println!("map saved to {}", SAVED_MAP_PATH),
+                Err(error) => println!("failed to save the map: {}", error),
+            }
+        }
+        self.input_controller.key_press(keycode, keymod);
+    }
+
+    /// This will be called every time a key is released
+    fn key_up_event(&mut self, _ctx: &mut Context, keycode: KeyCode, keymod: KeyMods) {
+        self.input_controller.key_release(keycode, keymod);
+    }
+
+    /// This will be called every time the mouse moves over the window
+    fn mouse_motion_event(&mut self, _ctx: &mut Context, x: f32, y: f32, _dx: f32, _dy: f32) {
+        self.input_controller.mouse_motion(x, y);
+    }
+
     // This is called when ggez wants us to draw our game
     fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
         view::play_sounds(ctx, &mut self.event_buffer, self.resources)?;
@@ -132,7 +217,40 @@ fn main() {
     let opt = Opt::from_args();
 
     // Create the game size
-    let game_size = Size::new(opt.width, opt.height);
+    let game_size = ScreenSize::new(Scalar::from_f32(opt.width), Scalar::from_f32(opt.height));
+
+    // Load the map file, or the map generation parameters, the seed and the generator given on
+    // the command line winning over the ones of the config
+    let map_source = match &opt.map {
+        Some(path) if path.ends_with(".tmj") || path.ends_with(".tmx") => MapSource::Tiled(path.clone()),
+        Some(path) => match MapFile::load(path) {
+            Ok(file) => MapSource::File(file),
+            Err(error) => {
+                eprintln!("failed to load the map: {}", error);
+                std::process::exit(1);
+            }
+        },
+        None => {
+            let mut map_config = MapGenConfig::load(&opt.map_config);
+            if opt.seed.is_some() {
+                map_config.seed = opt.seed;
+            }
+            if let Some(generator) = opt.generator {
+                map_config.generator = generator;
+            }
+            MapSource::Generated(map_config)
+        }
+    };
+
+    // The simulation is seeded with the seed given on the command line, or the one of the map,
+    // so that a run with the same seed and inputs plays the same way
+    let map_seed = match &map_source {
+        MapSource::Generated(config) => config.seed,
+        MapSource::File(file) => file.header.seed,
+        MapSource::Tiled(_) => None,
+    };
+    let seed = opt.seed.or(map_seed).unwrap_or_else(|| rand::thread_rng().gen());
+    println!("simulation seed: {}", seed);
 
     // Define the resource directory
     let resource_dir = std::path::PathBuf::from("../resources");
@@ -140,8 +258,14 @@ fn main() {
     // Build the window
     let (mut ctx, event_loop) = init_rendering_ctx(game_size, resource_dir).unwrap();
 
-    // Load the application state and start the event loop
-    let mut state = ApplicationState::new(&mut ctx, game_size).unwrap();
+    // Load the application state and start the event loop, the map being checked on the way
+    let mut state = match ApplicationState::new(&mut ctx, game_size, map_source, seed) {
+        Ok(state) => state,
+        Err(error) => {
+            eprintln!("failed to start the game: {}", error);
+            std::process::exit(1);
+        }
+    };
 
     // Run the event loop
     event::run(ctx, event_loop, state);
@@ -12,7 +12,8 @@ use ron::de::from_reader;
 use serde::Deserialize;
 use strum_macros::Display;
 
-use geometry::Size;
+use geometry::{Real, Scalar, ScreenRect, Size};
+use tilemap::{AtlasContent, Faction};
 
 use crate::view::SCALE;
 
@@ -24,6 +25,18 @@ pub enum Type {
     TowersRed,
 }
 
+impl From<AtlasContent> for Type {
+    /// Returns the atlas holding the frames of the given content
+    fn from(content: AtlasContent) -> Type {
+        match content {
+            AtlasContent::Landscape => Type::Landscape,
+            AtlasContent::Towers(Faction::Brown) => Type::TowersBrown,
+            AtlasContent::Towers(Faction::Grey) => Type::TowersGrey,
+            AtlasContent::Towers(Faction::Red) => Type::TowersRed,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct Frame {
     pub name: String,
@@ -87,6 +100,16 @@ impl Atlas {
         }
     }
 
+    /// Returns the number of frames in the atlas.
+    pub fn frames_len(&self) -> usize {
+        self.meta.frames.len()
+    }
+
+    /// Returns the name of the frame for the given `SpriteRef`.
+    pub fn frame_name(&self, sprite_ref: SpriteRef) -> Option<&str> {
+        self.get_frame(sprite_ref).map(|frame| frame.name.as_str())
+    }
+
     /// Returns the frame for the given `SpriteRef`.
     fn get_frame(&self, value: SpriteRef) -> Option<&Frame> {
         match value {
@@ -148,7 +171,7 @@ pub struct Sprite {
 impl Sprite {
     /// Creates a new `Sprite`.
     pub fn new(rect: Rect, width: f32, height: f32) -> Self {
-        Sprite { rect, width, height, scale: Point2 { x: SCALE, y: SCALE } }
+        Sprite { rect, width, height, scale: Point2 { x: SCALE.to_f32(), y: SCALE.to_f32() } }
     }
 
     /// Get draw params for the `Sprite` at the given position.
@@ -159,10 +182,9 @@ impl Sprite {
             .dest(pos)
     }
 
-    /// Returns the bounding box for this sprite.
-    pub fn get_bound_box(&self) -> Rect {
-        let mut rect = self.rect.clone();
-        rect.scale(self.scale.x, self.scale.y);
-        rect
+    /// Returns the bounding box for this sprite, relative to its draw position.
+    pub fn get_bound_box(&self) -> ScreenRect {
+        let (width, height) = (self.width * self.scale.x, self.height * self.scale.y);
+        ScreenRect::new(Scalar::ZERO, Scalar::ZERO, Scalar::from_f32(width), Scalar::from_f32(height))
     }
 }
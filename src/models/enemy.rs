@@ -1,27 +1,62 @@
 use rand::Rng;
-use geometry::{Size, Vector, Position, Point, Collide};
-use geometry_derive::Position;
+use geometry::{scalar, Scalar, Size, Vector, Position, WorldPoint, Collide, steering};
+use geometry_derive::{Collide, Position};
 use crate::models::{Powerup, PowerupKind};
 
+/// Enemies closer than this distance push each other away
+const SEPARATION_RADIUS: Scalar = scalar!(24.0);
+/// How much keeping apart matters compared to chasing the target
+const SEPARATION_WEIGHT: Scalar = scalar!(1.5);
+/// How far ahead of the enemy, and how wide, the wandering circle is
+const WANDER_DISTANCE: Scalar = scalar!(40.0);
+const WANDER_RADIUS: Scalar = scalar!(20.0);
+/// The maximum change of direction while wandering, in radians
+const WANDER_JITTER: Scalar = scalar!(0.5);
+
 /// The `Enemy` is the hero controlled by the user
-#[derive(Default, Position)]
+#[derive(Default, Position, Collide)]
+#[collide(radius = 6.0)]
 pub struct Enemy {
     pub vector: Vector,
 }
 
 impl Enemy {
     /// Create a new `Enemy` at the given position
-    pub fn new(position: Point) -> Enemy {
+    pub fn new(position: WorldPoint) -> Enemy {
         Enemy {
-            vector: Vector::new(position, 1.0),
+            vector: Vector::new(position, scalar!(1.0)),
         }
     }
-}
 
+    /// Chases the target at up to `max_speed`, while keeping away from the neighbouring enemies
+    pub fn update(&mut self, dt: Scalar, max_speed: Scalar, target: &Vector, neighbours: &[WorldPoint]) {
+        let chase = steering::pursuit(&self.vector, target, max_speed);
+        let separation = steering::separation(&self.vector, neighbours, SEPARATION_RADIUS, max_speed);
+        self.vector.apply_force(chase + separation * SEPARATION_WEIGHT);
+        self.vector.advance_limited(dt, max_speed);
+    }
 
+    /// Walks to the given waypoint at up to `max_speed`, while keeping away from the neighbouring
+    /// enemies
+    pub fn follow(&mut self, dt: Scalar, max_speed: Scalar, waypoint: WorldPoint, neighbours: &[WorldPoint]) {
+        let seek = steering::seek(&self.vector, waypoint, max_speed);
+        let separation = steering::separation(&self.vector, neighbours, SEPARATION_RADIUS, max_speed);
+        self.vector.apply_force(seek + separation * SEPARATION_WEIGHT);
+        self.vector.advance_limited(dt, max_speed);
+    }
 
-impl Collide for Enemy {
-    fn radius(&self) -> f32 {
-        6.0
+    /// Roams around randomly at up to `max_speed`, while keeping away from the neighbouring enemies
+    pub fn wander<R: Rng>(&mut self, dt: Scalar, max_speed: Scalar, rng: &mut R, neighbours: &[WorldPoint]) {
+        let wander = steering::wander(&self.vector, rng, max_speed, WANDER_DISTANCE, WANDER_RADIUS, WANDER_JITTER);
+        let separation = steering::separation(&self.vector, neighbours, SEPARATION_RADIUS, max_speed);
+        self.vector.apply_force(wander + separation * SEPARATION_WEIGHT);
+        self.vector.advance_limited(dt, max_speed);
+    }
+
+    /// Drifts in the direction the enemy is facing
+    pub fn advance(&mut self, dt: Scalar, speed: Scalar) {
+        self.vector.velocity = self.vector.heading() * speed;
+        self.vector.advance(dt);
     }
 }
+
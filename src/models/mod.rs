@@ -1,7 +1,6 @@
 mod world;
 mod player;
 mod enemy;
-mod map;
 pub mod atlas;
 mod media;
 mod powerup;
@@ -9,7 +8,9 @@ mod powerup;
 pub use self::world::World;
 pub use self::player::Player;
 pub use self::enemy::Enemy;
-pub use self::map::{Map, Tile, Layer};
+pub use tilemap::{
+    Chunk, GeneratorKind, Layer, Map, MapAssets, MapFile, MapFileError, MapGenConfig, MapObject, MapSource, ObjectKind, Pathfinder,
+};
 pub use self::atlas::{Atlas, AtlasData, Sprite};
 pub use self::media::{Jukebox, Images};
 pub use self::powerup::{Powerup, PowerupKind};
@@ -1,10 +1,11 @@
 use rand::Rng;
-use geometry::{Size, Vector, Position, Point, Collide};
-use geometry_derive::Position;
+use geometry::{scalar, Size, Vector, Position, WorldPoint, Collide};
+use geometry_derive::{Collide, Position};
 use crate::models::{Powerup, PowerupKind};
 
 /// The `Player` is the hero controlled by the user
-#[derive(Default, Position)]
+#[derive(Default, Position, Collide)]
+#[collide(radius = 6.0)]
 pub struct Player {
     pub is_dead: bool,
     pub vector: Vector,
@@ -13,19 +14,12 @@ pub struct Player {
 
 impl Player {
     /// Create a new `Player` at the given position
-    pub fn new(position: Point) -> Player {
+    pub fn new(position: WorldPoint) -> Player {
         Player {
             is_dead: true,
-            vector: Vector::new(position, 1.0),
+            vector: Vector::new(position, scalar!(1.0)),
             powerup: None,
         }
     }
 }
 
-
-
-impl Collide for Player {
-    fn radius(&self) -> f32 {
-        6.0
-    }
-}
@@ -1,8 +1,16 @@
+use std::time::Duration;
+
 use rand::{Rng, seq::SliceRandom};
-use geometry::{Collide, Point, Position, Size};
+use geometry::{scalar, Collide, Point, Position, Scalar, WorldPoint};
+use geometry_derive::{Collide, Position};
+
+use crate::controllers::tween::{Animation, Easing, Repeat, Tween};
+use crate::models::World;
 
-const POWERUP_TTL: f32 = 10.0;
-const POWERUP_SIZE: f32 = 20.0;
+const POWERUP_TTL: Scalar = scalar!(10.0);
+const POWERUP_SIZE: Scalar = scalar!(20.0);
+/// The duration of a pulse, from the smallest to the biggest size
+const POWERUP_PULSE: Duration = Duration::from_millis(600);
 
 /// This is an enum of the different powerup types
 #[derive(PartialEq, Clone, Copy)]
@@ -30,57 +38,39 @@ impl Default for PowerupKind {
 }
 
 /// This is the struct containing information about a powerup
+#[derive(Position, Collide)]
+#[position(field = "position")]
+#[collide(radius = Self::shrinking_radius)]
 pub struct Powerup {
-    pub ttl: f32,
+    pub ttl: Scalar,
     pub kind: PowerupKind,
-    pub color: u8,
-    pub position: Point,
+    /// The scale of the powerup, going back and forth to make it stand out
+    pub pulse: Tween<f32>,
+    pub position: WorldPoint,
 }
 
 impl Powerup {
-    pub fn new(kind: PowerupKind, position: Point) -> Powerup {
+    pub fn new(kind: PowerupKind, position: WorldPoint) -> Powerup {
         Powerup {
             ttl: POWERUP_TTL,
             kind,
-            color: 0,
+            pulse: Tween::new(0.8, 1.2, POWERUP_PULSE, Easing::SineInOut).repeat(Repeat::PingPong),
             position,
         }
     }
 
-    pub fn random<R: Rng>(rng: &mut R, bounds: Size) -> Powerup {
-        Powerup::new(PowerupKind::random(rng), Point::random(rng, bounds))
-    }
-
-    pub fn update(&mut self, elapsed_time: f32) {
-        self.ttl -= elapsed_time;
-        self.color = if self.color < u8::MAX {
-            self.color + 1
-        } else {
-            0
-        }
+    pub fn random<R: Rng>(rng: &mut R, world: &World) -> Powerup {
+        Powerup::new(PowerupKind::random(rng), world.random_point(rng))
     }
-}
 
-impl Position for Powerup {
-    fn x(&self) -> f32 {
-        self.position.x
-    }
-    fn y(&self) -> f32 {
-        self.position.y
-    }
-    fn x_mut(&mut self) -> &mut f32 {
-        &mut self.position.x
-    }
-    fn y_mut(&mut self) -> &mut f32 {
-        &mut self.position.y
-    }
-    fn position(&self) -> Point {
-        Point::new(self.x(), self.y())
+    /// The powerup shrinks as it runs out of time
+    fn shrinking_radius(&self) -> Scalar {
+        POWERUP_SIZE * (self.ttl / POWERUP_TTL)
     }
-}
 
-impl Collide for Powerup {
-    fn radius(&self) -> f32 {
-        POWERUP_SIZE * (self.ttl / POWERUP_TTL)
+    /// Runs the powerup out of time and advances its pulse by the animation time
+    pub fn update(&mut self, elapsed_time: Scalar, animation_time: Duration) {
+        self.ttl -= elapsed_time;
+        self.pulse.advance(animation_time);
     }
 }
@@ -1,13 +1,22 @@
+use std::io;
+use std::sync::Arc;
+
 use ggez::mint::Point2;
-use rand::Rng;
+use rand::{Rng, seq::SliceRandom};
 
-use geometry::{Point, Size};
+use geometry::{scalar, Collide, IsoProjection, Neighbourhood, Position, Real, Scalar, ScreenPoint, ScreenSize, SpatialGrid, WorldPoint};
 
 use crate::{
     models::{
         atlas::AtlasData,
         Enemy,
         Map,
+        MapAssets,
+        MapFileError,
+        MapObject,
+        MapSource,
+        ObjectKind,
+        Pathfinder,
         Player,
     },
     view::{
@@ -17,41 +26,122 @@ use crate::{
 };
 use crate::models::Powerup;
 
+/// The size of the cells of the enemies spatial index, in world pixels
+const ENEMY_GRID_CELL_SIZE: Scalar = scalar!(64.0);
+/// The number of positions tried to find a walkable one
+const RANDOM_POINT_TRIES: usize = 32;
+
 /// A model that contains the other models and renders them
 pub struct World {
     pub player: Player,
     pub enemies: Vec<Enemy>,
+    /// Spatial index of the enemies, keyed by their index in `enemies`
+    pub enemy_grid: SpatialGrid<usize>,
     pub powerups: Vec<Powerup>,
     pub map: Map,
-    pub size: Size,
+    /// Paths of the enemies over the map
+    pub pathfinder: Pathfinder,
+    pub size: ScreenSize,
 }
 
 impl World {
-    /// Returns a new world of the given size
-    pub fn new<R: Rng>(rng: &mut R, size: Size) -> World {
-        let map_size = (size.height / TILE_SIZE.height) as usize;
-        let map = Map::new(rng, map_size);
-        println!("map_size: {}", map_size);
-        World {
-            player: Player::new(map.get_random_point(rng)),
+    /// Returns a new world of the given size, with a map from the given source made of the given
+    /// assets, or the reason why the map can't be played
+    pub fn new<R: Rng>(rng: &mut R, size: ScreenSize, map_source: &MapSource, assets: &Arc<MapAssets>) -> Result<World, MapFileError> {
+        let map = Map::from_source(map_source, assets.clone(), IsoProjection::new(TILE_SIZE, SCALE), rng)?;
+        if let MapSource::Generated(config) = map_source {
+            println!("map seed: {} ({} generator)", map.seed, config.generator);
+        }
+        // The player starts at the spawn point of the map, if it has one
+        let start = map.spawn_points(ObjectKind::PlayerSpawn).next();
+        Ok(World {
+            player: Player::new(start.unwrap_or_else(|| map.get_random_point(rng))),
             enemies: vec![],
+            enemy_grid: SpatialGrid::new(ENEMY_GRID_CELL_SIZE),
             powerups: vec![],
+            pathfinder: Pathfinder::new(&map, &assets.tiles, Neighbourhood::Eight),
             map,
             size,
-        }
+        })
     }
 
-    /// Returns the map position
-    pub fn map_position(&self) -> Point {
+    /// Returns the map position, on screen, the camera following the player
+    pub fn map_position(&self) -> ScreenPoint {
         // Calculate the center of the screen
-        let mut x = self.size.width / 2.0;
-        let mut y = self.size.height / 2.0;
+        let mut x = self.size.width / scalar!(2.0);
+        let mut y = self.size.height / scalar!(2.0);
 
-        // Remove the first tile size to center the map
-        // x -= TILE_SIZE.scale(SCALE).width / 2.0;
-        // y -= TILE_SIZE.scale(SCALE).height / 2.0;
+        // Move the map so that the player stands at the center of the screen
+        let player = self.map.projection.world_to_screen(self.player.position(), ScreenPoint::default());
+        x -= player.x;
+        y -= player.y;
 
         // Return the position
-        Point::new(x, y)
+        ScreenPoint::new(x, y)
+    }
+
+    /// Loads the chunks of the map around the player and drops the far away ones, with the
+    /// enemies and powerups left on them
+    pub fn stream_map(&mut self) {
+        if self.map.stream(self.player.position()) {
+            let area = &self.map.playable_area;
+            self.enemies.retain(|enemy| area.contains(enemy.position()));
+            self.powerups.retain(|powerup| area.contains(powerup.position()));
+            self.index_enemies();
+        }
+    }
+
+    /// Updates the spatial index of the enemies with their current positions, only moving the
+    /// enemies which changed cells
+    ///
+    /// Must be called whenever enemies move, spawn or get removed
+    pub fn index_enemies(&mut self) {
+        let count = self.enemies.len();
+        self.enemy_grid.retain(|&index| index < count);
+        for (index, enemy) in self.enemies.iter().enumerate() {
+            self.enemy_grid.insert(index, enemy.position(), enemy.shape().bounding_radius());
+        }
+    }
+
+    /// Returns a random world position on a walkable tile of the map, or anywhere on the map if
+    /// none was found after a few tries
+    pub fn random_point<R: Rng>(&self, rng: &mut R) -> WorldPoint {
+        let mut point = self.map.playable_area.random_point(rng);
+        for _ in 0..RANDOM_POINT_TRIES {
+            if self.map.is_walkable_at(point) {
+                break;
+            }
+            point = self.map.playable_area.random_point(rng);
+        }
+        point
+    }
+
+    /// Returns a random spawn point of the enemies on the map, or a random walkable position if
+    /// the map has none
+    pub fn enemy_spawn_point<R: Rng>(&self, rng: &mut R) -> WorldPoint {
+        let spawn_points: Vec<WorldPoint> = self.map.spawn_points(ObjectKind::EnemySpawn).collect();
+        match spawn_points.choose(rng) {
+            Some(&point) => point,
+            None => self.random_point(rng),
+        }
+    }
+
+    /// Saves the map to the given file, with the position of the player as its spawn point
+    pub fn save_map(&self, path: &str) -> io::Result<()> {
+        let mut file = self.map.to_file();
+        file.objects.retain(|object| object.kind != ObjectKind::PlayerSpawn);
+        let position = self.map.projection.world_to_tile(self.player.position());
+        file.objects.push(MapObject::new(ObjectKind::PlayerSpawn, position));
+        file.save(path)
+    }
+
+    /// Converts a world position to a screen position
+    pub fn to_screen(&self, position: WorldPoint) -> ScreenPoint {
+        self.map.projection.world_to_screen(position, self.map_position())
+    }
+
+    /// Returns the index of the map tile under the given screen position, if any
+    pub fn pick_tile(&self, screen: ScreenPoint) -> Option<(i32, i32)> {
+        self.map.tile_index(self.map.projection.screen_to_world(screen, self.map_position()))
     }
 }
@@ -0,0 +1,148 @@
+use std::collections::{HashMap, HashSet};
+
+use ggez::{Context, GameResult};
+use ggez::graphics::{Drawable, DrawParam};
+use ggez::graphics::spritebatch::SpriteBatch;
+
+use geometry::{scalar, IsoProjection, Point, Real, Scalar, ScreenPoint, TilePoint};
+use tilemap::{AtlasContent, FrameTable};
+
+use crate::{
+    models::{
+        atlas::SpriteRef,
+        AtlasData,
+        Chunk,
+        Layer,
+        Map,
+        Sprite,
+    },
+    view::TILE_SIZE,
+};
+
+/// How much higher the top of the ground sprites is drawn than the tile diamond, in world pixels
+const GROUND_THICKNESS: Scalar = scalar!(35.0);
+
+/// The sprite batch of a layer of a chunk
+struct LayerBatch {
+    level: i32,
+    batch: SpriteBatch,
+}
+
+/// The `MapRenderCache` keeps a sprite batch for each layer of the loaded chunks of the map,
+/// built from the tiles of the layers when the chunk is loaded
+///
+/// The map only holds frame IDs, so the same map data can be generated and used without drawing
+/// it.
+pub struct MapRenderCache {
+    chunks: HashMap<(i32, i32), Vec<LayerBatch>>,
+    /// The sprite of each frame of the frame table, by frame ID
+    sprites: Vec<Option<Sprite>>,
+}
+
+impl MapRenderCache {
+    /// Creates an empty cache, with the sprite of every frame of the frame table
+    pub fn new(atlas_data: &AtlasData, frames: &FrameTable) -> MapRenderCache {
+        let mut sprites = vec![None];
+        for (_, frame) in frames.iter() {
+            let atlas = atlas_data.get(frame.atlas.into()).unwrap();
+            sprites.push(atlas.create_sprite(SpriteRef::Name(&frame.name)));
+        }
+        MapRenderCache { chunks: HashMap::new(), sprites }
+    }
+
+    /// Builds the batches of the chunks loaded since the last update and drops the ones of the
+    /// chunks the map dropped
+    pub fn update(&mut self, map: &Map, atlas_data: &AtlasData) {
+        let chunks = map.chunks();
+        let loaded: HashSet<(i32, i32)> = chunks.iter().map(|chunk| chunk.index).collect();
+        self.chunks.retain(|index, _| loaded.contains(index));
+        for chunk in chunks {
+            let sprites = &self.sprites;
+            self.chunks.entry(chunk.index).or_insert_with(|| {
+                chunk
+                    .layers
+                    .iter()
+                    .map(|layer| LayerBatch {
+                        level: layer.level,
+                        batch: build_batch(chunk.index, layer, atlas_data, sprites, &map.projection),
+                    })
+                    .collect()
+            });
+        }
+    }
+
+    /// Drops every batch, when the map is replaced by a new one
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+    }
+
+    /// Draws the loaded chunks of the map at the given position, the ground of every chunk first,
+    /// then what stands on it
+    pub fn draw(&self, ctx: &mut Context, map: &Map, position: ScreenPoint) -> GameResult<()> {
+        let layers = map.chunks().into_iter().filter_map(|chunk| self.chunks.get(&chunk.index)).flatten();
+        let (ground, standing): (Vec<&LayerBatch>, Vec<&LayerBatch>) = layers.partition(|layer| layer.level == 0);
+
+        for layer in ground.into_iter().chain(standing) {
+            layer.batch.draw(ctx, DrawParam::new().dest(position.point2()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Builds the sprite batch of a layer of the chunk at the given index, with the sprite of each
+/// of its tiles
+fn build_batch(
+    index: (i32, i32),
+    layer: &Layer,
+    atlas_data: &AtlasData,
+    sprites: &[Option<Sprite>],
+    projection: &IsoProjection,
+) -> SpriteBatch {
+    let atlas = atlas_data.get(layer.atlas.into()).unwrap();
+    let mut batch = atlas.create_sprite_batch();
+    let (left, top) = Chunk::origin_of(index);
+    for ((x, y), id) in layer.ids() {
+        let sprite = sprites[id as usize].as_ref().unwrap();
+        let tile = TilePoint::new(Scalar::from_f32((left + x as i32) as f32), Scalar::from_f32((top + y as i32) as f32));
+        let position = match layer.atlas {
+            AtlasContent::Landscape => screen_position(tile, sprite, projection),
+            AtlasContent::Towers(_) => standing_position(tile, sprite, projection),
+        };
+        batch.add(sprite.draw_params(position.point2()));
+    }
+    batch
+}
+
+/// Calculate the position of the sprite of a tile, relative to the map position on screen
+fn screen_position(tile: TilePoint, sprite: &Sprite, projection: &IsoProjection) -> ScreenPoint {
+    // Start from the top corner of the tile diamond
+    let mut position = projection.tile_to_world(tile);
+
+    // Move to the top left corner of the sprite
+    position.x -= TILE_SIZE.width / scalar!(2.0);
+
+    // Subtracting the difference between the tile size and the sprite size
+    // to center the sprite in the tile
+    position.x -= Scalar::from_f32(sprite.width) - TILE_SIZE.width;
+    position.y -= Scalar::from_f32(sprite.height) - TILE_SIZE.height;
+
+    // The top of the ground sprites sits half a tile below the tile diamond
+    position.y += TILE_SIZE.height / scalar!(2.0);
+
+    projection.world_to_screen(position, ScreenPoint::default())
+}
+
+/// Calculate the position of a sprite standing on a tile (like a tower), relative to the map
+/// position on screen
+fn standing_position(tile: TilePoint, sprite: &Sprite, projection: &IsoProjection) -> ScreenPoint {
+    // Start from the center of the tile diamond, on top of the ground sprite, which is drawn half
+    // a tile lower
+    let mut position = projection.tile_to_world(tile + Point::new(scalar!(0.5), scalar!(0.5)));
+    position.y -= GROUND_THICKNESS - TILE_SIZE.height / scalar!(2.0);
+
+    // Put the bottom of the sprite a bit below the center, where its base touches the ground
+    position.x -= Scalar::from_f32(sprite.width) / scalar!(2.0);
+    position.y -= Scalar::from_f32(sprite.height) - TILE_SIZE.height / scalar!(4.0);
+
+    projection.world_to_screen(position, ScreenPoint::default())
+}
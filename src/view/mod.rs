@@ -2,7 +2,9 @@ mod resources;
 mod sound;
 mod render;
 mod colors;
+mod map_cache;
 
 pub use self::render::{init_rendering_ctx, render_game, TILE_SIZE, SCALE};
+pub use self::map_cache::MapRenderCache;
 pub use self::resources::Resources;
 pub use self::sound::play_sounds;
@@ -9,27 +9,29 @@ use ggez::input::mouse::position;
 use ggez::mint::Point2;
 use rand::seq::index::sample;
 
-use geometry::{Collide, Point, Position, Size};
+use geometry::{scalar, Collide, Point, Position, Real, Scalar, ScreenPoint, ScreenRect, ScreenSize, Size, TilePoint, WorldSize};
 
 use crate::{
     ApplicationState,
+    controllers::tween::Animation,
     game_state::Message,
-    models::{Layer, Map, Player, Tile, World},
+    models::{Map, Player, PowerupKind, World},
     view::{
         colors,
+        MapRenderCache,
         Resources,
     },
 };
 
-pub const TILE_SIZE: Size = Size { width: 128.0, height: 64.0 };
-pub const SCALE: f32 = 0.6;
+pub const TILE_SIZE: WorldSize = WorldSize::new(scalar!(128.0), scalar!(64.0));
+pub const SCALE: Scalar = scalar!(0.6);
 
-pub fn init_rendering_ctx(game_size: Size, resource_dir: PathBuf) -> GameResult<(Context, EventLoop<()>)> {
+pub fn init_rendering_ctx(game_size: ScreenSize, resource_dir: PathBuf) -> GameResult<(Context, EventLoop<()>)> {
     let cb = ContextBuilder::new("Rust RPG", "GuillaumeMCK")
         .add_resource_path(resource_dir)
         .window_setup(conf::WindowSetup::default().title("Rust RPG"))
         .window_mode(conf::WindowMode::default()
-            .dimensions(game_size.width as f32, game_size.height as f32)
+            .dimensions(game_size.width.to_f32(), game_size.height.to_f32())
             .resizable(true));
     let ctx = cb.build()?;
     Ok(ctx)
@@ -40,8 +42,12 @@ pub fn render_game(ctx: &mut Context, app: &mut ApplicationState) -> GameResult<
     // Clear everything
     graphics::clear(ctx, colors::BACKGROUND);
 
-    // Render the world
-    render_world(ctx, &app.game_state.world, app.resources)?;
+    // Render the world, building the sprites of the newly loaded chunks first
+    app.map_cache.update(&app.game_state.world.map, &app.resources.atlas_data);
+    render_world(ctx, &app.game_state.world, &app.map_cache, app.resources)?;
+
+    // Outline the tile under the mouse cursor
+    render_hovered_tile(ctx, &app.game_state.world, app.input_controller.cursor())?;
 
     // Render a message if there is one set
     render_message(ctx, app)?;
@@ -49,7 +55,7 @@ pub fn render_game(ctx: &mut Context, app: &mut ApplicationState) -> GameResult<
     // Render the score
     let fragment = TextFragment::new(format!("Score: {}", 00)).scale(graphics::PxScale::from(24.0));
     let text = graphics::Text::new(fragment);
-    let pt = Point::new(8.0, 8.0);
+    let pt = ScreenPoint::new(scalar!(8.0), scalar!(8.0));
     graphics::draw(ctx, &text, DrawParam::new().dest(pt.point2()).color(colors::WHITE))?;
 
     // println!("{}", ggez::timer::fps(ctx));
@@ -62,10 +68,8 @@ pub fn render_game(ctx: &mut Context, app: &mut ApplicationState) -> GameResult<
 fn render_message(ctx: &mut Context, app: &mut ApplicationState) -> GameResult<()> {
     if let Some(ref message) = app.game_state.message {
         let Message { title, subtitle } = *message;
-        let Size { width, height } = app.game_state.world.size;
-
-        let w = width / 2.0;
-        let h = height / 2.0;
+        let size = app.game_state.world.size;
+        let ScreenPoint { x: w, y: h, .. } = ScreenRect::new(Scalar::ZERO, Scalar::ZERO, size.width, size.height).center();
 
         let mut draw_text = |text: &str, color: Color, font_size: f32, is_title: bool| {
             let fragment = TextFragment::new(text)
@@ -73,11 +77,11 @@ fn render_message(ctx: &mut Context, app: &mut ApplicationState) -> GameResult<(
                 .color(color);
             let drawable = graphics::Text::new(fragment);
 
-            let x = w - drawable.width(ctx) as f32 / 2.0;
-            let y = if is_title { h - drawable.height(ctx) as f32 } else { h };
+            let x = w - Scalar::from_f32(drawable.width(ctx) / 2.0);
+            let y = if is_title { h - Scalar::from_f32(drawable.height(ctx)) } else { h };
 
             graphics::draw(ctx, &drawable, DrawParam::new()
-                .dest(Point::new(x, y).point2()))
+                .dest(ScreenPoint::new(x, y).point2()))
         };
 
         draw_text(title, colors::GREY, 32.0, true)?;
@@ -88,12 +92,14 @@ fn render_message(ctx: &mut Context, app: &mut ApplicationState) -> GameResult<(
 }
 
 /// Renders the world and everything in it
-pub fn render_world(ctx: &mut Context, world: &World, resources: &Resources) -> GameResult<()> {
-    render_map(ctx, &world.map, resources, world.map_position())?;
+pub fn render_world(ctx: &mut Context, world: &World, map_cache: &MapRenderCache, resources: &Resources) -> GameResult<()> {
+    render_map(ctx, &world.map, map_cache, world.map_position())?;
 
     // render_enemy(ctx, world, resources)?;
 
-    render_player(ctx, &world.player, resources, world.map_position())?;
+    render_powerups(ctx, world)?;
+
+    render_player(ctx, &world.player, resources, world.to_screen(world.player.position()))?;
 
     render_debug(ctx, world)?;
     // Finally draw the player as red
@@ -104,40 +110,50 @@ pub fn render_world(ctx: &mut Context, world: &World, resources: &Resources) ->
     Ok(())
 }
 
-/// Renders the map
-pub fn render_map(ctx: &mut Context, map: &Map, resources: &Resources, pos: Point) -> GameResult<()> {
-    let layers: &Vec<Layer> = &map.layers();
-
-    for layer in layers {
-        layer.sprite_batch.draw(ctx, DrawParam::new().dest(pos.point2()))?;
-    }
-
-    Ok(())
+/// Renders the map, the ground of every chunk first, then what stands on it
+pub fn render_map(ctx: &mut Context, map: &Map, map_cache: &MapRenderCache, pos: ScreenPoint) -> GameResult<()> {
+    map_cache.draw(ctx, map, pos)
 }
 
 /// Renders the player
-pub fn render_player(ctx: &mut Context, player: &Player, resources: &Resources, pos: Point) -> GameResult<()> {
+pub fn render_player(ctx: &mut Context, player: &Player, resources: &Resources, pos: ScreenPoint) -> GameResult<()> {
     let image = &resources.images.hero;
-    let scale = player.radius() * 2.0 / resources.images.hero.width() as f32;
     graphics::draw(
         ctx,
         image,
         DrawParam::new()
-            .dest((pos + player.position()).point2())
-            .scale(Point2::<f32>::from([SCALE, SCALE]))
+            .dest(pos.point2())
+            .scale(Point2::<f32>::from([SCALE.to_f32(), SCALE.to_f32()]))
     )
 }
 
+/// Renders the powerups as discs pulsing in and out, shrinking as they run out of time
+pub fn render_powerups(ctx: &mut Context, world: &World) -> GameResult<()> {
+    for powerup in &world.powerups {
+        let radius = powerup.radius().to_f32() * powerup.pulse.value() * SCALE.to_f32();
+        if radius <= 0.0 {
+            continue;
+        }
+        let color = match powerup.kind {
+            PowerupKind::Shield => colors::BLUE,
+            PowerupKind::TimeSlow => colors::GREEN,
+        };
+        let mesh = Mesh::new_circle(ctx, DrawMode::fill(), world.to_screen(powerup.position()).point2(), radius, 0.5, color)?;
+        mesh.draw(ctx, DrawParam::new())?;
+    }
+    Ok(())
+}
+
 /// Debug grid to show to middle of the screen
 pub fn render_debug(ctx: &mut Context, world: &World) -> GameResult<()> {
-    let Size { width, height } = world.size;
-    let w = width / 2.0;
-    let h = height / 2.0;
+    let Size { width, height, .. } = world.size;
+    let w = width / scalar!(2.0);
+    let h = height / scalar!(2.0);
 
     // draw horizontal line
     let mut mesh = Mesh::new_line(
         ctx,
-        &[Point::new(0.0, h).point2(), Point::new(width, h).point2()],
+        &[ScreenPoint::new(Scalar::ZERO, h).point2(), ScreenPoint::new(width, h).point2()],
         1.0,
         colors::GREEN,
     )?;
@@ -146,7 +162,7 @@ pub fn render_debug(ctx: &mut Context, world: &World) -> GameResult<()> {
     // draw vertical line
     mesh = Mesh::new_line(
         ctx,
-        &[Point::new(w, 0.0).point2(), Point::new(w, height).point2()],
+        &[ScreenPoint::new(w, Scalar::ZERO).point2(), ScreenPoint::new(w, height).point2()],
         1.0,
         colors::RED,
     )?;
@@ -154,14 +170,32 @@ pub fn render_debug(ctx: &mut Context, world: &World) -> GameResult<()> {
 
     // draw the playable area as a polygon
     let mut points = Vec::new();
-    for point in world.map.playable_area {
-        points.push(point.point2());
+    for &point in world.map.playable_area.vertices() {
+        points.push(world.to_screen(point).point2());
     }
 
     let mut mesh = Mesh::new_polygon(ctx, DrawMode::stroke(1.0), &points, colors::BLUE)?;
+    mesh.draw(ctx, DrawParam::new())?;
 
-    // draw at the position of the first point
-    mesh.draw(ctx, DrawParam::new().dest(world.map_position().point2()))?;
+    Ok(())
+}
+
+/// Outlines the map tile under the given screen position
+pub fn render_hovered_tile(ctx: &mut Context, world: &World, cursor: ScreenPoint) -> GameResult<()> {
+    if let Some((column, row)) = world.pick_tile(cursor) {
+        let (column, row) = (Scalar::from_f32(column as f32), Scalar::from_f32(row as f32));
+        let points: Vec<[f32; 2]> = [(0, 0), (1, 0), (1, 1), (0, 1)]
+            .iter()
+            .map(|&(dx, dy)| {
+                let (dx, dy) = (Scalar::from_f32(dx as f32), Scalar::from_f32(dy as f32));
+                let tile = world.map.projection.tile_to_world(TilePoint::new(column + dx, row + dy));
+                world.to_screen(tile).point2()
+            })
+            .collect();
+
+        let mesh = Mesh::new_polygon(ctx, DrawMode::stroke(1.0), &points, colors::WHITE)?;
+        mesh.draw(ctx, DrawParam::new())?;
+    }
 
     Ok(())
 }
@@ -1,4 +1,5 @@
 use std::fs::File;
+use std::sync::Arc;
 
 use ggez::{
     audio::{self, SoundSource},
@@ -10,7 +11,7 @@ use structopt::lazy_static::lazy_static;
 
 use geometry::Size;
 
-use crate::models::{AtlasData, Images, Jukebox};
+use crate::models::{AtlasData, Images, Jukebox, MapAssets};
 
 #[derive(Debug, Deserialize)]
 pub struct Config {
@@ -21,6 +22,8 @@ pub struct Config {
 pub struct Resources {
     pub images: Images,
     pub atlas_data: AtlasData,
+    /// What the maps are made of, shared with the maps generating their chunks
+    pub map_assets: Arc<MapAssets>,
     pub jukebox: Jukebox,
 }
 
@@ -48,12 +51,14 @@ impl Resources {
         };
 
         let atlas_data = AtlasData::parse_file(ctx, "resources/atlas.ron");
+        let map_assets = Arc::new(MapAssets::load("resources"));
 
         println!("Resources loaded.");
         unsafe {
             RESOURCES = Some(Resources {
                 images,
                 atlas_data,
+                map_assets,
                 jukebox,
             });
         }